@@ -1,17 +1,34 @@
 use std::path::PathBuf;
 
+use miette::{Diagnostic, Severity};
 use polarity_lang_driver::Database;
 
+use crate::global_settings::GlobalSettings;
+
 #[derive(clap::Args)]
 pub struct Args {
     #[clap(value_parser, value_name = "FILE")]
     filepath: PathBuf,
+    /// Also report hints, such as top-level definitions which are never used
+    #[clap(long)]
+    hints: bool,
 }
 
-pub async fn exec(cmd: Args) -> Result<(), Vec<miette::Report>> {
+pub async fn exec(cmd: Args, settings: &GlobalSettings) -> Result<(), Vec<miette::Report>> {
     let mut db = Database::from_path(&cmd.filepath);
     let uri = db.resolve_path(&cmd.filepath).map_err(|e| vec![e.into()])?;
     let _ = db.ast(&uri).await.map_err(|errs| db.pretty_errors(&uri, errs))?;
+
+    let mut warnings = db.warnings(&uri).await;
+    if !cmd.hints {
+        warnings.retain(|warning| warning.severity() != Some(Severity::Advice));
+    }
+    if !warnings.is_empty() {
+        let reports = db.pretty_warnings(&uri, warnings);
+        let mut stderr = std::io::stderr().lock();
+        polarity_lang_driver::render_reports_io(&mut stderr, &reports, settings.colorize);
+    }
+
     println!("{} typechecked successfully!", cmd.filepath.display());
     Ok(())
 }
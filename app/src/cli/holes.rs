@@ -0,0 +1,44 @@
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+use polarity_lang_driver::Database;
+use polarity_lang_printer::{ColorChoice, Print, StandardStream};
+
+use crate::global_settings::GlobalSettings;
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[clap(value_parser, value_name = "FILE")]
+    filepath: PathBuf,
+}
+
+pub async fn exec(cmd: Args, settings: &GlobalSettings) -> Result<(), Vec<miette::Report>> {
+    let mut db = Database::from_path(&cmd.filepath);
+    let uri = db.resolve_path(&cmd.filepath).map_err(|e| vec![e.into()])?;
+    let goals = db.goals(&uri).await.map_err(|errs| db.pretty_errors(&uri, errs))?;
+
+    if goals.is_empty() {
+        println!("{} contains no unsolved holes", cmd.filepath.display());
+        return Ok(());
+    }
+
+    // Like the error reports of `pol check`, only colorize the output if it goes to a terminal.
+    // `ColorChoice::Auto` on its own does not check this.
+    let colorize = match settings.colorize {
+        ColorChoice::Auto if !std::io::stdout().is_terminal() => ColorChoice::Never,
+        colorize => colorize,
+    };
+    let mut stream = StandardStream::stdout(colorize);
+    for (idx, goal) in goals.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        if let Some(range) = db.span_to_locations(&uri, goal.span) {
+            let (line, character) = (range.start.line + 1, range.start.character + 1);
+            println!("{}:{line}:{character}", cmd.filepath.display());
+        }
+        goal.print_colored(&Default::default(), &mut stream).expect("Failed to print to stdout");
+        println!();
+    }
+    Ok(())
+}
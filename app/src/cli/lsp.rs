@@ -1,4 +1,4 @@
-use tower_lsp_server::{LspService, Server};
+use tower_lsp_server::Server;
 
 #[derive(clap::Args)]
 pub struct Args {}
@@ -6,7 +6,8 @@ pub struct Args {}
 pub async fn exec(_: Args) -> Result<(), Vec<miette::Report>> {
     let stdin = async_std::io::stdin();
     let stdout = async_std::io::stdout();
-    let (service, messages) = LspService::new(polarity_lang_lsp_server::Server::new);
+    let (service, messages) =
+        polarity_lang_lsp_server::service(polarity_lang_lsp_server::Server::new);
     Server::new(stdin, stdout, messages).serve(service).await;
     Ok(())
 }
@@ -8,6 +8,7 @@ mod compile;
 mod doc;
 mod format;
 mod gen_completions;
+mod holes;
 mod lex;
 mod lift;
 mod lsp;
@@ -47,11 +48,12 @@ pub fn exec(settings: &mut GlobalSettings) -> Result<(), Vec<miette::Report>> {
     let fut = async {
         match cli.command {
             Run(args) => run::exec(args, settings).await,
-            Check(args) => check::exec(args).await,
+            Check(args) => check::exec(args, settings).await,
             Fmt(args) => format::exec(args, settings).await,
             Prettify(args) => prettify::exec(args).await,
             Xfunc(args) => xfunc::exec(args).await,
             Lex(args) => lex::exec(args).await,
+            Holes(args) => holes::exec(args, settings).await,
             Lsp(args) => lsp::exec(args).await,
             Lift(args) => lift::exec(args).await,
             Doc(args) => doc::exec(args).await,
@@ -97,6 +99,8 @@ enum Command {
     /// Lex a file and print the resulting token stream for debugging
     #[clap(hide(true))]
     Lex(lex::Args),
+    /// List the unsolved holes of a file with their expected types and local contexts
+    Holes(holes::Args),
     /// Lift local (co)matches of a type to the top-level
     Lift(lift::Args),
     /// Generate documentation for a file
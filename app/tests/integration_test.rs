@@ -41,3 +41,78 @@ fn run_normalize_command() {
         .success()
         .stdout("Cons(S(S(S(Z))), Z, Cons(S(S(Z)), Z, Cons(S(Z), Z, Cons(Z, Z, Nil))))\n");
 }
+
+/// Check that "pol check" prints warnings, but still succeeds
+#[test]
+fn check_command_warnings() {
+    let assert = pol_cmd()
+        .env("POLARITY_COLORIZE", "never")
+        .args(vec!["check", "../test/suites/success/026-typedhole.pol"])
+        .assert();
+    let output = assert
+        .success()
+        .stdout("../test/suites/success/026-typedhole.pol typechecked successfully!\n")
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    for warning in ["Unsolved hole ?0", "Unsolved hole ?3", "Unused variable xs"] {
+        assert!(stderr.contains(warning), "missing warning {warning:?} in:\n{stderr}");
+    }
+    // Files are shown by their path rather than their URI.
+    assert!(stderr.contains("026-typedhole.pol:14:24"), "{stderr}");
+    assert!(!stderr.contains("file://"), "{stderr}");
+    // Unused top-level definitions are hints, which are only shown on request.
+    assert!(!stderr.contains("is never used"));
+}
+
+/// Check that "pol check --hints" also prints hints
+#[test]
+fn check_command_hints() {
+    let assert = pol_cmd()
+        .env("POLARITY_COLORIZE", "never")
+        .args(vec!["check", "--hints", "../test/suites/success/026-typedhole.pol"])
+        .assert();
+    let output = assert.success().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("is never used"), "missing hint in:\n{stderr}");
+}
+
+/// Check that "pol holes" works correctly
+#[test]
+fn holes_command() {
+    let assert = pol_cmd()
+        .env("POLARITY_COLORIZE", "never")
+        .args(vec!["holes", "../test/suites/success/026-typedhole.pol"])
+        .assert();
+    let rule = "—".repeat(40);
+    let ctx = "xs : Vec(n)\nx : Nat\nn' : Nat\nn : Nat";
+    assert.success().stdout(format!(
+        "../test/suites/success/026-typedhole.pol:14:24\nGoal ?0: Nat\n{rule}\n{ctx}\n\n\
+         ../test/suites/success/026-typedhole.pol:19:24\nGoal ?1: Vec(n)\n{rule}\n{ctx}\n\n\
+         ../test/suites/success/026-typedhole.pol:57:15\nGoal ?2: Stream\n{rule}\n\n\
+         ../test/suites/success/026-typedhole.pol:61:42\nGoal ?3: Nat\n{rule}\nchoose : Bool\n"
+    ));
+}
+
+/// Check that the output of "pol fmt" for compiled patterns typechecks and is formatted to itself
+#[test]
+fn fmt_command_round_trip() {
+    for case in ["051-nested-patterns", "052-multi-match"] {
+        // The output is written next to the input, so that its imports can be resolved.
+        let formatted_path = format!("../test/suites/success/{case}.fmt.tmp");
+        pol_cmd()
+            .args(vec!["fmt", "--width", "100", "--output", &formatted_path])
+            .arg(format!("../test/suites/success/{case}.pol"))
+            .assert()
+            .success();
+        let formatted = std::fs::read_to_string(&formatted_path).unwrap();
+        let check = pol_cmd().args(vec!["check", &formatted_path]).assert();
+        let reformat = pol_cmd()
+            .env("POLARITY_COLORIZE", "never")
+            .args(vec!["fmt", "--width", "100", &formatted_path])
+            .assert();
+        std::fs::remove_file(&formatted_path).unwrap();
+        check.success();
+        reformat.success().stdout(format!("{formatted}\n"));
+    }
+}
@@ -20,10 +20,10 @@ use polarity_lang_printer::tokens::DOT;
 use polarity_lang_printer::tokens::EXTERN;
 use polarity_lang_printer::tokens::HASH;
 use polarity_lang_printer::tokens::IMPLICIT;
-use polarity_lang_printer::tokens::INFIX;
 use polarity_lang_printer::tokens::LET;
 use polarity_lang_printer::tokens::NOTE;
 use polarity_lang_printer::tokens::USE;
+use polarity_lang_printer::tokens::{INFIX, INFIXL, INFIXR};
 use polarity_lang_printer::util::BracesExt;
 use polarity_lang_printer::util::IsNilExt;
 
@@ -43,16 +43,23 @@ use crate::rename::Rename;
 use crate::rename::RenameCtx;
 use crate::shift_and_clone;
 
+/// Print the return type of a declaration with parameters `params`, together with its comments
+///
+/// If the parameters or the return type have comments, the declaration spans several lines anyway,
+/// so the return type directly follows the parameters instead of moving to a line of its own.
 fn print_return_type<'a, T: Print>(
     cfg: &PrintCfg,
     alloc: &'a Alloc<'a>,
+    params: &Telescope,
     ret_typ: &'a T,
+    comments: &'a Comments,
 ) -> Builder<'a> {
-    alloc
-        .line_()
+    let break_before =
+        if params.has_comments() || !comments.is_empty() { alloc.nil() } else { alloc.line_() };
+    break_before
         .append(COLON)
         .append(alloc.space())
-        .append(ret_typ.print(cfg, alloc).group())
+        .append(comments.print_around(ret_typ.print(cfg, alloc).group(), alloc))
         .nest(cfg.indent)
 }
 
@@ -96,6 +103,76 @@ impl Print for DocComment {
     }
 }
 
+pub use polarity_lang_miette_util::trivia::{Comments, Trivia};
+
+/// Printing the comments which are attached to a node
+pub trait CommentsExt {
+    /// Print the leading comments, each followed by a line break
+    fn print_leading<'a>(&'a self, alloc: &'a Alloc<'a>) -> Builder<'a>;
+
+    /// Print the end-of-line comment and the trailing comments, each preceded by a line break
+    ///
+    /// A blank line which separates the node from the next one is printed at the end,
+    /// so the caller has to follow the node with a line break.
+    fn print_trailing<'a>(&'a self, alloc: &'a Alloc<'a>) -> Builder<'a>;
+
+    /// Print `doc` surrounded by these comments
+    fn print_around<'a>(&'a self, doc: Builder<'a>, alloc: &'a Alloc<'a>) -> Builder<'a>;
+
+    /// A space, or a line break if the node is followed by comments
+    fn space_after<'a>(&self, alloc: &'a Alloc<'a>) -> Builder<'a>;
+}
+
+impl CommentsExt for Comments {
+    fn print_leading<'a>(&'a self, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let mut doc = alloc.nil();
+        let mut pending_line = false;
+        for trivia in &self.leading {
+            match trivia {
+                Trivia::Comment(c) => {
+                    if pending_line {
+                        doc = doc.append(alloc.hardline());
+                    }
+                    doc = doc.append(alloc.comment(c));
+                    pending_line = true;
+                }
+                Trivia::BlankLine => doc = doc.append(blank_line(alloc)),
+            }
+        }
+        if pending_line { doc.append(alloc.hardline()) } else { doc }
+    }
+
+    fn print_trailing<'a>(&'a self, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let end_of_line = match &self.end_of_line {
+            Some(c) => alloc.space().append(alloc.comment(c)),
+            None => alloc.nil(),
+        };
+        end_of_line.append(alloc.concat(self.trailing.iter().map(|trivia| match trivia {
+            Trivia::Comment(c) => alloc.hardline().append(alloc.comment(c)),
+            Trivia::BlankLine => blank_line(alloc),
+        })))
+    }
+
+    fn print_around<'a>(&'a self, doc: Builder<'a>, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        self.print_leading(alloc).append(doc).append(self.print_trailing(alloc))
+    }
+
+    fn space_after<'a>(&self, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        if self.end_of_line.is_some() || !self.trailing.is_empty() {
+            alloc.hardline()
+        } else {
+            alloc.space()
+        }
+    }
+}
+
+/// A line break which turns the following line break into an empty line
+///
+/// Unlike a second hard line break, it does not indent the empty line.
+fn blank_line<'a>(alloc: &'a Alloc<'a>) -> Builder<'a> {
+    alloc.text("\n")
+}
+
 /// A single attribute.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Attribute {
@@ -199,13 +276,16 @@ impl MetaVarState {
 #[derive(Debug, Clone)]
 pub struct UseDecl {
     pub span: Span,
+    pub comments: Comments,
     pub path: String,
 }
 
 impl Print for UseDecl {
     fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let UseDecl { path, .. } = self;
-        alloc.keyword(USE).append(alloc.space()).append(alloc.text(path).double_quotes())
+        let UseDecl { comments, path, .. } = self;
+        let use_decl =
+            alloc.keyword(USE).append(alloc.space()).append(alloc.text(path).double_quotes());
+        comments.print_around(use_decl, alloc)
     }
 }
 
@@ -412,6 +492,27 @@ impl Decl {
         }
     }
 
+    /// The ordinary comments attached to the declaration
+    pub fn comments(&self) -> &Comments {
+        match self {
+            Decl::Data(Data { comments, .. }) => comments,
+            Decl::Codata(Codata { comments, .. }) => comments,
+            Decl::Def(Def { comments, .. }) => comments,
+            Decl::Codef(Codef { comments, .. }) => comments,
+            Decl::Let(Let { comments, .. }) => comments,
+            Decl::Extern(Extern { comments, .. }) => comments,
+            Decl::Infix(Infix { comments, .. }) => comments,
+            Decl::Note(Note { comments, .. }) => comments,
+        }
+    }
+
+    /// The span of the declaration, extended to the comments attached to it
+    ///
+    /// Code actions which replace a declaration replace this span, so that its comments are not duplicated.
+    pub fn span_with_comments(&self) -> Option<Span> {
+        self.span().map(|span| self.comments().span_with(span))
+    }
+
     /// Returns whether the declaration is the "main" expression of the module.
     pub fn get_main(&self) -> Option<Let> {
         match self {
@@ -517,19 +618,43 @@ impl Rename for Decl {
 pub struct Infix {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub attr: Attributes,
+    pub assoc: Associativity,
+    /// The precedence level, if it is given explicitly
+    pub precedence: Option<u32>,
     pub lhs: String,
     pub rhs: String,
 }
 
+impl Infix {
+    pub fn fixity(&self) -> Fixity {
+        Fixity {
+            assoc: self.assoc,
+            precedence: self.precedence.unwrap_or(Fixity::DEFAULT_PRECEDENCE),
+        }
+    }
+}
+
 impl Print for Infix {
     fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Infix { lhs, rhs, .. } = self;
-        alloc
-            .keyword(INFIX)
+        let Infix { comments, assoc, precedence, lhs, rhs, .. } = self;
+        let keyword = match assoc {
+            Associativity::Left => INFIXL,
+            Associativity::Right => INFIXR,
+            Associativity::NonAssoc => INFIX,
+        };
+        let precedence = match precedence {
+            Some(precedence) => format!(" {precedence}"),
+            None => String::new(),
+        };
+        let infix = alloc
+            .keyword(keyword)
+            .append(precedence)
             .append(format!(" _ {lhs} _ "))
             .append(COLONEQ)
-            .append(format!(" {rhs}(_,_)"))
+            .append(format!(" {rhs}(_,_)"));
+        comments.print_around(infix, alloc)
     }
 }
 
@@ -560,6 +685,7 @@ impl Rename for Infix {
 pub struct Data {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
     pub typ: Box<Telescope>,
@@ -568,7 +694,7 @@ pub struct Data {
 
 impl Print for Data {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Data { span: _, doc, name, attr, typ, ctors } = self;
+        let Data { span: _, doc, comments, name, attr, typ, ctors } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -586,6 +712,12 @@ impl Print for Data {
 
         let body = if ctors.is_empty() {
             empty_braces(alloc)
+        } else if ctors.iter().any(|ctor| !ctor.comments.is_empty()) {
+            print_commented_items(
+                ctors.iter().map(|ctor| (ctor.print(cfg, alloc), &ctor.comments)),
+                cfg,
+                alloc,
+            )
         } else {
             alloc
                 .line()
@@ -601,13 +733,13 @@ impl Print for Data {
 
         let body = if typ.params.is_empty() { body.group() } else { body };
 
-        head.append(body)
+        comments.print_around(head.append(body), alloc)
     }
 }
 
 impl Zonk for Data {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Data { span: _, doc: _, name: _, attr: _, typ, ctors } = self;
+        let Data { span: _, doc: _, comments: _, name: _, attr: _, typ, ctors } = self;
         typ.zonk(meta_vars)?;
         for ctor in ctors {
             ctor.zonk(meta_vars)?;
@@ -618,7 +750,7 @@ impl Zonk for Data {
 
 impl ContainsMetaVars for Data {
     fn contains_metavars(&self) -> bool {
-        let Data { span: _, doc: _, name: _, attr: _, typ, ctors } = self;
+        let Data { span: _, doc: _, comments: _, name: _, attr: _, typ, ctors } = self;
 
         typ.contains_metavars() || ctors.contains_metavars()
     }
@@ -639,6 +771,7 @@ impl Rename for Data {
 pub struct Codata {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
     pub typ: Box<Telescope>,
@@ -647,7 +780,7 @@ pub struct Codata {
 
 impl Print for Codata {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Codata { span: _, doc, name, attr, typ, dtors } = self;
+        let Codata { span: _, doc, comments, name, attr, typ, dtors } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -665,6 +798,12 @@ impl Print for Codata {
 
         let body = if dtors.is_empty() {
             empty_braces(alloc)
+        } else if dtors.iter().any(|dtor| !dtor.comments.is_empty()) {
+            print_commented_items(
+                dtors.iter().map(|dtor| (dtor.print(cfg, alloc), &dtor.comments)),
+                cfg,
+                alloc,
+            )
         } else {
             alloc
                 .line()
@@ -680,13 +819,13 @@ impl Print for Codata {
 
         let body = if typ.params.is_empty() { body.group() } else { body };
 
-        head.append(body)
+        comments.print_around(head.append(body), alloc)
     }
 }
 
 impl Zonk for Codata {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Codata { span: _, doc: _, name: _, attr: _, typ, dtors } = self;
+        let Codata { span: _, doc: _, comments: _, name: _, attr: _, typ, dtors } = self;
         typ.zonk(meta_vars)?;
         for dtor in dtors {
             dtor.zonk(meta_vars)?;
@@ -697,7 +836,7 @@ impl Zonk for Codata {
 
 impl ContainsMetaVars for Codata {
     fn contains_metavars(&self) -> bool {
-        let Codata { span: _, doc: _, name: _, attr: _, typ, dtors } = self;
+        let Codata { span: _, doc: _, comments: _, name: _, attr: _, typ, dtors } = self;
 
         typ.contains_metavars() || dtors.contains_metavars()
     }
@@ -718,24 +857,27 @@ impl Rename for Codata {
 pub struct Ctor {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: TypCtor,
 }
 
 impl Print for Ctor {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Ctor { span: _, doc, name, params, typ } = self;
+        let Ctor { span: _, doc, comments: _, name, params, typ_comments, typ } = self;
 
         let doc = doc.print(cfg, alloc);
         let head = alloc.ctor(&name.id).append(params.print(cfg, alloc));
 
-        let head = if typ.is_simple() {
+        let head = if typ.is_simple() && typ_comments.is_empty() {
             head
         } else {
             let mut cfg = cfg.clone();
             cfg.print_function_sugar = false;
-            head.append(print_return_type(&cfg, alloc, typ)).group()
+            head.append(print_return_type(&cfg, alloc, params, typ, typ_comments)).group()
         };
         doc.append(head)
     }
@@ -743,7 +885,7 @@ impl Print for Ctor {
 
 impl Zonk for Ctor {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Ctor { span: _, doc: _, name: _, params, typ } = self;
+        let Ctor { span: _, doc: _, comments: _, name: _, params, typ_comments: _, typ } = self;
         params.zonk(meta_vars)?;
         typ.zonk(meta_vars)?;
         Ok(())
@@ -752,7 +894,7 @@ impl Zonk for Ctor {
 
 impl ContainsMetaVars for Ctor {
     fn contains_metavars(&self) -> bool {
-        let Ctor { span: _, doc: _, name: _, params, typ } = self;
+        let Ctor { span: _, doc: _, comments: _, name: _, params, typ_comments: _, typ } = self;
 
         params.contains_metavars() || typ.contains_metavars()
     }
@@ -773,22 +915,26 @@ impl Rename for Ctor {
 pub struct Dtor {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub params: Telescope,
     pub self_param: SelfParam,
+    /// The comments of the return type
+    pub ret_typ_comments: Comments,
     pub ret_typ: Box<Exp>,
 }
 
 impl Print for Dtor {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Dtor { span: _, doc, name, params, self_param, ret_typ } = self;
+        let Dtor { span: _, doc, comments: _, name, params, self_param, ret_typ_comments, ret_typ } =
+            self;
 
         let doc = doc.print(cfg, alloc);
         let head = print_self_param(cfg, alloc, params, self_param, ret_typ, true);
         let head = head
             .append(alloc.dtor(&name.id))
             .append(params.print(cfg, alloc))
-            .append(print_return_type(cfg, alloc, ret_typ))
+            .append(print_return_type(cfg, alloc, params, ret_typ, ret_typ_comments))
             .group();
         doc.append(head)
     }
@@ -796,7 +942,16 @@ impl Print for Dtor {
 
 impl Zonk for Dtor {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Dtor { span: _, doc: _, name: _, params, self_param, ret_typ } = self;
+        let Dtor {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            params,
+            self_param,
+            ret_typ_comments: _,
+            ret_typ,
+        } = self;
         params.zonk(meta_vars)?;
         self_param.zonk(meta_vars)?;
         ret_typ.zonk(meta_vars)?;
@@ -806,7 +961,16 @@ impl Zonk for Dtor {
 
 impl ContainsMetaVars for Dtor {
     fn contains_metavars(&self) -> bool {
-        let Dtor { span: _, doc: _, name: _, params, self_param, ret_typ } = self;
+        let Dtor {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            params,
+            self_param,
+            ret_typ_comments: _,
+            ret_typ,
+        } = self;
 
         params.contains_metavars() || self_param.contains_metavars() || ret_typ.contains_metavars()
     }
@@ -833,12 +997,17 @@ impl Rename for Dtor {
 pub struct Def {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
     pub params: Telescope,
     pub self_param: SelfParam,
+    /// The comments of the return type
+    pub ret_typ_comments: Comments,
     pub ret_typ: Box<Exp>,
     pub cases: Vec<Case>,
+    /// The cases as they are written in the source, if their patterns have been compiled
+    pub source_cases: Option<Vec<SourceCase>>,
 }
 
 impl Def {
@@ -846,9 +1015,11 @@ impl Def {
         Dtor {
             span: self.span,
             doc: self.doc.clone(),
+            comments: self.comments.clone(),
             name: self.name.clone(),
             params: self.params.clone(),
             self_param: self.self_param.clone(),
+            ret_typ_comments: self.ret_typ_comments.clone(),
             ret_typ: self.ret_typ.clone(),
         }
     }
@@ -856,7 +1027,19 @@ impl Def {
 
 impl Print for Def {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Def { span: _, doc, name, attr, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span: _,
+            doc,
+            comments,
+            name,
+            attr,
+            params,
+            self_param,
+            ret_typ_comments,
+            ret_typ,
+            cases,
+            source_cases,
+        } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -869,18 +1052,36 @@ impl Print for Def {
             .append(print_self_param(cfg, alloc, params, self_param, ret_typ, false))
             .append(alloc.dtor(&name.id))
             .append(params.print(cfg, alloc))
-            .append(print_return_type(cfg, alloc, ret_typ))
+            .append(print_return_type(cfg, alloc, params, ret_typ, ret_typ_comments))
             .group();
 
-        let body = print_cases(cases, cfg, alloc);
+        let body = match source_cases {
+            Some(source_cases) => print_source_cases(source_cases, cfg, alloc),
+            None => print_cases(cases, cfg, alloc),
+        };
 
-        doc.append(head).append(alloc.space()).append(body)
+        comments.print_around(
+            doc.append(head).append(ret_typ_comments.space_after(alloc)).append(body),
+            alloc,
+        )
     }
 }
 
 impl Zonk for Def {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Def { span: _, doc: _, name: _, attr: _, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            attr: _,
+            params,
+            self_param,
+            ret_typ_comments: _,
+            ret_typ,
+            cases,
+            source_cases: _,
+        } = self;
         params.zonk(meta_vars)?;
         self_param.zonk(meta_vars)?;
         ret_typ.zonk(meta_vars)?;
@@ -893,7 +1094,19 @@ impl Zonk for Def {
 
 impl ContainsMetaVars for Def {
     fn contains_metavars(&self) -> bool {
-        let Def { span: _, doc: _, name: _, attr: _, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            attr: _,
+            params,
+            self_param,
+            ret_typ_comments: _,
+            ret_typ,
+            cases,
+            source_cases: _,
+        } = self;
 
         params.contains_metavars()
             || self_param.contains_metavars()
@@ -908,6 +1121,7 @@ impl Rename for Def {
         ctx.bind_iter(self.params.params.iter(), |new_ctx| {
             self.self_param.rename_in_ctx(new_ctx);
             self.cases.rename_in_ctx(new_ctx);
+            self.source_cases.rename_in_ctx(new_ctx);
 
             new_ctx.bind_single(&self.self_param, |new_ctx| self.ret_typ.rename_in_ctx(new_ctx))
         })
@@ -922,9 +1136,12 @@ impl Rename for Def {
 pub struct Codef {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: TypCtor,
     pub cases: Vec<Case>,
 }
@@ -934,8 +1151,10 @@ impl Codef {
         Ctor {
             span: self.span,
             doc: self.doc.clone(),
+            comments: self.comments.clone(),
             name: self.name.clone(),
             params: self.params.clone(),
+            typ_comments: self.typ_comments.clone(),
             typ: self.typ.clone(),
         }
     }
@@ -943,7 +1162,7 @@ impl Codef {
 
 impl Print for Codef {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Codef { span: _, doc, name, attr, params, typ, cases } = self;
+        let Codef { span: _, doc, comments, name, attr, params, typ_comments, typ, cases } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -958,19 +1177,34 @@ impl Print for Codef {
             .append(print_return_type(
                 &PrintCfg { print_function_sugar: false, ..*cfg },
                 alloc,
+                params,
                 typ,
+                typ_comments,
             ))
             .group();
 
         let body = print_cases(cases, cfg, alloc);
 
-        doc.append(head).append(alloc.space()).append(body)
+        comments.print_around(
+            doc.append(head).append(typ_comments.space_after(alloc)).append(body),
+            alloc,
+        )
     }
 }
 
 impl Zonk for Codef {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Codef { span: _, doc: _, name: _, attr: _, params, typ, cases } = self;
+        let Codef {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            attr: _,
+            params,
+            typ_comments: _,
+            typ,
+            cases,
+        } = self;
         params.zonk(meta_vars)?;
         typ.zonk(meta_vars)?;
         for case in cases {
@@ -982,7 +1216,17 @@ impl Zonk for Codef {
 
 impl ContainsMetaVars for Codef {
     fn contains_metavars(&self) -> bool {
-        let Codef { span: _, doc: _, name: _, attr: _, params, typ, cases } = self;
+        let Codef {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            attr: _,
+            params,
+            typ_comments: _,
+            typ,
+            cases,
+        } = self;
 
         params.contains_metavars() || typ.contains_metavars() || cases.contains_metavars()
     }
@@ -1007,10 +1251,15 @@ impl Rename for Codef {
 pub struct Let {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: Box<Exp>,
+    /// The comments of the body within the braces
+    pub body_comments: Comments,
     pub body: Box<Exp>,
 }
 
@@ -1023,7 +1272,18 @@ impl Let {
 
 impl Print for Let {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Let { span: _, doc, name, attr, params, typ, body } = self;
+        let Let {
+            span: _,
+            doc,
+            comments,
+            name,
+            attr,
+            params,
+            typ_comments,
+            typ,
+            body_comments,
+            body,
+        } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -1035,18 +1295,42 @@ impl Print for Let {
             .append(alloc.space())
             .append(&name.id)
             .append(params.print(cfg, alloc))
-            .append(print_return_type(cfg, alloc, typ))
+            .append(print_return_type(cfg, alloc, params, typ, typ_comments))
             .group();
 
-        let body = body.print(cfg, alloc).braces_anno();
+        // A body which spans several lines is printed as a block, so that its comments are indented.
+        let body = if body_comments.is_empty() && !matches!(**body, Exp::LocalLet(_)) {
+            body.print(cfg, alloc).braces_anno()
+        } else {
+            alloc
+                .hardline()
+                .append(body_comments.print_around(body.print(cfg, alloc), alloc))
+                .nest(cfg.indent)
+                .append(alloc.hardline())
+                .braces_anno()
+        };
 
-        doc.append(head).append(alloc.space()).append(body)
+        comments.print_around(
+            doc.append(head).append(typ_comments.space_after(alloc)).append(body),
+            alloc,
+        )
     }
 }
 
 impl Zonk for Let {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Let { span: _, doc: _, name: _, attr: _, params, typ, body } = self;
+        let Let {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            attr: _,
+            params,
+            typ_comments: _,
+            typ,
+            body_comments: _,
+            body,
+        } = self;
         params.zonk(meta_vars)?;
         typ.zonk(meta_vars)?;
         body.zonk(meta_vars)?;
@@ -1056,7 +1340,18 @@ impl Zonk for Let {
 
 impl ContainsMetaVars for Let {
     fn contains_metavars(&self) -> bool {
-        let Let { span: _, doc: _, name: _, attr: _, params, typ, body } = self;
+        let Let {
+            span: _,
+            doc: _,
+            comments: _,
+            name: _,
+            attr: _,
+            params,
+            typ_comments: _,
+            typ,
+            body_comments: _,
+            body,
+        } = self;
 
         params.contains_metavars() || typ.contains_metavars() || body.contains_metavars()
     }
@@ -1080,15 +1375,18 @@ impl Rename for Let {
 pub struct Extern {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: Box<Exp>,
 }
 
 impl Print for Extern {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Extern { span: _, doc, name, attr, params, typ } = self;
+        let Extern { span: _, doc, comments, name, attr, params, typ_comments, typ } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -1100,10 +1398,10 @@ impl Print for Extern {
             .append(alloc.space())
             .append(&name.id)
             .append(params.print(cfg, alloc))
-            .append(print_return_type(cfg, alloc, typ))
+            .append(print_return_type(cfg, alloc, params, typ, typ_comments))
             .group();
 
-        doc.append(head)
+        comments.print_around(doc.append(head), alloc)
     }
 }
 
@@ -1155,6 +1453,7 @@ impl SelfParam {
             name: self.name.clone(),
             typ: Box::new(self.typ.to_exp()),
             erased: false,
+            comments: Comments::default(),
         }
     }
 
@@ -1231,6 +1530,11 @@ impl Telescope {
         self.params.is_empty()
     }
 
+    /// Whether any of the parameters has comments
+    pub fn has_comments(&self) -> bool {
+        self.params.iter().any(|param| !param.comments.is_empty())
+    }
+
     pub fn instantiate(&self) -> TelescopeInst {
         let params = self
             .params
@@ -1250,7 +1554,7 @@ impl Print for Telescope {
     /// This function tries to "chunk" successive blocks of parameters which have the same type.
     /// For example, instead of printing `x: Type, y: Type` we print `x y: Type`. We do this by
     /// remembering in the `running` variable what the current type of the parameters is, and
-    /// whether we can append the current parameter to this list. There are two complications:
+    /// whether we can append the current parameter to this list. There are three complications:
     ///
     /// 1) Due to de Bruijn indices we have to shift the types when we compare them. For example,
     ///    instead of printing `n: Nat, x: Vec(Bool,n), y: Vec(Bool,n)` we want to print
@@ -1259,62 +1563,62 @@ impl Print for Telescope {
     ///
     /// 2) We cannot chunk two parameters if one is implicit and the other isn't, even if they have
     ///    the same type. For example: `implicit a: Type, b: Type` cannot be chunked.
+    ///
+    /// 3) Parameters with comments are not chunked, since we cannot tell whether the comments
+    ///    belong to the first parameter only. If there are any comments, every chunk is printed on
+    ///    a separate line.
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
         let Telescope { params } = self;
-        let mut output = alloc.nil();
         if params.is_empty() {
-            return output;
+            return alloc.nil();
         };
-        // Running stands for the type and implicitness of the current "chunk" we are building.
-        let mut running: Option<(&Exp, bool)> = None;
-        for Param { implicit, name, typ, erased: _ } in params {
-            match running {
+        // The completed chunks, together with the comments of their first parameter
+        let mut chunks: Vec<(Builder<'a>, &'a Comments)> = vec![];
+        let close = |(chunk, comments, rtype, _): (Builder<'a>, &'a Comments, &'a Exp, bool)| {
+            (chunk.append(COLON).append(alloc.space()).append(rtype.print(cfg, alloc)), comments)
+        };
+        // Running stands for the current "chunk" we are building, together with the type and implicitness of its parameters.
+        let mut running: Option<(Builder<'a>, &'a Comments, &'a Exp, bool)> = None;
+        for Param { implicit, name, typ, erased: _, comments } in params {
+            running = match running.take() {
                 // We need to shift before comparing to ensure we compare the correct De-Bruijn indices
-                Some((rtype, rimplicit))
-                    if shift_and_clone(rtype, (0, 1)) == **typ && rimplicit == *implicit =>
+                Some((chunk, chunk_comments, rtype, rimplicit))
+                    if shift_and_clone(rtype, (0, 1)) == **typ
+                        && rimplicit == *implicit
+                        && chunk_comments.is_empty()
+                        && comments.is_empty() =>
                 {
                     // We are adding another parameter of the same type.
-                    output = output.append(alloc.space()).append(name.print(cfg, alloc));
+                    let chunk = chunk.append(alloc.space()).append(name.print(cfg, alloc));
+                    Some((chunk, chunk_comments, typ, *implicit))
                 }
-                Some((rtype, _)) => {
-                    // We are adding another parameter with a different type,
-                    // and have to close the previous list first.
-                    output = output
-                        .append(COLON)
-                        .append(alloc.space())
-                        .append(rtype.print(cfg, alloc))
-                        .append(COMMA)
-                        .append(alloc.line());
-                    if *implicit {
-                        output = output
-                            .append(alloc.keyword(IMPLICIT))
-                            .append(alloc.space())
-                            .append(name.print(cfg, alloc));
-                    } else {
-                        output = output.append(name.print(cfg, alloc));
-                    }
-                }
-                None => {
-                    // We are starting a new chunk and adding the very first parameter.
+                previous => {
+                    // We are starting a new chunk, and have to close the previous one first.
                     // If we are starting a chunk of implicit parameters then we also have to
                     // add the "implicit" keyword at this point.
-                    if *implicit {
-                        output = output.append(alloc.keyword(IMPLICIT)).append(alloc.space())
-                    }
-
-                    output = output.append(name.print(cfg, alloc));
+                    chunks.extend(previous.map(close));
+                    let chunk = if *implicit {
+                        alloc.keyword(IMPLICIT).append(alloc.space()).append(name.print(cfg, alloc))
+                    } else {
+                        name.print(cfg, alloc)
+                    };
+                    Some((chunk, comments, typ, *implicit))
                 }
-            }
-            running = Some((typ, *implicit));
+            };
         }
-        // Close the last parameter
-        match running {
-            None => {}
-            Some((rtype, _)) => {
-                output = output.append(COLON).append(alloc.space()).append(rtype.print(cfg, alloc));
-            }
+        // Close the last chunk
+        chunks.extend(running.map(close));
+
+        if chunks.iter().any(|(_, comments)| !comments.is_empty()) {
+            return print_commented_list(chunks, cfg, alloc);
         }
-        output.append(alloc.line_()).align().parens().group()
+        let sep = alloc.text(COMMA).append(alloc.line());
+        alloc
+            .intersperse(chunks.into_iter().map(|(chunk, _)| chunk), sep)
+            .append(alloc.line_())
+            .align()
+            .parens()
+            .group()
     }
 }
 
@@ -1388,12 +1692,14 @@ mod print_telescope_tests {
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let param2 = Param {
             implicit: false,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(x y: Type)")
@@ -1406,12 +1712,14 @@ mod print_telescope_tests {
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let param2 = Param {
             implicit: true,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(implicit x y: Type)")
@@ -1424,12 +1732,14 @@ mod print_telescope_tests {
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let param2 = Param {
             implicit: false,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(implicit x: Type, y: Type)")
@@ -1442,12 +1752,14 @@ mod print_telescope_tests {
             name: VarBind::from_string("x"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let param2 = Param {
             implicit: true,
             name: VarBind::from_string("y"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let tele = Telescope { params: vec![param1, param2] };
         assert_eq!(tele.print_to_string(Default::default()), "(x: Type, implicit y: Type)")
@@ -1460,6 +1772,7 @@ mod print_telescope_tests {
             name: VarBind::from_string("a"),
             typ: Box::new(TypeUniv::new().into()),
             erased: false,
+            comments: Comments::default(),
         };
         let param2 = Param {
             implicit: false,
@@ -1472,6 +1785,7 @@ mod print_telescope_tests {
                 erased: false,
             })),
             erased: false,
+            comments: Comments::default(),
         };
         let param3 = Param {
             implicit: false,
@@ -1484,6 +1798,7 @@ mod print_telescope_tests {
                 erased: false,
             })),
             erased: false,
+            comments: Comments::default(),
         };
         let tele = Telescope { params: vec![param1, param2, param3] };
         assert_eq!(tele.print_to_string(Default::default()), "(a: Type, x y: a)")
@@ -1513,6 +1828,7 @@ mod print_def_tests {
         let def = Def {
             span: None,
             doc: None,
+            comments: Default::default(),
             name: IdBind::from_string("foo"),
             attr: Default::default(),
             params: Telescope { params: vec![] },
@@ -1521,8 +1837,10 @@ mod print_def_tests {
                 name: VarBind::Wildcard { span: None },
                 typ: simple_typ("Nat"),
             },
+            ret_typ_comments: Default::default(),
             ret_typ: Box::new(simple_typ("Nat").into()),
             cases: vec![],
+            source_cases: None,
         };
 
         assert_eq!(def.print_to_string(Default::default()), "def Nat.foo: Nat { }");
@@ -1542,24 +1860,30 @@ pub struct Param {
     pub typ: Box<Exp>,
     /// Whether the parameter is erased during compilation.
     pub erased: bool,
+    /// The comments of the parameter
+    ///
+    /// If several parameters are declared together, as in `x y: T`, the first one carries their comments.
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub comments: Comments,
 }
 
 impl Substitutable for Param {
     type Target = Param;
     fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
-        let Param { implicit, name, typ, erased } = self;
+        let Param { implicit, name, typ, erased, comments } = self;
         Param {
             implicit: *implicit,
             name: name.clone(),
             typ: typ.subst(ctx, subst),
             erased: *erased,
+            comments: comments.clone(),
         }
     }
 }
 
 impl Print for Param {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Param { implicit, name, typ, erased: _ } = self;
+        let Param { implicit, name, typ, erased: _, comments: _ } = self;
         if *implicit {
             alloc
                 .keyword(IMPLICIT)
@@ -1576,14 +1900,14 @@ impl Print for Param {
 
 impl Zonk for Param {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Param { implicit: _, name: _, typ, erased: _ } = self;
+        let Param { implicit: _, name: _, typ, erased: _, comments: _ } = self;
         typ.zonk(meta_vars)
     }
 }
 
 impl ContainsMetaVars for Param {
     fn contains_metavars(&self) -> bool {
-        let Param { implicit: _, name: _, typ, erased: _ } = self;
+        let Param { implicit: _, name: _, typ, erased: _, comments: _ } = self;
 
         typ.contains_metavars()
     }
@@ -1604,13 +1928,14 @@ impl Rename for Param {
 pub struct Note {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: IdBind,
     pub attr: Attributes,
 }
 
 impl Print for Note {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Note { span: _, doc, name, attr } = self;
+        let Note { span: _, doc, comments, name, attr } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -1619,7 +1944,7 @@ impl Print for Note {
 
         let note = alloc.keyword(NOTE).append(alloc.space()).append(&name.id).group();
 
-        doc.append(note)
+        comments.print_around(doc.append(note), alloc)
     }
 }
 
@@ -9,8 +9,8 @@ use polarity_lang_printer::{
 
 use super::{Exp, Hole, MetaVar, VarBound};
 use crate::{
-    ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Subst, Substitutable,
-    Zonk, ZonkError,
+    Comments, CommentsExt, ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRange,
+    Subst, Substitutable, Zonk, ZonkError,
     ctx::LevelCtx,
     rename::{Rename, RenameCtx},
 };
@@ -25,9 +25,23 @@ use crate::{
 #[derive(Debug, Clone, Derivative)]
 #[derivative(Eq, PartialEq, Hash)]
 pub enum Arg {
-    UnnamedArg { arg: Box<Exp>, erased: bool },
-    NamedArg { name: VarBound, arg: Box<Exp>, erased: bool },
-    InsertedImplicitArg { hole: Hole, erased: bool },
+    UnnamedArg {
+        arg: Box<Exp>,
+        erased: bool,
+        #[derivative(PartialEq = "ignore", Hash = "ignore")]
+        comments: Comments,
+    },
+    NamedArg {
+        name: VarBound,
+        arg: Box<Exp>,
+        erased: bool,
+        #[derivative(PartialEq = "ignore", Hash = "ignore")]
+        comments: Comments,
+    },
+    InsertedImplicitArg {
+        hole: Hole,
+        erased: bool,
+    },
 }
 
 impl Arg {
@@ -51,6 +65,15 @@ impl Arg {
         }
     }
 
+    /// The comments attached to the argument, unless it was inserted during elaboration
+    pub fn comments(&self) -> Option<&Comments> {
+        match self {
+            Arg::UnnamedArg { comments, .. } => Some(comments),
+            Arg::NamedArg { comments, .. } => Some(comments),
+            Arg::InsertedImplicitArg { .. } => None,
+        }
+    }
+
     pub fn erased(&self) -> bool {
         match self {
             Arg::UnnamedArg { erased, .. } => *erased,
@@ -107,12 +130,17 @@ impl Substitutable for Arg {
     type Target = Arg;
     fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
         match self {
-            Arg::UnnamedArg { arg, erased } => {
-                Arg::UnnamedArg { arg: arg.subst(ctx, subst), erased: *erased }
-            }
-            Arg::NamedArg { name: var, arg, erased } => {
-                Arg::NamedArg { name: var.clone(), arg: arg.subst(ctx, subst), erased: *erased }
-            }
+            Arg::UnnamedArg { arg, erased, comments } => Arg::UnnamedArg {
+                arg: arg.subst(ctx, subst),
+                erased: *erased,
+                comments: comments.clone(),
+            },
+            Arg::NamedArg { name: var, arg, erased, comments } => Arg::NamedArg {
+                name: var.clone(),
+                arg: arg.subst(ctx, subst),
+                erased: *erased,
+                comments: comments.clone(),
+            },
             Arg::InsertedImplicitArg { hole, erased } => {
                 Arg::InsertedImplicitArg { hole: hole.subst(ctx, subst), erased: *erased }
             }
@@ -175,8 +203,10 @@ impl Rename for Arg {
 impl FreeVars for Arg {
     fn free_vars_mut(&self, ctx: &LevelCtx, cutoff: usize, fvs: &mut crate::HashSet<crate::Lvl>) {
         match self {
-            Arg::UnnamedArg { arg, erased: _ } => arg.free_vars_mut(ctx, cutoff, fvs),
-            Arg::NamedArg { name: _, arg, erased: _ } => arg.free_vars_mut(ctx, cutoff, fvs),
+            Arg::UnnamedArg { arg, erased: _, comments: _ } => arg.free_vars_mut(ctx, cutoff, fvs),
+            Arg::NamedArg { name: _, arg, erased: _, comments: _ } => {
+                arg.free_vars_mut(ctx, cutoff, fvs)
+            }
             Arg::InsertedImplicitArg { hole, erased: _ } => hole.free_vars_mut(ctx, cutoff, fvs),
         }
     }
@@ -245,6 +275,15 @@ impl Print for Args {
             return alloc.nil();
         }
 
+        let commented: Vec<_> = self
+            .args
+            .iter()
+            .filter_map(|arg| arg.comments().map(|comments| (arg.print(cfg, alloc), comments)))
+            .collect();
+        if commented.iter().any(|(_, comments)| !comments.is_empty()) {
+            return print_commented_list(commented, cfg, alloc);
+        }
+
         let mut doc = alloc.nil();
         let mut first = true;
 
@@ -262,6 +301,30 @@ impl Print for Args {
     }
 }
 
+/// Print a parenthesized list whose elements have comments, with one element per line
+///
+/// The comma follows an element directly, so that an end-of-line comment is printed after it.
+pub fn print_commented_list<'a>(
+    items: Vec<(Builder<'a>, &'a Comments)>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let last = items.len() - 1;
+    let items = items.into_iter().enumerate().map(|(idx, (item, comments))| {
+        comments
+            .print_leading(alloc)
+            .append(item)
+            .append(if idx < last { alloc.text(COMMA) } else { alloc.nil() })
+            .append(comments.print_trailing(alloc))
+    });
+    alloc
+        .hardline()
+        .append(alloc.intersperse(items, alloc.hardline()))
+        .nest(cfg.indent)
+        .append(alloc.hardline())
+        .parens()
+}
+
 impl Zonk for Args {
     fn zonk(
         &mut self,
@@ -333,16 +396,26 @@ mod args_tests {
         );
 
         assert_eq!(
-            Args { args: vec![Arg::UnnamedArg { arg: ctor.clone(), erased: false }] }
-                .print_to_string(Default::default()),
+            Args {
+                args: vec![Arg::UnnamedArg {
+                    arg: ctor.clone(),
+                    erased: false,
+                    comments: Default::default()
+                }]
+            }
+            .print_to_string(Default::default()),
             "(T)".to_string()
         );
 
         assert_eq!(
             Args {
                 args: vec![
-                    Arg::UnnamedArg { arg: ctor.clone(), erased: false },
-                    Arg::UnnamedArg { arg: ctor, erased: false }
+                    Arg::UnnamedArg {
+                        arg: ctor.clone(),
+                        erased: false,
+                        comments: Default::default()
+                    },
+                    Arg::UnnamedArg { arg: ctor, erased: false, comments: Default::default() }
                 ]
             }
             .print_to_string(Default::default()),
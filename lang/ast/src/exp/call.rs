@@ -1,12 +1,9 @@
 use derivative::Derivative;
-use pretty::DocAllocator;
 
 use polarity_lang_miette_util::codespan::Span;
-use polarity_lang_printer::{
-    Alloc, Builder, Precedence, Print, PrintCfg, theme::ThemeExt, util::ParensIfExt,
-};
+use polarity_lang_printer::{Alloc, Builder, Precedence, Print, PrintCfg, theme::ThemeExt};
 
-use super::{Args, Exp, IdBound, MetaVar};
+use super::{Args, Exp, IdBound, InfixOp, MetaVar};
 use crate::{
     ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Subst, Substitutable,
     Zonk, ZonkError,
@@ -46,7 +43,7 @@ pub struct Call {
     pub args: Args,
     /// If this Call has been lowered from a binary operator.
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
-    pub is_bin_op: Option<String>,
+    pub is_bin_op: Option<InfixOp>,
     /// The inferred result type of the call.
     /// This type is annotated during elaboration.
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
@@ -114,10 +111,7 @@ impl Print for Call {
         match is_bin_op {
             Some(op) => {
                 assert!(args.len() == 2);
-                let arg = args.args[0].print_prec(cfg, alloc, Precedence::Ops);
-                let res = args.args[1].print_prec(cfg, alloc, Precedence::Exp);
-                let fun = arg.append(alloc.space()).append(op).append(alloc.space()).append(res);
-                fun.parens_if(prec > Precedence::NonLet)
+                op.print_app(&args.args[0], &args.args[1], cfg, alloc, prec)
             }
             _ => alloc
                 .ctor(&name.id)
@@ -11,8 +11,8 @@ use polarity_lang_printer::{
 
 use super::{Exp, IdBound, MetaVar, TelescopeInst};
 use crate::{
-    ContainsMetaVars, FreeVars, Occurs, Shift, ShiftRange, ShiftRangeExt, Subst, Substitutable,
-    Zonk, ZonkError,
+    Comments, CommentsExt, ContainsMetaVars, FreeVars, Occurs, Shift, ShiftRange, ShiftRangeExt,
+    Subst, Substitutable, Zonk, ZonkError,
     ctx::{BindContext, LevelCtx},
     rename::{Rename, RenameCtx},
 };
@@ -65,9 +65,14 @@ impl Zonk for Pattern {
 pub struct Case {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub span: Option<Span>,
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub comments: Comments,
     pub pattern: Pattern,
     /// Body being `None` represents an absurd pattern
     pub body: Option<Box<Exp>>,
+    /// Whether the case was generated by the compilation of nested patterns for a constructor
+    /// which is only matched by wildcards. Such a case is absurd if its constructor cannot occur.
+    pub from_wildcard: bool,
 }
 
 impl Shift for Case {
@@ -91,10 +96,12 @@ impl Occurs for Case {
 impl Substitutable for Case {
     type Target = Case;
     fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
-        let Case { span, pattern, body } = self;
+        let Case { span, comments, pattern, body, from_wildcard } = self;
         ctx.bind_iter(pattern.params.params.iter(), |ctx| Case {
             span: *span,
+            comments: comments.clone(),
             pattern: pattern.clone(),
+            from_wildcard: *from_wildcard,
             body: body.as_ref().map(|body| {
                 let mut subst = (*subst).clone();
                 subst.shift((1, 0));
@@ -116,7 +123,7 @@ impl Print for Case {
         alloc: &'a Alloc<'a>,
         _prec: Precedence,
     ) -> Builder<'a> {
-        let Case { span: _, pattern, body } = self;
+        let Case { span: _, comments: _, pattern, body, from_wildcard: _ } = self;
 
         let body = match body {
             None => alloc.keyword(ABSURD),
@@ -136,7 +143,7 @@ impl Zonk for Case {
         &mut self,
         meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
     ) -> Result<(), ZonkError> {
-        let Case { span: _, pattern, body } = self;
+        let Case { span: _, comments: _, pattern, body, from_wildcard: _ } = self;
         pattern.zonk(meta_vars)?;
         body.zonk(meta_vars)?;
         Ok(())
@@ -144,12 +151,29 @@ impl Zonk for Case {
 }
 
 pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    print_case_items(
+        cases.iter().map(|case| (case.print(cfg, alloc), &case.comments)).collect(),
+        cfg,
+        alloc,
+    )
+}
+
+/// Print the block of a match, given its printed cases together with their comments
+pub fn print_case_items<'a>(
+    cases: Vec<(Builder<'a>, &'a Comments)>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    if cases.iter().any(|(_, comments)| !comments.is_empty()) {
+        return print_commented_items(cases.into_iter(), cfg, alloc);
+    }
+
     match cases.len() {
         0 => empty_braces(alloc),
 
         1 => alloc
             .line()
-            .append(cases[0].print(cfg, alloc))
+            .append(cases.into_iter().next().map(|(case, _)| case))
             .nest(cfg.indent)
             .append(alloc.line())
             .braces_anno()
@@ -158,7 +182,7 @@ pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>)
             let sep = alloc.text(COMMA).append(alloc.hardline());
             alloc
                 .hardline()
-                .append(alloc.intersperse(cases.iter().map(|x| x.print(cfg, alloc)), sep))
+                .append(alloc.intersperse(cases.into_iter().map(|(case, _)| case), sep))
                 .append(alloc.text(COMMA).flat_alt(alloc.nil()))
                 .nest(cfg.indent)
                 .append(alloc.hardline())
@@ -167,9 +191,32 @@ pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>)
     }
 }
 
+/// Print the items of a block together with their comments, one item per line
+///
+/// Every item is followed by a comma, so that an end-of-line comment is printed after the comma.
+pub fn print_commented_items<'a>(
+    items: impl Iterator<Item = (Builder<'a>, &'a Comments)>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let items = items.map(|(item, comments)| {
+        comments
+            .print_leading(alloc)
+            .append(item)
+            .append(COMMA)
+            .append(comments.print_trailing(alloc))
+    });
+    alloc
+        .hardline()
+        .append(alloc.intersperse(items, alloc.hardline()))
+        .nest(cfg.indent)
+        .append(alloc.hardline())
+        .braces_anno()
+}
+
 impl ContainsMetaVars for Case {
     fn contains_metavars(&self) -> bool {
-        let Case { span: _, pattern: _, body } = self;
+        let Case { span: _, comments: _, pattern: _, body, from_wildcard: _ } = self;
 
         body.contains_metavars()
     }
@@ -187,7 +234,7 @@ impl Rename for Case {
 
 impl FreeVars for Case {
     fn free_vars_mut(&self, ctx: &LevelCtx, cutoff: usize, fvs: &mut crate::HashSet<crate::Lvl>) {
-        let Case { span: _, pattern: _, body } = self;
+        let Case { span: _, comments: _, pattern: _, body, from_wildcard: _ } = self;
 
         body.free_vars_mut(ctx, cutoff + 1, fvs)
     }
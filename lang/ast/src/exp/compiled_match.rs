@@ -0,0 +1,337 @@
+use derivative::Derivative;
+use pretty::DocAllocator;
+
+use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_printer::{
+    Alloc, Builder, Precedence, Print, PrintCfg,
+    theme::ThemeExt,
+    tokens::{ABSURD, COMMA, DOT, FAT_ARROW, MATCH, UNDERSCORE},
+};
+
+use super::{Exp, IdBind, IdBound, Literal, MetaVar, Motive, print_case_items};
+use crate::{
+    Comments, ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRange,
+    ShiftRangeExt, Subst, Substitutable, VarBind, Zonk, ZonkError,
+    ctx::{BindContext, LevelCtx},
+    rename::{Rename, RenameCtx},
+};
+
+// SourcePattern
+//
+//
+
+/// A pattern as it is written in the source
+#[derive(Debug, Clone)]
+pub enum SourcePattern {
+    Wildcard,
+    Var(VarBind),
+    Ctor {
+        name: IdBound,
+        params: Vec<SourcePattern>,
+    },
+    Literal(Literal),
+    /// The patterns of a case of a match on several scrutinees, e.g. `(Z, S(y))`
+    Tuple(Vec<SourcePattern>),
+}
+
+impl SourcePattern {
+    /// The variables bound by the pattern, from left to right
+    pub fn vars(&self) -> Vec<&VarBind> {
+        let mut vars = vec![];
+        self.collect_vars(&mut vars);
+        vars
+    }
+
+    fn collect_vars<'a>(&'a self, vars: &mut Vec<&'a VarBind>) {
+        match self {
+            SourcePattern::Wildcard | SourcePattern::Literal(_) => {}
+            SourcePattern::Var(var) => vars.push(var),
+            SourcePattern::Ctor { params, .. } | SourcePattern::Tuple(params) => {
+                params.iter().for_each(|param| param.collect_vars(vars))
+            }
+        }
+    }
+
+    fn vars_mut(&mut self) -> Vec<&mut VarBind> {
+        let mut vars = vec![];
+        self.collect_vars_mut(&mut vars);
+        vars
+    }
+
+    fn collect_vars_mut<'a>(&'a mut self, vars: &mut Vec<&'a mut VarBind>) {
+        match self {
+            SourcePattern::Wildcard | SourcePattern::Literal(_) => {}
+            SourcePattern::Var(var) => vars.push(var),
+            SourcePattern::Ctor { params, .. } | SourcePattern::Tuple(params) => {
+                params.iter_mut().for_each(|param| param.collect_vars_mut(vars))
+            }
+        }
+    }
+}
+
+impl Print for SourcePattern {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let print_params = |params: &'a [SourcePattern]| {
+            let sep = alloc.text(COMMA).append(alloc.space());
+            alloc.intersperse(params.iter().map(|param| param.print(cfg, alloc)), sep).parens()
+        };
+        match self {
+            SourcePattern::Wildcard => alloc.text(UNDERSCORE),
+            SourcePattern::Var(var) => var.print(cfg, alloc),
+            SourcePattern::Ctor { name, params } if params.is_empty() => alloc.ctor(&name.id),
+            SourcePattern::Ctor { name, params } => {
+                alloc.ctor(&name.id).append(print_params(params))
+            }
+            SourcePattern::Literal(lit) => lit.print(cfg, alloc),
+            SourcePattern::Tuple(params) => print_params(params),
+        }
+    }
+}
+
+// SourceCase
+//
+//
+
+/// A case as it is written in the source, whose patterns have been compiled by the lowering
+///
+/// The variables of the pattern are bound from left to right, each by a telescope of its own.
+#[derive(Debug, Clone)]
+pub struct SourceCase {
+    pub span: Option<Span>,
+    pub comments: Comments,
+    pub pattern: SourcePattern,
+    /// Body being `None` represents an absurd pattern
+    pub body: Option<Box<Exp>>,
+}
+
+/// Bind the variables of a source case in the same way as the lowering
+fn bind_vars<C, O, F>(ctx: &mut C, vars: &[&VarBind], f: F) -> O
+where
+    C: BindContext<Content = ()>,
+    F: FnOnce(&mut C) -> O,
+{
+    match vars.split_first() {
+        None => f(ctx),
+        Some((var, vars)) => ctx.bind_single((*var).clone(), |ctx| bind_vars(ctx, vars, f)),
+    }
+}
+
+impl Shift for SourceCase {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        let n = self.pattern.vars().len() as isize;
+        self.body.shift_in_range(&range.clone().shift(n), by);
+    }
+}
+
+impl Substitutable for SourceCase {
+    type Target = SourceCase;
+    fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
+        let SourceCase { span, comments, pattern, body } = self;
+        let vars = pattern.vars();
+        bind_vars(ctx, &vars, |ctx| SourceCase {
+            span: *span,
+            comments: comments.clone(),
+            pattern: pattern.clone(),
+            body: body.as_ref().map(|body| {
+                let mut subst = (*subst).clone();
+                subst.shift((vars.len() as isize, 0));
+                body.subst(ctx, &subst)
+            }),
+        })
+    }
+}
+
+impl Print for SourceCase {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let SourceCase { span: _, comments: _, pattern, body } = self;
+
+        let body = match body {
+            None => alloc.keyword(ABSURD),
+            Some(body) => alloc
+                .text(FAT_ARROW)
+                .append(alloc.line())
+                .append(body.print(cfg, alloc))
+                .nest(cfg.indent),
+        };
+
+        pattern.print(cfg, alloc).append(alloc.space()).append(body).group()
+    }
+}
+
+impl Rename for SourceCase {
+    fn rename_in_ctx(&mut self, ctx: &mut RenameCtx) {
+        let SourceCase { span: _, comments: _, pattern, body } = self;
+        rename_vars(ctx, &mut pattern.vars_mut(), |ctx| body.rename_in_ctx(ctx));
+    }
+}
+
+/// Rename the variables of a source case one after another, and rename `f` in their scope
+fn rename_vars<F: FnOnce(&mut RenameCtx)>(ctx: &mut RenameCtx, vars: &mut [&mut VarBind], f: F) {
+    match vars.split_first_mut() {
+        None => f(ctx),
+        Some((var, vars)) => {
+            **var = ctx.disambiguate_var_bind((**var).clone());
+            ctx.bind_single((**var).clone(), |ctx| rename_vars(ctx, vars, f))
+        }
+    }
+}
+
+/// Print the cases of a match as they are written in the source
+pub fn print_source_cases<'a>(
+    cases: &'a [SourceCase],
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    print_case_items(
+        cases.iter().map(|case| (case.print(cfg, alloc), &case.comments)).collect(),
+        cfg,
+        alloc,
+    )
+}
+
+// CompiledMatch
+//
+//
+
+/// A match whose patterns have been compiled by the lowering
+///
+/// A match is compiled if its patterns are nested, if it matches literals, or if it matches several
+/// scrutinees at once as in `match (x, y) { ... }`. Only the compiled expression is typechecked and
+/// evaluated, the match as it is written is kept for printing.
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Eq, PartialEq, Hash)]
+pub struct CompiledMatch {
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub span: Option<Span>,
+    /// The scrutinee of `e.match { ... }`, or the scrutinees of `match (e1, e2) { ... }`
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub on_exps: Vec<Exp>,
+    /// Whether the match is written as `match (e1, e2) { ... }`
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub is_multi: bool,
+    /// The name which the user gave to the match
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub label: Option<IdBind>,
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub motive: Option<Motive>,
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub cases: Vec<SourceCase>,
+    pub compiled: Box<Exp>,
+}
+
+impl HasSpan for CompiledMatch {
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl From<CompiledMatch> for Exp {
+    fn from(val: CompiledMatch) -> Self {
+        Exp::CompiledMatch(val)
+    }
+}
+
+impl Shift for CompiledMatch {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        let CompiledMatch { span: _, on_exps, is_multi: _, label: _, motive, cases, compiled } =
+            self;
+        on_exps.shift_in_range(range, by);
+        motive.shift_in_range(range, by);
+        cases.shift_in_range(range, by);
+        compiled.shift_in_range(range, by);
+    }
+}
+
+impl Occurs for CompiledMatch {
+    fn occurs<F>(&self, ctx: &mut LevelCtx, f: &F) -> bool
+    where
+        F: Fn(&LevelCtx, &Exp) -> bool,
+    {
+        self.compiled.occurs(ctx, f)
+    }
+}
+
+impl HasType for CompiledMatch {
+    fn typ(&self) -> Option<Box<Exp>> {
+        self.compiled.typ()
+    }
+}
+
+impl Substitutable for CompiledMatch {
+    type Target = CompiledMatch;
+    fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
+        let CompiledMatch { span, on_exps, is_multi, label, motive, cases, compiled } = self;
+        CompiledMatch {
+            span: *span,
+            on_exps: on_exps.iter().map(|exp| exp.subst(ctx, subst)).collect(),
+            is_multi: *is_multi,
+            label: label.clone(),
+            motive: motive.as_ref().map(|m| m.subst(ctx, subst)),
+            cases: cases.iter().map(|case| case.subst(ctx, subst)).collect(),
+            compiled: compiled.subst(ctx, subst),
+        }
+    }
+}
+
+impl Print for CompiledMatch {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        _prec: Precedence,
+    ) -> Builder<'a> {
+        let CompiledMatch { on_exps, is_multi, label, motive, cases, .. } = self;
+        let head = if *is_multi {
+            let sep = alloc.text(COMMA).append(alloc.space());
+            alloc.keyword(MATCH).append(alloc.space()).append(
+                alloc.intersperse(on_exps.iter().map(|exp| exp.print(cfg, alloc)), sep).parens(),
+            )
+        } else {
+            let on_exp = on_exps.first().map(|exp| exp.print_prec(cfg, alloc, Precedence::Ops));
+            alloc
+                .nil()
+                .append(on_exp)
+                .append(DOT)
+                .append(alloc.keyword(MATCH))
+                .append(match label {
+                    Some(label) => alloc.space().append(alloc.dtor(&label.id)),
+                    None => alloc.nil(),
+                })
+                .append(motive.as_ref().map(|m| m.print(cfg, alloc)).unwrap_or(alloc.nil()))
+        };
+        head.append(alloc.space()).append(print_source_cases(cases, cfg, alloc))
+    }
+}
+
+/// The source cases are only kept for printing, so only the compiled expression is zonked.
+impl Zonk for CompiledMatch {
+    fn zonk(
+        &mut self,
+        meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+    ) -> Result<(), ZonkError> {
+        self.compiled.zonk(meta_vars)
+    }
+}
+
+impl ContainsMetaVars for CompiledMatch {
+    fn contains_metavars(&self) -> bool {
+        self.compiled.contains_metavars()
+    }
+}
+
+impl Rename for CompiledMatch {
+    fn rename_in_ctx(&mut self, ctx: &mut RenameCtx) {
+        let CompiledMatch { span: _, on_exps, is_multi: _, label: _, motive, cases, compiled } =
+            self;
+        on_exps.rename_in_ctx(ctx);
+        motive.rename_in_ctx(ctx);
+        cases.rename_in_ctx(ctx);
+        compiled.rename_in_ctx(ctx);
+    }
+}
+
+impl FreeVars for CompiledMatch {
+    fn free_vars_mut(&self, ctx: &LevelCtx, cutoff: usize, fvs: &mut crate::HashSet<crate::Lvl>) {
+        self.compiled.free_vars_mut(ctx, cutoff, fvs)
+    }
+}
@@ -11,8 +11,8 @@ use pretty::DocAllocator;
 
 use super::{Exp, VarBind};
 use crate::{
-    ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRangeExt, Subst,
-    Substitutable, Zonk,
+    Comments, CommentsExt, ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift,
+    ShiftRangeExt, Subst, Substitutable, Zonk,
     ctx::{BindContext, LevelCtx},
     rename::Rename,
 };
@@ -45,6 +45,8 @@ pub enum DoStatements {
     Bind {
         #[derivative(PartialEq = "ignore", Hash = "ignore")]
         span: Span,
+        #[derivative(PartialEq = "ignore", Hash = "ignore")]
+        comments: Comments,
 
         name: VarBind,
         bound: Box<Exp>,
@@ -56,6 +58,8 @@ pub enum DoStatements {
     Let {
         #[derivative(PartialEq = "ignore", Hash = "ignore")]
         span: Span,
+        #[derivative(PartialEq = "ignore", Hash = "ignore")]
+        comments: Comments,
 
         name: VarBind,
         typ: Option<Box<Exp>>,
@@ -68,6 +72,8 @@ pub enum DoStatements {
     Return {
         #[derivative(PartialEq = "ignore", Hash = "ignore")]
         span: Span,
+        #[derivative(PartialEq = "ignore", Hash = "ignore")]
+        comments: Comments,
 
         exp: Box<Exp>,
 
@@ -76,6 +82,28 @@ pub enum DoStatements {
     },
 }
 
+impl DoStatements {
+    /// The comments attached to the first statement
+    pub fn comments(&self) -> &Comments {
+        match self {
+            DoStatements::Bind { comments, .. } => comments,
+            DoStatements::Let { comments, .. } => comments,
+            DoStatements::Return { comments, .. } => comments,
+        }
+    }
+
+    /// Whether comments are attached to any of the statements
+    pub fn has_comments(&self) -> bool {
+        !self.comments().is_empty()
+            || match self {
+                DoStatements::Bind { body, .. } | DoStatements::Let { body, .. } => {
+                    body.has_comments()
+                }
+                DoStatements::Return { .. } => false,
+            }
+    }
+}
+
 impl HasSpan for DoBlock {
     fn span(&self) -> Option<Span> {
         Some(self.span)
@@ -93,18 +121,26 @@ impl Shift for DoBlock {
 impl Shift for DoStatements {
     fn shift_in_range<R: crate::ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
         match self {
-            DoStatements::Bind { span: _, name: _, bound, body, inferred_type } => {
+            DoStatements::Bind { span: _, comments: _, name: _, bound, body, inferred_type } => {
                 bound.shift_in_range(range, by);
                 body.shift_in_range(&range.clone().shift(1), by);
                 *inferred_type = None;
             }
-            DoStatements::Let { span: _, name: _, typ, bound, body, inferred_type } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name: _,
+                typ,
+                bound,
+                body,
+                inferred_type,
+            } => {
                 typ.shift_in_range(range, by);
                 bound.shift_in_range(range, by);
                 body.shift_in_range(&range.clone().shift(1), by);
                 *inferred_type = None;
             }
-            DoStatements::Return { span: _, exp, inferred_type } => {
+            DoStatements::Return { span: _, comments: _, exp, inferred_type } => {
                 exp.shift_in_range(range, by);
                 *inferred_type = None;
             }
@@ -128,15 +164,25 @@ impl Occurs for DoStatements {
         F: Fn(&LevelCtx, &Exp) -> bool,
     {
         match self {
-            DoStatements::Bind { span: _, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span: _, comments: _, name, bound, body, inferred_type: _ } => {
                 bound.occurs(ctx, f) || ctx.bind_single(name.clone(), |ctx| body.occurs(ctx, f))
             }
-            DoStatements::Let { span: _, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name,
+                typ,
+                bound,
+                body,
+                inferred_type: _,
+            } => {
                 typ.as_ref().is_some_and(|t| t.occurs(ctx, f))
                     || bound.occurs(ctx, f)
                     || ctx.bind_single(name.clone(), |ctx| body.occurs(ctx, f))
             }
-            DoStatements::Return { span: _, exp, inferred_type: _ } => exp.occurs(ctx, f),
+            DoStatements::Return { span: _, comments: _, exp, inferred_type: _ } => {
+                exp.occurs(ctx, f)
+            }
         }
     }
 }
@@ -171,13 +217,14 @@ impl Substitutable for DoStatements {
 
     fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
         match self {
-            DoStatements::Bind { span, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span, comments, name, bound, body, inferred_type: _ } => {
                 let bound = bound.subst(ctx, subst);
                 ctx.bind_single(name.clone(), |ctx| {
                     let mut subst = (*subst).clone();
                     subst.shift((1, 0));
                     DoStatements::Bind {
                         span: *span,
+                        comments: comments.clone(),
                         name: name.clone(),
                         bound,
                         body: body.subst(ctx, &subst),
@@ -185,7 +232,7 @@ impl Substitutable for DoStatements {
                     }
                 })
             }
-            DoStatements::Let { span, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let { span, comments, name, typ, bound, body, inferred_type: _ } => {
                 let typ = typ.subst(ctx, subst);
                 let bound = bound.subst(ctx, subst);
                 ctx.bind_single(name.clone(), |ctx| {
@@ -193,6 +240,7 @@ impl Substitutable for DoStatements {
                     subst.shift((1, 0));
                     DoStatements::Let {
                         span: *span,
+                        comments: comments.clone(),
                         name: name.clone(),
                         typ,
                         bound,
@@ -201,9 +249,14 @@ impl Substitutable for DoStatements {
                     }
                 })
             }
-            DoStatements::Return { span, exp, inferred_type: _ } => {
+            DoStatements::Return { span, comments, exp, inferred_type: _ } => {
                 let exp = exp.subst(ctx, subst);
-                DoStatements::Return { span: *span, exp, inferred_type: None }
+                DoStatements::Return {
+                    span: *span,
+                    comments: comments.clone(),
+                    exp,
+                    inferred_type: None,
+                }
             }
         }
     }
@@ -218,11 +271,13 @@ impl Print for DoBlock {
     ) -> polarity_lang_printer::Builder<'a> {
         let DoBlock { span: _, statements, inferred_type: _ } = self;
 
-        let body = alloc
-            .line()
+        // An end-of-line comment must not be followed by the closing brace on the same line.
+        let line = if statements.has_comments() { alloc.hardline() } else { alloc.line() };
+        let body = line
+            .clone()
             .append(statements.print(cfg, alloc))
             .nest(cfg.indent)
-            .append(alloc.line())
+            .append(line)
             .braces_anno();
 
         alloc.keyword(DO).append(alloc.space()).append(body)
@@ -237,7 +292,7 @@ impl Print for DoStatements {
         _prec: polarity_lang_printer::Precedence,
     ) -> polarity_lang_printer::Builder<'a> {
         match self {
-            DoStatements::Bind { span: _, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span: _, comments, name, bound, body, inferred_type: _ } => {
                 let head = name
                     .print(cfg, alloc)
                     .append(alloc.space())
@@ -249,9 +304,9 @@ impl Print for DoStatements {
 
                 let body = body.print_prec(cfg, alloc, Precedence::Exp);
 
-                head.append(alloc.hardline()).append(body)
+                comments.print_around(head, alloc).append(alloc.hardline()).append(body)
             }
-            DoStatements::Let { span: _, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let { span: _, comments, name, typ, bound, body, inferred_type: _ } => {
                 let typ = typ.as_ref().map(|t| {
                     alloc.text(COLON).append(alloc.space()).append(t.print_prec(
                         cfg,
@@ -274,10 +329,10 @@ impl Print for DoStatements {
 
                 let body = body.print_prec(cfg, alloc, Precedence::Exp);
 
-                head.append(alloc.hardline()).append(body)
+                comments.print_around(head, alloc).append(alloc.hardline()).append(body)
             }
-            DoStatements::Return { span: _, exp, inferred_type: _ } => {
-                exp.print_prec(cfg, alloc, Precedence::Exp)
+            DoStatements::Return { span: _, comments, exp, inferred_type: _ } => {
+                comments.print_around(exp.print_prec(cfg, alloc, Precedence::Exp), alloc)
             }
         }
     }
@@ -299,18 +354,28 @@ impl Zonk for DoStatements {
         meta_vars: &crate::HashMap<crate::MetaVar, crate::MetaVarState>,
     ) -> Result<(), crate::ZonkError> {
         match self {
-            DoStatements::Bind { span: _, name: _, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span: _, comments: _, name: _, bound, body, inferred_type: _ } => {
                 bound.zonk(meta_vars)?;
                 body.zonk(meta_vars)?;
                 Ok(())
             }
-            DoStatements::Let { span: _, name: _, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name: _,
+                typ,
+                bound,
+                body,
+                inferred_type: _,
+            } => {
                 typ.zonk(meta_vars)?;
                 bound.zonk(meta_vars)?;
                 body.zonk(meta_vars)?;
                 Ok(())
             }
-            DoStatements::Return { span: _, exp, inferred_type: _ } => exp.zonk(meta_vars),
+            DoStatements::Return { span: _, comments: _, exp, inferred_type: _ } => {
+                exp.zonk(meta_vars)
+            }
         }
     }
 }
@@ -325,18 +390,26 @@ impl ContainsMetaVars for DoBlock {
 impl ContainsMetaVars for DoStatements {
     fn contains_metavars(&self) -> bool {
         match self {
-            DoStatements::Bind { span: _, name: _, bound, body, inferred_type } => {
+            DoStatements::Bind { span: _, comments: _, name: _, bound, body, inferred_type } => {
                 bound.contains_metavars()
                     || body.contains_metavars()
                     || inferred_type.contains_metavars()
             }
-            DoStatements::Let { span: _, name: _, typ, bound, body, inferred_type } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name: _,
+                typ,
+                bound,
+                body,
+                inferred_type,
+            } => {
                 typ.contains_metavars()
                     || bound.contains_metavars()
                     || body.contains_metavars()
                     || inferred_type.contains_metavars()
             }
-            DoStatements::Return { span: _, exp, inferred_type } => {
+            DoStatements::Return { span: _, comments: _, exp, inferred_type } => {
                 exp.contains_metavars() || inferred_type.contains_metavars()
             }
         }
@@ -353,20 +426,30 @@ impl Rename for DoBlock {
 impl Rename for DoStatements {
     fn rename_in_ctx(&mut self, ctx: &mut crate::rename::RenameCtx) {
         match self {
-            DoStatements::Bind { span: _, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span: _, comments: _, name, bound, body, inferred_type: _ } => {
                 bound.rename_in_ctx(ctx);
                 ctx.bind_single(name.clone(), |ctx| {
                     body.rename_in_ctx(ctx);
                 })
             }
-            DoStatements::Let { span: _, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name,
+                typ,
+                bound,
+                body,
+                inferred_type: _,
+            } => {
                 typ.rename_in_ctx(ctx);
                 bound.rename_in_ctx(ctx);
                 ctx.bind_single(name.clone(), |ctx| {
                     body.rename_in_ctx(ctx);
                 })
             }
-            DoStatements::Return { span: _, exp, inferred_type: _ } => exp.rename_in_ctx(ctx),
+            DoStatements::Return { span: _, comments: _, exp, inferred_type: _ } => {
+                exp.rename_in_ctx(ctx)
+            }
         }
     }
 }
@@ -387,16 +470,24 @@ impl FreeVars for DoBlock {
 impl FreeVars for DoStatements {
     fn free_vars_mut(&self, ctx: &LevelCtx, cutoff: usize, fvs: &mut crate::HashSet<crate::Lvl>) {
         match self {
-            DoStatements::Bind { span: _, name: _, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span: _, comments: _, name: _, bound, body, inferred_type: _ } => {
                 bound.free_vars_mut(ctx, cutoff, fvs);
                 body.free_vars_mut(ctx, cutoff + 1, fvs);
             }
-            DoStatements::Let { span: _, name: _, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name: _,
+                typ,
+                bound,
+                body,
+                inferred_type: _,
+            } => {
                 typ.free_vars_mut(ctx, cutoff, fvs);
                 bound.free_vars_mut(ctx, cutoff, fvs);
                 body.free_vars_mut(ctx, cutoff + 1, fvs);
             }
-            DoStatements::Return { span: _, exp, inferred_type: _ } => {
+            DoStatements::Return { span: _, comments: _, exp, inferred_type: _ } => {
                 exp.free_vars_mut(ctx, cutoff, fvs)
             }
         }
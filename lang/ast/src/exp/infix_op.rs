@@ -0,0 +1,100 @@
+use pretty::DocAllocator;
+
+use polarity_lang_printer::{Alloc, Builder, Precedence, Print, PrintCfg, util::ParensIfExt};
+
+use super::{Arg, Exp};
+
+/// The associativity of an infix operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    /// Declared with `infixl`: `a - b - c` means `(a - b) - c`
+    Left,
+    /// Declared with `infixr`: `a -> b -> c` means `a -> (b -> c)`
+    Right,
+    /// Declared with `infix`: `a == b == c` is rejected
+    NonAssoc,
+}
+
+/// The associativity and the precedence level of an infix operator
+///
+/// Operators with a higher precedence level bind more tightly than operators with a lower one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fixity {
+    pub assoc: Associativity,
+    pub precedence: u32,
+}
+
+impl Fixity {
+    /// The precedence level of operators whose declaration does not specify one
+    pub const DEFAULT_PRECEDENCE: u32 = 9;
+
+    /// Whether an application of an operator with the fixity `operand` can occur without parentheses
+    /// as the left (`is_lhs`) or right operand of an operator with this fixity
+    pub fn allows_operand(&self, operand: &Fixity, is_lhs: bool) -> bool {
+        if operand.precedence != self.precedence {
+            return operand.precedence > self.precedence;
+        }
+        let assoc = if is_lhs { Associativity::Left } else { Associativity::Right };
+        self.assoc == assoc && operand.assoc == assoc
+    }
+}
+
+/// An infix operator from which a `Call` or `TypCtor` has been lowered
+///
+/// If the user has written `a -> b`, the operator is `->` together with its declared fixity.
+#[derive(Debug, Clone)]
+pub struct InfixOp {
+    pub id: String,
+    pub fixity: Fixity,
+}
+
+/// An operand of an infix operator
+///
+/// Operands which are themselves applications of infix operators report their operator,
+/// so that they are only put in parentheses where this is necessary.
+pub trait InfixOperand: Print {
+    /// The operator of which this operand is an application, if any
+    fn infix_op(&self) -> Option<&InfixOp>;
+}
+
+impl InfixOperand for Arg {
+    fn infix_op(&self) -> Option<&InfixOp> {
+        match self {
+            Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => match &**arg {
+                Exp::Call(call) => call.is_bin_op.as_ref(),
+                Exp::TypCtor(typ_ctor) => typ_ctor.is_bin_op.as_ref(),
+                _ => None,
+            },
+            Arg::InsertedImplicitArg { .. } => None,
+        }
+    }
+}
+
+impl InfixOp {
+    /// Print the application `lhs op rhs` of this operator
+    ///
+    /// Operands which are applications of operators are put in parentheses exactly if
+    /// they would otherwise be parsed differently.
+    pub fn print_app<'a, A: InfixOperand>(
+        &'a self,
+        lhs: &'a A,
+        rhs: &'a A,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
+        let lhs = lhs.print_prec(cfg, alloc, self.operand_prec(lhs, true));
+        let rhs = rhs.print_prec(cfg, alloc, self.operand_prec(rhs, false));
+        let app = lhs.append(alloc.space()).append(&self.id).append(alloc.space()).append(rhs);
+        app.parens_if(prec > Precedence::NonLet)
+    }
+
+    fn operand_prec<A: InfixOperand>(&self, operand: &A, is_lhs: bool) -> Precedence {
+        match operand.infix_op() {
+            Some(op) if self.fixity.allows_operand(&op.fixity, is_lhs) => Precedence::NonLet,
+            Some(_) => Precedence::Ops,
+            None if is_lhs => Precedence::Ops,
+            None => Precedence::Exp,
+        }
+    }
+}
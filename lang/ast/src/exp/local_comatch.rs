@@ -102,7 +102,7 @@ impl Substitutable for LocalComatch {
 /// one cocase "ap" with three arguments; the function will
 /// panic otherwise.
 fn print_lambda_sugar<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-    let Case { span: _, pattern, body } =
+    let Case { span: _, comments: _, pattern, body, from_wildcard: _ } =
         cases.first().expect("Empty comatch marked as lambda sugar");
 
     let body = match body {
@@ -10,8 +10,8 @@ use polarity_lang_printer::{
 
 use super::{Exp, VarBind};
 use crate::{
-    ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRangeExt, Subst,
-    Substitutable, Zonk,
+    Comments, CommentsExt, ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift,
+    ShiftRangeExt, Subst, Substitutable, Zonk,
     ctx::{BindContext, LevelCtx},
     rename::Rename,
 };
@@ -26,6 +26,9 @@ use crate::{
 pub struct LocalLet {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub span: Span,
+    /// The comments of the line `let x := e;`
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub comments: Comments,
     /// The name of the variable being bound.
     pub name: VarBind,
     /// Optionally annotated type of the bound expression.
@@ -47,7 +50,7 @@ impl HasSpan for LocalLet {
 
 impl Shift for LocalLet {
     fn shift_in_range<R: crate::ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
-        let LocalLet { span: _, name: _, typ, bound, body, inferred_type } = self;
+        let LocalLet { span: _, comments: _, name: _, typ, bound, body, inferred_type } = self;
 
         typ.shift_in_range(range, by);
         bound.shift_in_range(range, by);
@@ -61,7 +64,7 @@ impl Occurs for LocalLet {
     where
         F: Fn(&crate::ctx::LevelCtx, &Exp) -> bool,
     {
-        let LocalLet { span: _, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name, typ, bound, body, inferred_type: _ } = self;
         typ.as_ref().is_some_and(|t| t.occurs(ctx, f))
             || bound.occurs(ctx, f)
             || ctx.bind_single(name.clone(), |ctx| body.occurs(ctx, f))
@@ -78,7 +81,7 @@ impl Substitutable for LocalLet {
     type Target = LocalLet;
 
     fn subst(&self, ctx: &mut LevelCtx, subst: &Subst) -> Self::Target {
-        let LocalLet { span, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span, comments, name, typ, bound, body, inferred_type: _ } = self;
 
         let typ = typ.subst(ctx, subst);
         let bound = bound.subst(ctx, subst);
@@ -88,6 +91,7 @@ impl Substitutable for LocalLet {
             subst.shift((1, 0));
             LocalLet {
                 span: *span,
+                comments: comments.clone(),
                 name: name.clone(),
                 typ,
                 bound,
@@ -105,7 +109,7 @@ impl Print for LocalLet {
         alloc: &'a polarity_lang_printer::Alloc<'a>,
         _prec: polarity_lang_printer::Precedence,
     ) -> polarity_lang_printer::Builder<'a> {
-        let LocalLet { span: _, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments, name, typ, bound, body, inferred_type: _ } = self;
 
         let typ = typ.as_ref().map(|t| {
             alloc.text(COLON).append(alloc.space()).append(t.print_prec(
@@ -128,7 +132,7 @@ impl Print for LocalLet {
 
         let body = body.print_prec(cfg, alloc, Precedence::Exp);
 
-        head.append(alloc.hardline()).append(body)
+        comments.print_around(head, alloc).append(alloc.hardline()).append(body)
     }
 }
 
@@ -137,7 +141,7 @@ impl Zonk for LocalLet {
         &mut self,
         meta_vars: &crate::HashMap<crate::MetaVar, crate::MetaVarState>,
     ) -> Result<(), crate::ZonkError> {
-        let LocalLet { span: _, name: _, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name: _, typ, bound, body, inferred_type: _ } = self;
         typ.zonk(meta_vars)?;
         bound.zonk(meta_vars)?;
         body.zonk(meta_vars)?;
@@ -147,7 +151,7 @@ impl Zonk for LocalLet {
 
 impl ContainsMetaVars for LocalLet {
     fn contains_metavars(&self) -> bool {
-        let LocalLet { span: _, name: _, typ, bound, body, inferred_type } = self;
+        let LocalLet { span: _, comments: _, name: _, typ, bound, body, inferred_type } = self;
         typ.contains_metavars()
             || bound.contains_metavars()
             || body.contains_metavars()
@@ -157,11 +161,15 @@ impl ContainsMetaVars for LocalLet {
 
 impl Rename for LocalLet {
     fn rename_in_ctx(&mut self, ctx: &mut crate::rename::RenameCtx) {
-        let LocalLet { span: _, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name, typ, bound, body, inferred_type: _ } = self;
 
         typ.rename_in_ctx(ctx);
         bound.rename_in_ctx(ctx);
 
+        // Generated code may refer to a let binding without a name, so it is named like the wildcards of patterns.
+        if let VarBind::Wildcard { .. } = name {
+            *name = ctx.disambiguate_var_bind(name.clone());
+        }
         ctx.bind_single(name.clone(), |ctx| {
             body.rename_in_ctx(ctx);
         })
@@ -176,9 +184,73 @@ impl From<LocalLet> for Exp {
 
 impl FreeVars for LocalLet {
     fn free_vars_mut(&self, ctx: &LevelCtx, cutoff: usize, fvs: &mut crate::HashSet<crate::Lvl>) {
-        let LocalLet { span: _, name: _, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name: _, typ, bound, body, inferred_type: _ } = self;
         bound.free_vars_mut(ctx, cutoff, fvs);
         typ.free_vars_mut(ctx, cutoff, fvs);
         body.free_vars_mut(ctx, cutoff + 1, fvs);
     }
 }
+
+#[cfg(test)]
+mod local_let_tests {
+    use crate::{Exp, Idx, TypeUniv, VarBind, VarBound, Variable, rename::Rename};
+
+    use super::LocalLet;
+
+    fn var(fst: usize) -> Box<Exp> {
+        Box::new(
+            Variable {
+                span: None,
+                idx: Idx { fst, snd: 0 },
+                name: VarBound::from_string(""),
+                inferred_type: None,
+                erased: false,
+            }
+            .into(),
+        )
+    }
+
+    /// Rename `let x := Type; let <name> := x; <body>` and return the name of the inner binding and the body
+    fn rename_inner(name: VarBind, body: Box<Exp>) -> (VarBind, Exp) {
+        let inner = LocalLet {
+            span: Default::default(),
+            comments: Default::default(),
+            name,
+            typ: None,
+            bound: var(0),
+            body,
+            inferred_type: None,
+        };
+        let mut outer = LocalLet {
+            span: Default::default(),
+            comments: Default::default(),
+            name: VarBind::from_string("x"),
+            typ: None,
+            bound: Box::new(TypeUniv { span: None }.into()),
+            body: Box::new(inner.into()),
+            inferred_type: None,
+        };
+        outer.rename();
+        let Exp::LocalLet(inner) = *outer.body else { panic!("Expected a let binding") };
+        (inner.name, *inner.body)
+    }
+
+    fn var_name(exp: &Exp) -> &str {
+        let Exp::Variable(Variable { name, .. }) = exp else { panic!("Expected a variable") };
+        &name.id
+    }
+
+    #[test]
+    fn rename_wildcard() {
+        let (name, body) = rename_inner(VarBind::Wildcard { span: None }, var(1));
+        assert_eq!(name, VarBind::from_string("x0"));
+        assert_eq!(var_name(&body), "x");
+    }
+
+    #[test]
+    fn rename_keeps_names() {
+        let (name, body) = rename_inner(VarBind::from_string("x"), var(0));
+        assert_eq!(name, VarBind::from_string("x"));
+        assert_eq!(var_name(&body), "x");
+    }
+}
@@ -21,9 +21,11 @@ mod args;
 mod call;
 mod case;
 mod closure;
+mod compiled_match;
 mod do_block;
 mod dot_call;
 mod hole;
+mod infix_op;
 mod literal;
 mod local_comatch;
 mod local_let;
@@ -38,9 +40,11 @@ pub use args::*;
 pub use call::*;
 pub use case::*;
 pub use closure::*;
+pub use compiled_match::*;
 pub use do_block::*;
 pub use dot_call::*;
 pub use hole::*;
+pub use infix_op::*;
 pub use literal::*;
 pub use local_comatch::*;
 pub use local_let::*;
@@ -88,6 +92,7 @@ pub enum Exp {
     LocalLet(LocalLet),
     DoBlock(DoBlock),
     Literal(Literal),
+    CompiledMatch(CompiledMatch),
 }
 
 impl Exp {
@@ -140,6 +145,7 @@ impl HasSpan for Exp {
             Exp::LocalLet(e) => e.span(),
             Exp::DoBlock(e) => e.span(),
             Exp::Literal(e) => e.span(),
+            Exp::CompiledMatch(e) => e.span(),
         }
     }
 }
@@ -159,6 +165,7 @@ impl Shift for Exp {
             Exp::LocalLet(e) => e.shift_in_range(range, by),
             Exp::DoBlock(e) => e.shift_in_range(range, by),
             Exp::Literal(e) => e.shift_in_range(range, by),
+            Exp::CompiledMatch(e) => e.shift_in_range(range, by),
         }
     }
 }
@@ -184,6 +191,7 @@ impl Occurs for Exp {
             Exp::LocalLet(e) => e.occurs(ctx, f),
             Exp::DoBlock(e) => e.occurs(ctx, f),
             Exp::Literal(e) => e.occurs(ctx, f),
+            Exp::CompiledMatch(e) => e.occurs(ctx, f),
         }
     }
 }
@@ -203,6 +211,7 @@ impl HasType for Exp {
             Exp::LocalLet(e) => e.typ(),
             Exp::DoBlock(e) => e.typ(),
             Exp::Literal(e) => e.typ(),
+            Exp::CompiledMatch(e) => e.typ(),
         }
     }
 }
@@ -223,6 +232,7 @@ impl Substitutable for Exp {
             Exp::LocalLet(e) => e.subst(ctx, subst).into(),
             Exp::DoBlock(e) => e.subst(ctx, subst).into(),
             Exp::Literal(e) => e.subst(ctx, subst).into(),
+            Exp::CompiledMatch(e) => e.subst(ctx, subst).into(),
         }
     }
 }
@@ -246,6 +256,7 @@ impl Print for Exp {
             Exp::LocalLet(e) => e.print_prec(cfg, alloc, prec),
             Exp::DoBlock(e) => e.print_prec(cfg, alloc, prec),
             Exp::Literal(e) => e.print_prec(cfg, alloc, prec),
+            Exp::CompiledMatch(e) => e.print_prec(cfg, alloc, prec),
         }
     }
 }
@@ -268,6 +279,7 @@ impl Zonk for Exp {
             Exp::LocalLet(e) => e.zonk(meta_vars),
             Exp::DoBlock(e) => e.zonk(meta_vars),
             Exp::Literal(e) => e.zonk(meta_vars),
+            Exp::CompiledMatch(e) => e.zonk(meta_vars),
         }
     }
 }
@@ -287,6 +299,7 @@ impl ContainsMetaVars for Exp {
             Exp::LocalLet(local_let) => local_let.contains_metavars(),
             Exp::DoBlock(local_let) => local_let.contains_metavars(),
             Exp::Literal(literal) => literal.contains_metavars(),
+            Exp::CompiledMatch(e) => e.contains_metavars(),
         }
     }
 }
@@ -306,6 +319,7 @@ impl Rename for Exp {
             Exp::LocalLet(e) => e.rename_in_ctx(ctx),
             Exp::DoBlock(e) => e.rename_in_ctx(ctx),
             Exp::Literal(e) => e.rename_in_ctx(ctx),
+            Exp::CompiledMatch(e) => e.rename_in_ctx(ctx),
         }
     }
 }
@@ -325,6 +339,7 @@ impl FreeVars for Exp {
             Exp::LocalLet(e) => e.free_vars_mut(ctx, cutoff, fvs),
             Exp::DoBlock(e) => e.free_vars_mut(ctx, cutoff, fvs),
             Exp::Literal(e) => e.free_vars_mut(ctx, cutoff, fvs),
+            Exp::CompiledMatch(e) => e.free_vars_mut(ctx, cutoff, fvs),
         }
     }
 }
@@ -1,12 +1,9 @@
 use derivative::Derivative;
-use pretty::DocAllocator;
 
 use polarity_lang_miette_util::codespan::Span;
-use polarity_lang_printer::{
-    Alloc, Builder, Precedence, Print, PrintCfg, theme::ThemeExt, util::ParensIfExt,
-};
+use polarity_lang_printer::{Alloc, Builder, Precedence, Print, PrintCfg, theme::ThemeExt};
 
-use super::{Args, Exp, IdBound, MetaVar, TypeUniv};
+use super::{Args, Exp, IdBound, InfixOp, MetaVar, TypeUniv};
 use crate::{
     ContainsMetaVars, FreeVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Subst, Substitutable,
     Zonk, ZonkError,
@@ -29,9 +26,9 @@ pub struct TypCtor {
     pub args: Args,
     /// If this TypCtor has been lowered from a binary operator.
     ///
-    /// If the user has written "->" then we populate this field with `Some("->")`
+    /// If the user has written "->" then we populate this field with the operator `->`
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
-    pub is_bin_op: Option<String>,
+    pub is_bin_op: Option<InfixOp>,
 }
 
 impl TypCtor {
@@ -103,12 +100,7 @@ impl Print for TypCtor {
         match is_bin_op {
             Some(op) if cfg.print_function_sugar => {
                 assert!(args.len() == 2);
-                // TODO: Currently this only works for right associative operators at the same precedence level (e.g. ->).
-                // We still need to properly implement precedence for user-defined operators.
-                let arg = args.args[0].print_prec(cfg, alloc, Precedence::Ops);
-                let res = args.args[1].print_prec(cfg, alloc, Precedence::Exp);
-                let fun = arg.append(alloc.space()).append(op).append(alloc.space()).append(res);
-                fun.parens_if(prec > Precedence::NonLet)
+                op.print_app(&args.args[0], &args.args[1], cfg, alloc, prec)
             }
             _ => alloc
                 .typ(&name.id)
@@ -22,6 +22,9 @@ impl ToIR for polarity_lang_ast::Exp {
             polarity_lang_ast::Exp::LocalComatch(local_comatch) => {
                 ir::Exp::LocalComatch(local_comatch.to_ir()?)
             }
+            polarity_lang_ast::Exp::CompiledMatch(compiled_match) => {
+                compiled_match.compiled.to_ir()?
+            }
             polarity_lang_ast::Exp::Hole(hole) => hole.to_ir()?,
             polarity_lang_ast::Exp::LocalLet(local_let) => ir::Exp::LocalLet(local_let.to_ir()?),
             polarity_lang_ast::Exp::DoBlock(do_block) => ir::Exp::DoBlock(do_block.to_ir()?),
@@ -168,7 +171,7 @@ impl ToIR for LocalLet {
     type Target = ir::LocalLet;
 
     fn to_ir(&self) -> BackendResult<Self::Target> {
-        let LocalLet { span: _, name, typ: _, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name, typ: _, bound, body, inferred_type: _ } = self;
 
         Ok(ir::LocalLet {
             name: name.to_string().into(),
@@ -189,7 +192,14 @@ impl ToIR for polarity_lang_ast::DoBlock {
         let return_exp = loop {
             use polarity_lang_ast::DoStatements;
             match current_statements {
-                DoStatements::Bind { span: _, name, bound, body, inferred_type: _ } => {
+                DoStatements::Bind {
+                    span: _,
+                    comments: _,
+                    name,
+                    bound,
+                    body,
+                    inferred_type: _,
+                } => {
                     let binding = ir::DoBinding::Bind {
                         name: name.to_string().into(),
                         bound: Box::new(bound.to_ir()?),
@@ -197,7 +207,15 @@ impl ToIR for polarity_lang_ast::DoBlock {
                     bindings.push(binding);
                     current_statements = body.as_ref();
                 }
-                DoStatements::Let { span: _, name, typ: _, bound, body, inferred_type: _ } => {
+                DoStatements::Let {
+                    span: _,
+                    comments: _,
+                    name,
+                    typ: _,
+                    bound,
+                    body,
+                    inferred_type: _,
+                } => {
                     let binding = ir::DoBinding::Let {
                         name: name.to_string().into(),
                         bound: Box::new(bound.to_ir()?),
@@ -205,7 +223,12 @@ impl ToIR for polarity_lang_ast::DoBlock {
                     bindings.push(binding);
                     current_statements = body.as_ref();
                 }
-                polarity_lang_ast::DoStatements::Return { span: _, exp, inferred_type: _ } => {
+                polarity_lang_ast::DoStatements::Return {
+                    span: _,
+                    comments: _,
+                    exp,
+                    inferred_type: _,
+                } => {
                     break Box::new(exp.to_ir()?);
                 }
             }
@@ -1,4 +1,4 @@
-use polarity_lang_ast::{Case, Ctor, DocComment, Dtor};
+use polarity_lang_ast::{Case, Ctor, DocComment, Dtor, SourceCase};
 
 use comrak::{Options, markdown_to_html};
 use polarity_lang_printer::PrintCfg;
@@ -11,7 +11,7 @@ pub trait Generate {
 
 impl Generate for Ctor {
     fn generate(&self) -> String {
-        let Ctor { span: _, doc, name, params, typ } = self;
+        let Ctor { span: _, doc, comments: _, name, params, typ_comments: _, typ } = self;
         let parameter = print_html_to_string(params, Some(&PrintCfg::default()));
         let typs = print_html_to_string(typ, Some(&PrintCfg::default()));
 
@@ -26,7 +26,16 @@ impl Generate for Ctor {
 
 impl Generate for Dtor {
     fn generate(&self) -> String {
-        let Dtor { span: _, doc, name, params, self_param, ret_typ } = self;
+        let Dtor {
+            span: _,
+            doc,
+            comments: _,
+            name,
+            params,
+            self_param,
+            ret_typ_comments: _,
+            ret_typ,
+        } = self;
         let self_parameter = print_html_to_string(self_param, Some(&PrintCfg::default()));
         let parmeter = print_html_to_string(params, Some(&PrintCfg::default()));
         let ret_typ = print_html_to_string(ret_typ, Some(&PrintCfg::default()));
@@ -82,6 +91,17 @@ impl Generate for Vec<Case> {
     }
 }
 
+impl Generate for Vec<SourceCase> {
+    fn generate(&self) -> String {
+        self.iter()
+            .map(|value| {
+                format!("<li>{}</li>", print_html_to_string(value, Some(&PrintCfg::default())))
+            })
+            .collect::<Vec<String>>()
+            .join("")
+    }
+}
+
 impl Generate for Vec<Ctor> {
     fn generate(&self) -> String {
         self.iter().map(|value| value.generate()).collect::<Vec<String>>().join("")
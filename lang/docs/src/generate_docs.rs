@@ -1,7 +1,10 @@
 use askama::Template;
 
-use polarity_lang_ast::{Codata, Codef, Data, Decl, Def, Extern, Infix, Let, Module, Note};
+use polarity_lang_ast::{
+    Associativity, Codata, Codef, Data, Decl, Def, Extern, Infix, Let, Module, Note,
+};
 use polarity_lang_printer::PrintCfg;
+use polarity_lang_printer::tokens::{INFIX, INFIXL, INFIXR};
 
 use crate::generate::Generate;
 use crate::printer::print_html_to_string;
@@ -33,7 +36,7 @@ impl GenerateDocs for Decl {
 
 impl GenerateDocs for Data {
     fn generate_docs(&self) -> String {
-        let Data { span: _, doc, name, attr, typ, ctors } = self;
+        let Data { span: _, doc, comments: _, name, attr, typ, ctors } = self;
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
         let attr: String = print_html_to_string(attr, Some(&PrintCfg::default()));
@@ -52,7 +55,7 @@ impl GenerateDocs for Data {
 
 impl GenerateDocs for Codata {
     fn generate_docs(&self) -> String {
-        let Codata { span: _, doc, name, attr, typ, dtors } = self;
+        let Codata { span: _, doc, comments: _, name, attr, typ, dtors } = self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -72,7 +75,19 @@ impl GenerateDocs for Codata {
 
 impl GenerateDocs for Def {
     fn generate_docs(&self) -> String {
-        let Def { span: _, doc, name, attr: _, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span: _,
+            doc,
+            comments: _,
+            name,
+            attr: _,
+            params,
+            self_param,
+            ret_typ_comments: _,
+            ret_typ,
+            cases,
+            source_cases,
+        } = self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -80,10 +95,10 @@ impl GenerateDocs for Def {
         let self_param: String = print_html_to_string(self_param, Some(&PrintCfg::default()));
         let ret_typ: String = print_html_to_string(ret_typ, Some(&PrintCfg::default()));
 
-        let body = if cases.is_empty() {
-            "{}".to_string()
-        } else {
-            format!("<ul>{}</ul>", cases.generate())
+        let body = match source_cases {
+            Some(source_cases) => format!("<ul>{}</ul>", source_cases.generate()),
+            None if cases.is_empty() => "{}".to_string(),
+            None => format!("<ul>{}</ul>", cases.generate()),
         };
 
         let def = DefTemplate {
@@ -100,7 +115,8 @@ impl GenerateDocs for Def {
 
 impl GenerateDocs for Codef {
     fn generate_docs(&self) -> String {
-        let Codef { span: _, doc, name, attr: _, params, typ, cases } = self;
+        let Codef { span: _, doc, comments: _, name, attr: _, params, typ_comments: _, typ, cases } =
+            self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -120,7 +136,18 @@ impl GenerateDocs for Codef {
 
 impl GenerateDocs for Let {
     fn generate_docs(&self) -> String {
-        let Let { span: _, doc, name, attr: _, params, typ, body } = self;
+        let Let {
+            span: _,
+            doc,
+            comments: _,
+            name,
+            attr: _,
+            params,
+            typ_comments: _,
+            typ,
+            body_comments: _,
+            body,
+        } = self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -135,7 +162,8 @@ impl GenerateDocs for Let {
 
 impl GenerateDocs for Extern {
     fn generate_docs(&self) -> String {
-        let Extern { span: _, doc, name, attr: _, params, typ } = self;
+        let Extern { span: _, doc, comments: _, name, attr: _, params, typ_comments: _, typ } =
+            self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -149,18 +177,25 @@ impl GenerateDocs for Extern {
 
 impl GenerateDocs for Infix {
     fn generate_docs(&self) -> String {
-        let Infix { span: _, doc, attr: _, lhs, rhs } = self;
+        let Infix { span: _, doc, comments: _, attr: _, assoc, precedence, lhs, rhs } = self;
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
+        let keyword = match assoc {
+            Associativity::Left => INFIXL,
+            Associativity::Right => INFIXR,
+            Associativity::NonAssoc => INFIX,
+        };
+        let precedence = precedence.map(|precedence| format!(" {precedence}")).unwrap_or_default();
         let lhs = print_html_to_string(lhs, Some(&PrintCfg::default()));
         let rhs = print_html_to_string(rhs, Some(&PrintCfg::default()));
-        let infix_template = InfixTemplate { doc: &doc, lhs: &lhs, rhs: &rhs };
+        let infix_template =
+            InfixTemplate { doc: &doc, keyword, precedence: &precedence, lhs: &lhs, rhs: &rhs };
         infix_template.render().unwrap()
     }
 }
 
 impl GenerateDocs for Note {
     fn generate_docs(&self) -> String {
-        let Note { span: _, doc, name, attr: _ } = self;
+        let Note { span: _, doc, comments: _, name, attr: _ } = self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -234,6 +269,8 @@ struct ExternTemplate<'a> {
 #[template(path = "infix.html", escape = "none")]
 struct InfixTemplate<'a> {
     pub doc: &'a str,
+    pub keyword: &'a str,
+    pub precedence: &'a str,
     pub lhs: &'a str,
     pub rhs: &'a str,
 }
@@ -0,0 +1,434 @@
+//! Completion candidates for the LSP server
+//!
+//! Completion is usually requested while the user is typing, so the source of the module
+//! is often not well-formed. We therefore remove the identifier under the cursor (and, after
+//! a dot, the dot itself) and analyze the remaining source without storing the result in the
+//! caches of the database.
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+use polarity_lang_ast::{Decl, DocComment, HasSpan, IdBound, Module, Telescope};
+use polarity_lang_elaborator::{TypeInfoTable, build_type_info_table};
+use polarity_lang_lowering::{DeclMeta, ModuleSymbolTable, SymbolTable};
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use polarity_lang_parser::lexer::{Lexer, Token};
+use url::Url;
+
+use crate::database::Database;
+use crate::info::collect_module_info;
+use crate::result::{AppErrors, AppResult};
+
+impl Database {
+    /// Compute the completion candidates at the given index
+    ///
+    /// Candidates are not filtered by the identifier that is currently being typed,
+    /// this is left to the client.
+    pub async fn completions_at_index(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> AppResult<Vec<CompletionItem>> {
+        let source = self.source(uri).await?;
+        let idx = idx.to_usize().min(source.len());
+        if !source.is_char_boundary(idx) {
+            return Ok(vec![]);
+        }
+        let start = identifier_start(&source, idx);
+
+        let mut items = match start.checked_sub(1).filter(|dot| source[*dot..].starts_with('.')) {
+            Some(dot) => {
+                let remaining = format!("{}{}", &source[..dot], &source[idx..]);
+                self.dot_completions(uri, &source, &remaining, dot).await?
+            }
+            None => {
+                let remaining = format!("{}{}", &source[..start], &source[idx..]);
+                match self.case_completions(uri, &remaining, start).await? {
+                    Some(items) => items,
+                    None => self.global_completions(uri, &source).await?,
+                }
+            }
+        };
+
+        items.sort_by(|item1, item2| item1.label.cmp(&item2.label));
+        items.dedup_by(|item1, item2| item1.label == item2.label);
+        Ok(items)
+    }
+
+    /// Destructors and definitions which can be called on the expression ending at `dot`
+    ///
+    /// If the type of the expression cannot be inferred, all destructors and definitions in scope are returned.
+    async fn dot_completions(
+        &mut self,
+        uri: &Url,
+        source: &str,
+        remaining: &str,
+        dot: usize,
+    ) -> AppResult<Vec<CompletionItem>> {
+        let Some((module, type_name)) = self.type_ending_at(uri, remaining, dot).await else {
+            let symbol_tables = self.symbol_tables_in_scope(uri, source).await;
+            return Ok(symbol_table_completions(&symbol_tables, true));
+        };
+
+        let mut items = vec![];
+        for module in self.modules_in_scope(module).await {
+            for decl in module.decls.iter() {
+                match decl {
+                    Decl::Codata(codata)
+                        if codata.name.id == type_name.id && module.uri == type_name.uri =>
+                    {
+                        for dtor in codata.dtors.iter() {
+                            let mut item = completion_item(&dtor.name.id, DTOR);
+                            item.documentation = documentation(&dtor.doc);
+                            items.push(item);
+                        }
+                    }
+                    Decl::Def(def) if def.self_param.typ.name == type_name => {
+                        let mut item = completion_item(&def.name.id, DEF);
+                        item.documentation = documentation(&def.doc);
+                        items.push(item);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// Constructors which are not yet covered by the innermost pattern match around `idx`
+    ///
+    /// Returns `None` if `idx` is not at a position where a new case can be written.
+    async fn case_completions(
+        &mut self,
+        uri: &Url,
+        remaining: &str,
+        idx: usize,
+    ) -> AppResult<Option<Vec<CompletionItem>>> {
+        let Ok(module) = self.detached_ust(uri, remaining).await else {
+            return Ok(None);
+        };
+        let info = collect_module_info(self, uri, &module)?;
+
+        let idx = idx as u32;
+        let Some(match_info) = info
+            .matches
+            .find(idx, idx + 1)
+            .filter(|interval| interval.start < idx && idx < interval.stop)
+            .min_by_key(|interval| interval.stop - interval.start)
+            .map(|interval| interval.val.clone())
+        else {
+            return Ok(None);
+        };
+        if match_info.case_spans.iter().any(|span| span.start.0 <= idx && idx <= span.end.0) {
+            return Ok(None);
+        }
+
+        let modules = self.modules_in_scope(Arc::new(module)).await;
+        let data = modules
+            .iter()
+            .flat_map(|module| module.decls.iter().map(move |decl| (module, decl)))
+            .find_map(|(module, decl)| match decl {
+                Decl::Data(data) => {
+                    let is_matched_type = match &match_info.type_name {
+                        Some(type_name) => {
+                            data.name.id == type_name.id && module.uri == type_name.uri
+                        }
+                        None => {
+                            data.ctors.iter().any(|ctor| match_info.covered.contains(&ctor.name.id))
+                        }
+                    };
+                    is_matched_type.then_some(data)
+                }
+                _ => None,
+            });
+        let Some(data) = data else {
+            return Ok(None);
+        };
+
+        let items = data
+            .ctors
+            .iter()
+            .filter(|ctor| !match_info.covered.contains(&ctor.name.id))
+            .map(|ctor| {
+                let mut item = completion_item(&ctor.name.id, CTOR);
+                item.insert_text =
+                    Some(format!("{}{} => ?", ctor.name.id, pattern_params(&ctor.params)));
+                item.documentation = documentation(&ctor.doc);
+                item
+            })
+            .collect::<Vec<_>>();
+
+        if items.is_empty() { Ok(None) } else { Ok(Some(items)) }
+    }
+
+    /// All top-level names and infix operators which can be used without a preceding dot
+    async fn global_completions(
+        &mut self,
+        uri: &Url,
+        source: &str,
+    ) -> AppResult<Vec<CompletionItem>> {
+        let symbol_tables = self.symbol_tables_in_scope(uri, source).await;
+        Ok(symbol_table_completions(&symbol_tables, false))
+    }
+
+    /// Infer the type of the expression ending at `idx` in the given source
+    ///
+    /// Returns the lowered module together with the name of the type constructor.
+    async fn type_ending_at(
+        &mut self,
+        uri: &Url,
+        source: &str,
+        idx: usize,
+    ) -> Option<(Arc<Module>, IdBound)> {
+        let ust = self.detached_ust(uri, source).await.ok()?;
+        let ast = self.detached_ast_at(uri, ust.clone(), idx).await.ok()?;
+        let info = collect_module_info(self, uri, &ast).ok()?;
+
+        let idx = idx as u32;
+        let typ = info
+            .types
+            .find(idx.saturating_sub(1), idx)
+            .filter(|interval| interval.stop == idx)
+            .min_by_key(|interval| interval.stop - interval.start)
+            .map(|interval| interval.val.clone())?;
+        let type_name = typ.to_typctor()?.name;
+
+        Some((Arc::new(ust), type_name))
+    }
+
+    /// The given module together with the lowered modules it imports
+    async fn modules_in_scope(&mut self, module: Arc<Module>) -> Vec<Arc<Module>> {
+        let mut modules = vec![];
+        for use_decl in module.use_decls.iter() {
+            let Ok(dep) = self.resolve_module_name(&use_decl.path, &module.uri) else {
+                continue;
+            };
+            if let Ok(dep_module) = self.ust(&dep).await {
+                modules.push(dep_module);
+            }
+        }
+        modules.push(module);
+        modules
+    }
+
+    /// The symbol table of the module and of all the modules it imports
+    ///
+    /// If the module cannot be parsed, the symbol table of its last well-formed version is used.
+    async fn symbol_tables_in_scope(
+        &mut self,
+        uri: &Url,
+        source: &str,
+    ) -> Vec<Arc<ModuleSymbolTable>> {
+        let mut symbol_tables = vec![];
+        match self.symbol_table(uri).await {
+            Ok(symbol_table) => symbol_tables.push(symbol_table),
+            Err(_) => symbol_tables.extend(self.symbol_table.get_even_if_stale(uri).cloned()),
+        }
+        for path in scan_use_paths(source) {
+            let Ok(dep) = self.resolve_module_name(&path, uri) else {
+                continue;
+            };
+            if let Ok(symbol_table) = self.symbol_table(&dep).await {
+                symbol_tables.push(symbol_table);
+            }
+        }
+        symbol_tables
+    }
+
+    /// Lower the given source as if it was the content of the module `uri`
+    ///
+    /// The result is not stored in the database.
+    async fn detached_ust(&mut self, uri: &Url, source: &str) -> AppResult<Module> {
+        let cst =
+            polarity_lang_parser::parse_module(uri.clone(), source).map_err(AppErrors::from)?;
+
+        let mut symbol_table = SymbolTable::default();
+        let module_symbol_table = polarity_lang_lowering::build_symbol_table(&cst)?;
+        symbol_table.insert(uri.clone(), Arc::new(module_symbol_table));
+        for use_decl in cst.use_decls.iter() {
+            let dep = self.resolve_module_name(&use_decl.path, uri)?;
+            let module_symbol_table = self.symbol_table(&dep).await?;
+            symbol_table.insert(dep, module_symbol_table);
+        }
+
+        polarity_lang_lowering::lower_module_with_symbol_table(&cst, &symbol_table)
+            .map(|(ust, _)| ust)
+            .map_err(AppErrors::from)
+    }
+
+    /// Typecheck the declaration containing `idx` in a module obtained from [Database::detached_ust]
+    ///
+    /// The other declarations only contribute their signatures, so errors in them are ignored.
+    /// The result is not stored in the database.
+    async fn detached_ast_at(
+        &mut self,
+        uri: &Url,
+        mut ust: Module,
+        idx: usize,
+    ) -> AppResult<Module> {
+        let mut info_table = TypeInfoTable::default();
+        info_table.insert(uri.clone(), build_type_info_table(&ust));
+
+        let contains_idx = |span: Span| span.start.to_usize() <= idx && idx <= span.end.to_usize();
+        ust.decls.retain(|decl| decl.span().is_some_and(contains_idx));
+        ust.meta_vars.retain(|metavar, _| metavar.span.is_none_or(contains_idx));
+
+        for use_decl in ust.use_decls.iter() {
+            let dep = self.resolve_module_name(&use_decl.path, uri)?;
+            let mod_info_table = self.module_type_info_table(&dep, true).await?;
+            info_table.insert(dep, mod_info_table);
+        }
+
+        polarity_lang_elaborator::typechecker::check_with_lookup_table(Rc::new(ust), &info_table)
+            .map(|(ast, _)| ast)
+            .map_err(AppErrors::from)
+    }
+}
+
+/// Find the start of the identifier which ends at `idx`
+fn identifier_start(source: &str, idx: usize) -> usize {
+    source[..idx]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '\'')
+        .last()
+        .map(|(start, _)| start)
+        .unwrap_or(idx)
+}
+
+/// Collect the paths of the `use` declarations at the top of a module
+///
+/// In contrast to parsing, this also works if the rest of the module is not well-formed.
+pub(crate) fn scan_use_paths(source: &str) -> Vec<String> {
+    let mut tokens = Lexer::new(source);
+    let mut paths = vec![];
+    while let Some(Ok((_, Token::Use, _))) = tokens.next() {
+        match tokens.next() {
+            Some(Ok((_, Token::StringLit(path), _))) => paths.push(path.unescaped),
+            _ => break,
+        }
+    }
+    paths
+}
+
+fn symbol_table_completions(
+    symbol_tables: &[Arc<ModuleSymbolTable>],
+    after_dot: bool,
+) -> Vec<CompletionItem> {
+    let mut items = vec![];
+    for symbol_table in symbol_tables {
+        for (name, meta) in symbol_table.idents.iter() {
+            let is_dot_call = matches!(meta, DeclMeta::Dtor { .. } | DeclMeta::Def { .. });
+            if is_dot_call == after_dot && !matches!(meta, DeclMeta::Note) {
+                items.push(completion_item(&name.id, kind_and_detail(meta)));
+            }
+        }
+        if !after_dot {
+            for (operator, meta) in symbol_table.infix_ops.iter() {
+                items.push(CompletionItem {
+                    label: operator.id.clone(),
+                    kind: Some(CompletionItemKind::OPERATOR),
+                    detail: Some(format!("Infix operator: `{}`", meta.name.id)),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    items
+}
+
+const CTOR: (CompletionItemKind, &str) = (CompletionItemKind::CONSTRUCTOR, "Constructor");
+const DTOR: (CompletionItemKind, &str) = (CompletionItemKind::METHOD, "Destructor");
+const DEF: (CompletionItemKind, &str) = (CompletionItemKind::METHOD, "Definition");
+
+fn kind_and_detail(meta: &DeclMeta) -> (CompletionItemKind, &'static str) {
+    match meta {
+        DeclMeta::Data { .. } => (CompletionItemKind::ENUM, "Data type"),
+        DeclMeta::Codata { .. } => (CompletionItemKind::INTERFACE, "Codata type"),
+        DeclMeta::Ctor { .. } => CTOR,
+        DeclMeta::Dtor { .. } => DTOR,
+        DeclMeta::Def { .. } => DEF,
+        DeclMeta::Codef { .. } => (CompletionItemKind::FUNCTION, "Codefinition"),
+        DeclMeta::Let { .. } => (CompletionItemKind::FUNCTION, "Let-bound definition"),
+        DeclMeta::Extern { .. } => (CompletionItemKind::FUNCTION, "Extern declaration"),
+        DeclMeta::Note => (CompletionItemKind::TEXT, "Note"),
+    }
+}
+
+fn completion_item(label: &str, (kind, detail): (CompletionItemKind, &str)) -> CompletionItem {
+    CompletionItem {
+        label: label.to_owned(),
+        kind: Some(kind),
+        detail: Some(detail.to_owned()),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn documentation(doc: &Option<DocComment>) -> Option<Documentation> {
+    doc.as_ref().map(|doc| Documentation::String(doc.docs.join("\n")))
+}
+
+/// Print the parameters of a constructor as the parameters of a pattern
+fn pattern_params(params: &Telescope) -> String {
+    if params.params.is_empty() {
+        return String::new();
+    }
+    let names: Vec<String> = params.params.iter().map(|param| param.name.to_string()).collect();
+    format!("({})", names.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use crate::fs::InMemorySource;
+
+    use super::*;
+
+    const OTHER: &str = "data Bool { T, F }\n\ncodata Pair { .other: Bool }\n";
+
+    const MAIN: &str = r#"use "other.pol"
+
+codata Pair { .fst: Bool, .rest: Pair }
+
+def Bool.neg: Bool { T => F, F => T }
+
+let first(p: Pair): Bool { p.fst }
+
+let tail(p: Pair): Pair { p.rest }
+
+let not(b: Bool): Bool { b.neg }
+
+let and(b: Bool): Bool { b.match { F => F, } }
+"#;
+
+    /// The labels of the completions directly behind the first occurrence of `needle` in `MAIN`
+    fn labels_at(needle: &str) -> Vec<String> {
+        let main = Url::parse("inmemory:///main.pol").unwrap();
+        let mut source = InMemorySource::new();
+        source.insert(Url::parse("inmemory:///other.pol").unwrap(), OTHER.to_owned());
+        source.insert(main.clone(), MAIN.to_owned());
+        let mut db = Database::from_source(source);
+        let (start, _) = MAIN.match_indices(needle).next().unwrap();
+        let idx = ByteIndex((start + needle.len()) as u32);
+        let items = block_on(db.completions_at_index(&main, idx)).unwrap();
+        items.into_iter().map(|item| item.label).collect()
+    }
+
+    #[test]
+    fn dot_completions_of_local_type() {
+        // The imported type with the same name does not contribute its destructor `other`.
+        assert_eq!(labels_at("p.rest"), vec!["fst", "rest"]);
+    }
+
+    #[test]
+    fn dot_completions_of_imported_type() {
+        assert_eq!(labels_at("b.neg"), vec!["neg"]);
+    }
+
+    #[test]
+    fn case_completions_of_uncovered_ctors() {
+        assert_eq!(labels_at("F => F, "), vec!["T"]);
+    }
+}
@@ -9,6 +9,7 @@ use polarity_lang_miette_util::ToMiette;
 use polarity_lang_miette_util::codespan::Span;
 use url::Url;
 
+use polarity_lang_ast::Decl;
 use polarity_lang_ast::Exp;
 use polarity_lang_ast::HashSet;
 use polarity_lang_ast::Zonk;
@@ -27,7 +28,7 @@ use crate::info::*;
 use crate::result::AppErrors;
 use crate::result::AppResult;
 use crate::result::DriverError;
-use crate::{AppError, FileSource, cache::*};
+use crate::{AppError, AppWarning, FileSource, cache::*};
 
 use rust_lapper::Lapper;
 
@@ -47,6 +48,10 @@ pub struct Database {
     pub ust: Cache<AppResult<Arc<polarity_lang_ast::Module>>>,
     /// The typechecked AST of a module
     pub ast: Cache<AppResult<Arc<polarity_lang_ast::Module>>>,
+    /// The warnings emitted while lowering a module
+    pub lowering_warnings: Cache<Vec<AppWarning>>,
+    /// The warnings emitted while typechecking a module
+    pub type_warnings: Cache<Vec<AppWarning>>,
     /// The IR of a module
     pub ir: Cache<AppResult<Arc<ir::Module>>>,
     /// The type info table, either open or closed
@@ -57,6 +62,10 @@ pub struct Database {
     pub goto_by_id: Cache<Lapper<u32, (Url, Span)>>,
     /// Spans of top-level items
     pub item_by_id: Cache<Lapper<u32, Item>>,
+    /// Occurrences of names of top-level declarations
+    pub references_by_id: Cache<Lapper<u32, Reference>>,
+    /// Spans of holes
+    pub holes_by_id: Cache<Lapper<u32, HoleInfo>>,
 }
 
 /// Open or closed type info table
@@ -184,11 +193,27 @@ impl Database {
     pub async fn recompute_ust(&mut self, uri: &Url) -> AppResult<Arc<polarity_lang_ast::Module>> {
         log::debug!("Recomputing ust for: {uri}");
         let cst = self.cst(uri).await?;
+        let symbol_table = self.symbol_table_in_scope(uri).await?;
+
+        let ust = polarity_lang_lowering::lower_module_with_symbol_table(&cst, &symbol_table)
+            .map_err(AppErrors::from)
+            .map(|(ust, warnings)| {
+                self.lowering_warnings
+                    .insert(uri.clone(), warnings.into_iter().map(AppWarning::from).collect());
+                Arc::new(ust)
+            });
+
+        self.ust.insert(uri.clone(), ust.clone());
+        ust
+    }
+
+    /// Compute the SymbolTable which is used to lower the given module
+    ///
+    /// It consists of the ModuleSymbolTables of all direct dependencies
+    /// and the ModuleSymbolTable of the module itself.
+    pub async fn symbol_table_in_scope(&mut self, uri: &Url) -> AppResult<SymbolTable> {
         let deps = self.deps(uri).await?;
 
-        // Compute the SymbolTable consisting of all the
-        // ModuleSymbolTables of all direct dependencies
-        // and the SymbolTable from the module itself.
         let mut symbol_table = SymbolTable::default();
         let module_symbol_table = self.symbol_table(uri).await?;
         symbol_table.insert(uri.clone(), module_symbol_table);
@@ -196,13 +221,7 @@ impl Database {
             let module_symbol_table = self.symbol_table(&dep).await?;
             symbol_table.insert(dep.clone(), module_symbol_table);
         }
-
-        let ust = polarity_lang_lowering::lower_module_with_symbol_table(&cst, &symbol_table)
-            .map_err(AppErrors::from)
-            .map(Arc::new);
-
-        self.ust.insert(uri.clone(), ust.clone());
-        ust
+        Ok(symbol_table)
     }
 
     // Core API: TypeInfoTable
@@ -332,12 +351,75 @@ impl Database {
             Rc::new(ust),
             &info_table,
         )
-        .map(Arc::new)
+        .map(|(ast, warnings)| {
+            self.type_warnings
+                .insert(uri.clone(), warnings.into_iter().map(AppWarning::from).collect());
+            Arc::new(ast)
+        })
         .map_err(AppErrors::from);
         self.ast.insert(uri.clone(), ast.clone());
         ast
     }
 
+    // Core API: Warnings
+    //
+    //
+
+    /// The warnings of a module, ordered by the phase which emitted them
+    ///
+    /// Warnings are only reported for the phases which succeed, so a module which cannot be
+    /// typechecked has no warnings about unsolved holes.
+    pub async fn warnings(&mut self, uri: &Url) -> Vec<AppWarning> {
+        let _ = self.ast(uri).await;
+
+        let mut warnings = vec![];
+        for cache in [&self.lowering_warnings, &self.type_warnings] {
+            if let Some(phase_warnings) = cache.get_unless_stale(uri) {
+                warnings.extend(phase_warnings.iter().cloned());
+            }
+        }
+
+        // Lowering only sees the module itself, but a top-level declaration which is unused there
+        // may still be used by a module importing it.
+        if warnings.iter().any(AppWarning::is_unused_decl) {
+            let used = self.used_by_reverse_dependencies(uri).await;
+            warnings.retain(|warning| match warning {
+                AppWarning::Lowering(polarity_lang_lowering::LoweringWarning::UnusedDecl {
+                    name,
+                    ..
+                }) => !used.contains(name),
+                _ => true,
+            });
+        }
+
+        warnings
+    }
+
+    /// The names of the top-level declarations of a module which are used by the loaded modules importing it
+    async fn used_by_reverse_dependencies(&mut self, uri: &Url) -> HashSet<String> {
+        let rev_deps: Vec<Url> = self
+            .deps
+            .reverse_dependencies(uri)
+            .into_iter()
+            .filter(|dep| *dep != uri)
+            .cloned()
+            .collect();
+
+        let mut used = HashSet::default();
+        for dep in rev_deps {
+            let Ok(lapper) = self.references_by_id(&dep).await else {
+                continue;
+            };
+            used.extend(
+                lapper
+                    .iter()
+                    .filter(|interval| interval.val.name.uri == *uri)
+                    .map(|interval| interval.val.name.id.clone()),
+            );
+        }
+        used
+    }
+
     // Core API: ir
     //
     //
@@ -385,7 +467,7 @@ impl Database {
 
     async fn recompute_goto_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, (Url, Span)>> {
         log::debug!("Recomputing goto_by_id for: {uri}");
-        let (_hover_lapper, location_lapper, _item_lapper) = collect_info(self, uri).await?;
+        let location_lapper = collect_info(self, uri).await?.goto;
         self.goto_by_id.insert(uri.clone(), location_lapper.clone());
         Ok(location_lapper)
     }
@@ -406,7 +488,7 @@ impl Database {
 
     async fn recompute_hover_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, HoverContents>> {
         log::debug!("Recomputing hover_by_id for: {uri}");
-        let (hover_lapper, _location_lapper, _item_lapper) = collect_info(self, uri).await?;
+        let hover_lapper = collect_info(self, uri).await?.hover;
         self.hover_by_id.insert(uri.clone(), hover_lapper.clone());
         Ok(hover_lapper)
     }
@@ -427,11 +509,53 @@ impl Database {
 
     async fn recompute_item_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, Item>> {
         log::debug!("Recomputing item_by_id for: {uri}");
-        let (_info_lapper, _location_lapper, item_lapper) = collect_info(self, uri).await?;
+        let item_lapper = collect_info(self, uri).await?.items;
         self.item_by_id.insert(uri.clone(), item_lapper.clone());
         Ok(item_lapper)
     }
 
+    // Core API: references_by_id
+    //
+    //
+
+    pub async fn references_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, Reference>> {
+        match self.references_by_id.get_unless_stale(uri) {
+            Some(references) => {
+                log::debug!("Found references_by_id in cache: {uri}");
+                Ok(references.clone())
+            }
+            None => self.recompute_references_by_id(uri).await,
+        }
+    }
+
+    async fn recompute_references_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, Reference>> {
+        log::debug!("Recomputing references_by_id for: {uri}");
+        let reference_lapper = collect_info(self, uri).await?.references;
+        self.references_by_id.insert(uri.clone(), reference_lapper.clone());
+        Ok(reference_lapper)
+    }
+
+    // Core API: holes_by_id
+    //
+    //
+
+    pub async fn holes_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, HoleInfo>> {
+        match self.holes_by_id.get_unless_stale(uri) {
+            Some(holes) => {
+                log::debug!("Found holes_by_id in cache: {uri}");
+                Ok(holes.clone())
+            }
+            None => self.recompute_holes_by_id(uri).await,
+        }
+    }
+
+    async fn recompute_holes_by_id(&mut self, uri: &Url) -> AppResult<Lapper<u32, HoleInfo>> {
+        log::debug!("Recomputing holes_by_id for: {uri}");
+        let hole_lapper = collect_info(self, uri).await?.holes;
+        self.holes_by_id.insert(uri.clone(), hole_lapper.clone());
+        Ok(hole_lapper)
+    }
+
     // Core API: Dependencies
     //
     //
@@ -472,11 +596,15 @@ impl Database {
             symbol_table: Cache::default(),
             ust: Cache::default(),
             ast: Cache::default(),
+            lowering_warnings: Cache::default(),
+            type_warnings: Cache::default(),
             ir: Cache::default(),
             type_info_table: Cache::default(),
             hover_by_id: Cache::default(),
             goto_by_id: Cache::default(),
             item_by_id: Cache::default(),
+            references_by_id: Cache::default(),
+            holes_by_id: Cache::default(),
         }
     }
 
@@ -494,49 +622,91 @@ impl Database {
         &mut self.source
     }
 
+    /// Get a mutable reference to the source of the files if it has the type `S`
+    pub fn file_source_as_mut<S: FileSource>(&mut self) -> Option<&mut S> {
+        let source: &mut dyn std::any::Any = &mut *self.source;
+        source.downcast_mut()
+    }
+
     /// Invalidate the file behind the given URI and all its reverse dependencies
+    ///
+    /// The file itself is invalidated completely.
+    /// The source, CST and symbol table of a reverse dependency do not depend on the invalidated file,
+    /// so only the caches computed from its dependencies are invalidated for it.
     pub async fn invalidate(&mut self, uri: &Url) {
-        self.invalidate_impl(uri);
-        let rev_deps: HashSet<Url> =
-            self.deps.reverse_dependencies(uri).into_iter().cloned().collect();
+        let rev_deps: HashSet<Url> = self
+            .deps
+            .reverse_dependencies(uri)
+            .into_iter()
+            .filter(|rev_dep| *rev_dep != uri)
+            .cloned()
+            .collect();
         log::debug!(
             "Invalidating {} and its reverse dependencies: {:?}",
             uri,
             rev_deps.iter().map(ToString::to_string).collect::<Vec<_>>()
         );
+        self.invalidate_source(uri);
+        self.invalidate_dependents(uri);
         for rev_dep in &rev_deps {
-            self.invalidate_impl(rev_dep);
+            self.invalidate_dependents(rev_dep);
         }
     }
 
-    fn invalidate_impl(&mut self, uri: &Url) {
+    /// Invalidate the caches that are computed from the source of the file alone
+    fn invalidate_source(&mut self, uri: &Url) {
         self.files.invalidate(uri);
         self.deps.invalidate(uri);
         self.cst.invalidate(uri);
         self.symbol_table.invalidate(uri);
+    }
+
+    /// Invalidate the caches that are computed from the file together with its dependencies
+    fn invalidate_dependents(&mut self, uri: &Url) {
         self.ust.invalidate(uri);
         self.ast.invalidate(uri);
+        self.lowering_warnings.invalidate(uri);
+        self.type_warnings.invalidate(uri);
+        self.ir.invalidate(uri);
         self.type_info_table.invalidate(uri);
         self.hover_by_id.invalidate(uri);
         self.goto_by_id.invalidate(uri);
         self.item_by_id.invalidate(uri);
+        self.references_by_id.invalidate(uri);
+        self.holes_by_id.invalidate(uri);
     }
 
     pub async fn normalize_main(&mut self, uri: &Url) -> AppResult<Option<Box<Exp>>> {
         let ast = self.ast(uri).await?;
 
-        let main = ast.find_main();
-        let info_table = self.type_info_table(uri).await?;
+        match ast.find_main() {
+            Some(exp) => self.normalize_closed(uri, &exp).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Normalize the body of the top-level let binding with the given name, if it takes no parameters
+    pub async fn normalize_let(&mut self, uri: &Url, name: &str) -> AppResult<Option<Box<Exp>>> {
+        let ast = self.ast(uri).await?;
 
-        match main {
-            Some(exp) => {
-                let nf = exp.normalize_in_empty_env(&Rc::new(info_table));
-                nf.map(Some).map_err(AppErrors::from)
+        let body = ast.decls.iter().find_map(|decl| match decl {
+            Decl::Let(tl_let) if tl_let.name.id == name && tl_let.params.is_empty() => {
+                Some(tl_let.body.clone())
             }
+            _ => None,
+        });
+
+        match body {
+            Some(exp) => self.normalize_closed(uri, &exp).await.map(Some),
             None => Ok(None),
         }
     }
 
+    async fn normalize_closed(&mut self, uri: &Url, exp: &Exp) -> AppResult<Box<Exp>> {
+        let info_table = self.type_info_table(uri).await?;
+        exp.normalize_in_empty_env(&Rc::new(info_table)).map_err(AppErrors::from)
+    }
+
     /// Compile to JavaScript
     pub async fn js<W: io::Write>(&mut self, uri: &Url, mut output: W) -> AppResult<()> {
         self.build_dependency_dag().await?;
@@ -560,11 +730,23 @@ impl Database {
     }
 
     pub fn pretty_error(&self, uri: &Url, err: AppError) -> miette::Report {
-        let miette_error: miette::Error = err.into();
+        self.with_source_code(uri, err.into())
+    }
+
+    pub fn pretty_warnings(&self, uri: &Url, warnings: Vec<AppWarning>) -> Vec<miette::Report> {
+        warnings.into_iter().map(|warning| self.with_source_code(uri, warning.into())).collect()
+    }
+
+    fn with_source_code(&self, uri: &Url, report: miette::Report) -> miette::Report {
         if let Some(File { source, .. }) = self.files.get_even_if_stale(uri) {
-            miette_error.with_source_code(miette::NamedSource::new(uri, source.to_owned()))
+            // Show files on disk by their path rather than their URI.
+            let name = match uri.to_file_path() {
+                Ok(path) => path.display().to_string(),
+                Err(()) => uri.to_string(),
+            };
+            report.with_source_code(miette::NamedSource::new(name, source.to_owned()))
         } else {
-            miette_error
+            report
         }
     }
 
@@ -573,6 +755,17 @@ impl Database {
         self.source.write_string(uri, source).await.map_err(|err| err.into())
     }
 
+    /// Replace the source of the given file
+    ///
+    /// Unlike `write_source`, nothing is invalidated if the new source is identical to the cached one.
+    pub async fn update_source(&mut self, uri: &Url, source: &str) -> AppResult {
+        if self.files.get_unless_stale(uri).is_some_and(|file| file.source == source) {
+            log::debug!("Source unchanged, keeping caches: {uri}");
+            return Ok(());
+        }
+        self.write_source(uri, source).await
+    }
+
     pub async fn print_to_string(&mut self, uri: &Url) -> AppResult<String> {
         let module = self.ust(uri).await?;
         let mut module = (*module).clone();
@@ -637,7 +830,7 @@ impl Database {
         // Collect dependencies from `use` declarations
         let mut dependencies = Vec::new();
         for use_decl in &module.use_decls {
-            let UseDecl { path, span } = use_decl;
+            let UseDecl { path, span, comments: _ } = use_decl;
             // Resolve the module name to a `Url`
             let dep_url = self.resolve_module_name(path, module_uri)?;
             dependencies.push(dep_url.clone());
@@ -699,3 +892,89 @@ mod path_support {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn unused_decls(db: &mut Database, uri: &Url) -> Vec<String> {
+        block_on(db.warnings(uri))
+            .into_iter()
+            .filter_map(|warning| match warning {
+                AppWarning::Lowering(polarity_lang_lowering::LoweringWarning::UnusedDecl {
+                    name,
+                    ..
+                }) => Some(name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn declarations_used_by_importing_module() {
+        let lib = Url::parse("inmemory:///lib.pol").unwrap();
+        let main = Url::parse("inmemory:///main.pol").unwrap();
+        let mut source = InMemorySource::new();
+        source.insert(
+            lib.clone(),
+            "data Bool { T, F }\n\ndef Bool.not: Bool { T => F, F => T }\n\nlet top: Bool { T }\n"
+                .to_owned(),
+        );
+        source.insert(main.clone(), "use \"lib.pol\"\n\nlet main: Bool { T.not }\n".to_owned());
+        let mut db = Database::from_source(source);
+
+        assert_eq!(unused_decls(&mut db, &lib), vec!["not".to_owned(), "top".to_owned()]);
+
+        block_on(db.ast(&main)).unwrap();
+        block_on(db.build_dependency_dag()).unwrap();
+        assert_eq!(unused_decls(&mut db, &lib), vec!["top".to_owned()]);
+    }
+
+    #[test]
+    fn unreachable_case_is_a_warning() {
+        let uri = Url::parse("inmemory:///main.pol").unwrap();
+        let mut source = InMemorySource::new();
+        source.insert(
+            uri.clone(),
+            "data Nat { Z, S(n: Nat) }\n\ndef Nat.pred: Nat { Z => Z, S(n) => n, S(Z) => Z }\n"
+                .to_owned(),
+        );
+        let mut db = Database::from_source(source);
+
+        assert!(block_on(db.ast(&uri)).is_ok());
+        let unreachable: Vec<_> = block_on(db.warnings(&uri))
+            .into_iter()
+            .filter(|warning| {
+                matches!(
+                    warning,
+                    AppWarning::Lowering(
+                        polarity_lang_lowering::LoweringWarning::UnreachableCase { .. }
+                    )
+                )
+            })
+            .collect();
+        assert_eq!(unreachable.len(), 1);
+    }
+
+    #[test]
+    fn case_body_is_lowered_once() {
+        let uri = Url::parse("inmemory:///main.pol").unwrap();
+        let mut source = InMemorySource::new();
+        source.insert(
+            uri.clone(),
+            "data Nat { Z, S(n: Nat) }\n\ndef Nat.half: Nat { S(S(n)) => S(n.half), S(m) => ?, Z => Z }\n"
+                .to_owned(),
+        );
+        let mut db = Database::from_source(source);
+
+        assert_eq!(block_on(db.goals(&uri)).unwrap().len(), 1);
+        let warnings: Vec<_> = block_on(db.warnings(&uri))
+            .iter()
+            .filter(|warning| !warning.is_unused_decl())
+            .map(|warning| warning.to_string())
+            .collect();
+        assert_eq!(warnings, vec!["Unused variable m".to_owned(), "Unsolved hole ?0".to_owned()]);
+    }
+}
@@ -1,10 +1,12 @@
 use std::ops::Range;
 
+use lsp_types::{Position, TextDocumentContentChangeEvent};
 use polarity_lang_miette_util::codespan::Span;
 use ropey::Rope;
 use url::Url;
 
 use crate::database::Database;
+use crate::result::AppResult;
 
 #[derive(Ord, PartialOrd, Eq, PartialEq)]
 pub struct Edit {
@@ -31,6 +33,59 @@ impl Database {
 
         rope
     }
+
+    /// Apply the content changes sent by an LSP client to the source of the given file
+    ///
+    /// Changes carrying a range replace that range, changes without a range replace the whole text.
+    /// As required by the LSP specification, the changes are applied in the order in which they are given.
+    pub async fn apply_content_changes(
+        &mut self,
+        uri: &Url,
+        changes: Vec<TextDocumentContentChangeEvent>,
+    ) -> AppResult {
+        let source = self.source.read_to_string(uri).await?;
+        let source = apply_changes(&source, changes);
+        self.update_source(uri, &source).await
+    }
+}
+
+/// Apply a sequence of LSP content changes to a source text
+fn apply_changes(source: &str, changes: Vec<TextDocumentContentChangeEvent>) -> String {
+    let mut rope = Rope::from_str(source);
+
+    for change in changes {
+        match change.range {
+            Some(range) => {
+                let start = position_to_char(&rope, range.start);
+                let end = position_to_char(&rope, range.end).max(start);
+                rope.remove(start..end);
+                rope.insert(start, &change.text);
+            }
+            None => rope = Rope::from_str(&change.text),
+        }
+    }
+
+    rope.to_string()
+}
+
+/// Convert an LSP position to a char index into the rope
+///
+/// The character offset of the position counts UTF-16 code units, which is the default position encoding of LSP.
+/// Positions beyond the end of a line or the end of the document are clamped.
+fn position_to_char(rope: &Rope, position: Position) -> usize {
+    let line_idx = position.line as usize;
+    if line_idx >= rope.len_lines() {
+        return rope.len_chars();
+    }
+    let line = rope.line(line_idx);
+    let line_len = line.len_chars()
+        - line
+            .chars_at(line.len_chars())
+            .reversed()
+            .take_while(|c| *c == '\n' || *c == '\r')
+            .count();
+    let character = (position.character as usize).min(line.char_to_utf16_cu(line_len));
+    rope.line_to_char(line_idx) + line.utf16_cu_to_char(character)
 }
 
 trait SpanAsRange {
@@ -42,3 +97,92 @@ impl SpanAsRange for Span {
         self.start.0 as usize..self.end.0 as usize
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::Range;
+
+    use super::*;
+
+    fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: Position::new(start.0, start.1),
+                end: Position::new(end.0, end.1),
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    fn replace_all(text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent { range: None, range_length: None, text: text.to_string() }
+    }
+
+    #[test]
+    fn several_edits_are_applied_in_order() {
+        let source = "data Bool { T, F }\n";
+        // The second edit refers to positions in the text produced by the first one.
+        let changes = vec![change((0, 5), (0, 9), "Boolean"), change((0, 15), (0, 16), "True")];
+        assert_eq!(apply_changes(source, changes), "data Boolean { True, F }\n");
+    }
+
+    #[test]
+    fn edit_spanning_lines() {
+        let source = "data Nat {\n  Z,\n  S(n: Nat)\n}\n";
+        let changes = vec![change((1, 2), (2, 11), "Z")];
+        assert_eq!(apply_changes(source, changes), "data Nat {\n  Z\n}\n");
+    }
+
+    #[test]
+    fn full_document_replacement() {
+        let source = "data Bool { T, F }\n";
+        let changes = vec![change((0, 0), (0, 4), "codata"), replace_all("data Top { Unit }\n")];
+        assert_eq!(apply_changes(source, changes), "data Top { Unit }\n");
+    }
+
+    #[test]
+    fn edits_after_full_document_replacement() {
+        let changes = vec![replace_all("data Top { Unit }\n"), change((0, 11), (0, 15), "tt")];
+        assert_eq!(apply_changes("", changes), "data Top { tt }\n");
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let source = "data Bool {\r\n  T,\r\n  F\r\n}\r\n";
+        let changes = vec![change((2, 2), (2, 3), "False")];
+        assert_eq!(apply_changes(source, changes), "data Bool {\r\n  T,\r\n  False\r\n}\r\n");
+    }
+
+    #[test]
+    fn position_past_end_of_line_is_clamped() {
+        let rope = Rope::from_str("ab\r\ncd\n");
+        assert_eq!(position_to_char(&rope, Position::new(0, 10)), 2);
+        assert_eq!(position_to_char(&rope, Position::new(1, 10)), 6);
+
+        let changes = vec![change((0, 10), (0, 20), "!")];
+        assert_eq!(apply_changes("ab\r\ncd\n", changes), "ab!\r\ncd\n");
+    }
+
+    #[test]
+    fn characters_count_utf16_code_units() {
+        // The emoji is a single char, but two UTF-16 code units.
+        let source = "\"😀\" // 😀 ok\ndata Top { Unit }\n";
+        let rope = Rope::from_str(source);
+        assert_eq!(position_to_char(&rope, Position::new(0, 4)), 3);
+        assert_eq!(position_to_char(&rope, Position::new(0, 13)), 11);
+        assert_eq!(position_to_char(&rope, Position::new(0, 20)), 11);
+
+        let changes = vec![change((0, 8), (0, 10), "🙂"), change((0, 11), (0, 13), "fine")];
+        assert_eq!(apply_changes(source, changes), "\"😀\" // 🙂 fine\ndata Top { Unit }\n");
+    }
+
+    #[test]
+    fn position_past_end_of_file_is_clamped() {
+        let rope = Rope::from_str("ab\ncd");
+        assert_eq!(position_to_char(&rope, Position::new(7, 0)), 5);
+
+        let changes = vec![change((1, 2), (9, 9), "!"), change((42, 0), (42, 0), "?")];
+        assert_eq!(apply_changes("ab\ncd", changes), "ab\ncd!?");
+    }
+}
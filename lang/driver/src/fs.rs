@@ -1,15 +1,17 @@
+use std::any::Any;
+
 use async_trait::async_trait;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use file_system::FileSystemSource;
 
-use polarity_lang_ast::HashMap;
+use polarity_lang_ast::{HashMap, HashSet};
 use url::Url;
 
 use crate::result::DriverError;
 
 #[async_trait]
-pub trait FileSource: Send + Sync {
+pub trait FileSource: Send + Sync + Any {
     /// Check if a file with the given URI exists
     async fn exists(&mut self, uri: &Url) -> Result<bool, DriverError>;
     /// Instruct the source to register a file with the given URI.
@@ -53,6 +55,37 @@ mod file_system {
         pub fn new<P: AsRef<Path>>(root: P) -> Self {
             Self { root: root.as_ref().to_path_buf() }
         }
+
+        /// The URIs of all `.pol` files below the root folder
+        ///
+        /// Hidden folders and the `target_pol` output folder are skipped.
+        pub fn module_uris(&self) -> Vec<Url> {
+            let mut uris = vec![];
+            let mut folders = vec![self.root.clone()];
+            while let Some(folder) = folders.pop() {
+                let Ok(entries) = std::fs::read_dir(&folder) else {
+                    continue;
+                };
+                for entry in entries.filter_map(Result::ok) {
+                    let path = entry.path();
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    if path.is_dir() {
+                        if !name.starts_with('.')
+                            && name != crate::TARGET_PATH.trim_end_matches('/')
+                        {
+                            folders.push(path);
+                        }
+                    } else if path.extension().is_some_and(|ext| ext == "pol")
+                        && let Ok(uri) = Url::from_file_path(&path)
+                    {
+                        uris.push(uri);
+                    }
+                }
+            }
+            uris.sort();
+            uris
+        }
     }
 
     #[async_trait]
@@ -195,3 +228,134 @@ where
         }
     }
 }
+
+/// A file source for a workspace consisting of several root folders
+///
+/// Each workspace folder has its own file source, and a file is handled by the source of the
+/// innermost folder containing it. Files outside of all folders are handled by the default source.
+/// Registered files are moved to their new source when folders are added or removed.
+pub struct WorkspaceSource {
+    default: Box<dyn FileSource>,
+    folders: Vec<(Url, Box<dyn FileSource>)>,
+    registered: HashSet<Url>,
+}
+
+impl WorkspaceSource {
+    pub fn new<S: FileSource>(default: S) -> Self {
+        Self { default: Box::new(default), folders: vec![], registered: HashSet::default() }
+    }
+
+    /// The root URIs of the workspace folders
+    pub fn folders(&self) -> impl Iterator<Item = &Url> {
+        self.folders.iter().map(|(root, _)| root)
+    }
+
+    /// Add a workspace folder whose files are handled by the given source
+    ///
+    /// If there already is a folder with the same root, its source is replaced.
+    pub async fn add_folder<S: FileSource>(
+        &mut self,
+        root: &Url,
+        source: S,
+    ) -> Result<(), DriverError> {
+        let root = folder_root(root);
+        let files = self.take_registered(&root).await?;
+        self.folders.retain(|(other, _)| *other != root);
+        self.folders.push((root, Box::new(source)));
+        self.restore_registered(files).await
+    }
+
+    /// Remove the workspace folder with the given root
+    ///
+    /// Returns `true` if the folder was part of the workspace.
+    pub async fn remove_folder(&mut self, root: &Url) -> Result<bool, DriverError> {
+        let root = folder_root(root);
+        if !self.folders.iter().any(|(other, _)| *other == root) {
+            return Ok(false);
+        }
+        let files = self.take_registered(&root).await?;
+        self.folders.retain(|(other, _)| *other != root);
+        self.restore_registered(files).await?;
+        Ok(true)
+    }
+
+    fn source_for(&mut self, uri: &Url) -> &mut Box<dyn FileSource> {
+        let innermost = self
+            .folders
+            .iter()
+            .enumerate()
+            .filter(|(_, (root, _))| uri.as_str().starts_with(root.as_str()))
+            .max_by_key(|(_, (root, _))| root.as_str().len())
+            .map(|(idx, _)| idx);
+        match innermost {
+            Some(idx) => &mut self.folders[idx].1,
+            None => &mut self.default,
+        }
+    }
+
+    /// Remove the registered files within the given folder from their current sources
+    async fn take_registered(&mut self, root: &Url) -> Result<Vec<(Url, String)>, DriverError> {
+        let uris: Vec<Url> = self
+            .registered
+            .iter()
+            .filter(|uri| uri.as_str().starts_with(root.as_str()))
+            .cloned()
+            .collect();
+        let mut files = vec![];
+        for uri in uris {
+            let source = self.source_for(&uri);
+            let contents = source.read_to_string(&uri).await?;
+            source.forget(&uri);
+            files.push((uri, contents));
+        }
+        Ok(files)
+    }
+
+    /// Register files again after the folders have changed
+    async fn restore_registered(&mut self, files: Vec<(Url, String)>) -> Result<(), DriverError> {
+        for (uri, contents) in files {
+            let source = self.source_for(&uri);
+            source.register(&uri);
+            source.write_string(&uri, &contents).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Make sure that the root URI of a folder ends with a slash, so that it only prefixes its own files
+pub fn folder_root(root: &Url) -> Url {
+    let mut root = root.clone();
+    if !root.path().ends_with('/') {
+        let path = format!("{}/", root.path());
+        root.set_path(&path);
+    }
+    root
+}
+
+#[async_trait]
+impl FileSource for WorkspaceSource {
+    async fn exists(&mut self, uri: &Url) -> Result<bool, DriverError> {
+        self.source_for(uri).exists(uri).await
+    }
+
+    fn register(&mut self, uri: &Url) -> bool {
+        let registered = self.source_for(uri).register(uri);
+        if registered {
+            self.registered.insert(uri.clone());
+        }
+        registered
+    }
+
+    fn forget(&mut self, uri: &Url) -> bool {
+        self.registered.remove(uri);
+        self.source_for(uri).forget(uri)
+    }
+
+    async fn read_to_string(&mut self, uri: &Url) -> Result<String, DriverError> {
+        self.source_for(uri).read_to_string(uri).await
+    }
+
+    async fn write_string(&mut self, uri: &Url, source: &str) -> Result<(), DriverError> {
+        self.source_for(uri).write_string(uri, source).await
+    }
+}
@@ -0,0 +1,245 @@
+//! Call hierarchy and type hierarchy for the LSP server
+//!
+//! The call hierarchy relates definitions, codefinitions, let bindings and destructors with the
+//! declarations calling them. The type hierarchy shows the expression-problem matrix of a type:
+//! a data type has its constructors and the definitions matching on it as subtypes, a codata type
+//! its destructors and the codefinitions producing it.
+
+use lsp_types::{Range, SymbolKind};
+use polarity_lang_ast::*;
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use polarity_lang_printer::Print;
+use polarity_lang_transformations::matrix::{self, Repr};
+use url::Url;
+
+use crate::database::Database;
+
+/// A declaration shown in the call hierarchy or the type hierarchy
+#[derive(Debug, Clone)]
+pub struct HierarchyItem {
+    /// The name of the declaration, together with the module in which it is declared
+    pub name: IdBound,
+    pub kind: SymbolKind,
+    pub detail: Option<String>,
+    /// The range of the whole declaration
+    pub range: Range,
+    /// The range of the name of the declaration
+    pub selection_range: Range,
+}
+
+impl Database {
+    /// The item of the call hierarchy for the definition, codefinition, let binding or destructor
+    /// referenced at the given index
+    pub async fn call_hierarchy_item(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> Option<HierarchyItem> {
+        let reference = self.reference_at_index(uri, idx).await?;
+        let item = self.hierarchy_item(&reference.name).await?;
+        is_callable(item.kind).then_some(item)
+    }
+
+    /// The declarations calling the given one, together with the ranges of the calls
+    pub async fn incoming_calls(&mut self, name: &IdBound) -> Vec<(HierarchyItem, Vec<Range>)> {
+        let mut calls: Vec<(HierarchyItem, Vec<Range>)> = vec![];
+        for (module, span) in self.references(name, false).await {
+            // A codefinition implementing a destructor does not call it.
+            if self.reference_at_index(&module, span.start).await.is_some_and(|r| r.is_pattern) {
+                continue;
+            }
+            let Ok(ust) = self.ust(&module).await else {
+                continue;
+            };
+            let Some(caller) = ust.decls.iter().find_map(|decl| callable_decl(decl, span)) else {
+                continue;
+            };
+            let caller = IdBound { span: None, id: caller.id.clone(), uri: module.clone() };
+            let Some(range) = self.span_to_locations(&module, span) else {
+                continue;
+            };
+            match calls.iter_mut().find(|(item, _)| item.name == caller) {
+                Some((_, ranges)) => ranges.push(range),
+                None => {
+                    if let Some(item) = self.hierarchy_item(&caller).await {
+                        calls.push((item, vec![range]));
+                    }
+                }
+            }
+        }
+        calls
+    }
+
+    /// The declarations called by the given one, together with the ranges of the calls
+    pub async fn outgoing_calls(&mut self, name: &IdBound) -> Vec<(HierarchyItem, Vec<Range>)> {
+        let Ok(ust) = self.ust(&name.uri).await else {
+            return vec![];
+        };
+        let Some(span) = ust.lookup_decl(name).and_then(|decl| decl.span()) else {
+            return vec![];
+        };
+        let Ok(lapper) = self.references_by_id(&name.uri).await else {
+            return vec![];
+        };
+
+        let mut occurrences: Vec<_> = lapper
+            .find(span.start.0, span.end.0)
+            .filter(|interval| !interval.val.is_declaration && !interval.val.is_pattern)
+            .map(|interval| {
+                let span = Span { start: ByteIndex(interval.start), end: ByteIndex(interval.stop) };
+                (interval.val.name.clone(), span)
+            })
+            .collect();
+        occurrences.sort_by_key(|(_, span)| span.start);
+
+        let mut calls: Vec<(HierarchyItem, Vec<Range>)> = vec![];
+        for (callee, span) in occurrences {
+            let Some(range) = self.span_to_locations(&name.uri, span) else {
+                continue;
+            };
+            match calls.iter_mut().find(|(item, _)| item.name == callee) {
+                Some((_, ranges)) => ranges.push(range),
+                None => {
+                    if let Some(item) = self.hierarchy_item(&callee).await
+                        && is_callable(item.kind)
+                    {
+                        calls.push((item, vec![range]));
+                    }
+                }
+            }
+        }
+        calls
+    }
+
+    /// The item of the type hierarchy for the type, constructor, destructor, definition or
+    /// codefinition referenced at the given index
+    pub async fn type_hierarchy_item(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> Option<HierarchyItem> {
+        let reference = self.reference_at_index(uri, idx).await?;
+        let item = self.hierarchy_item(&reference.name).await?;
+        (item.kind != SymbolKind::FUNCTION).then_some(item)
+    }
+
+    /// The type to which a constructor, destructor, definition or codefinition belongs
+    pub async fn supertypes(&mut self, name: &IdBound) -> Vec<HierarchyItem> {
+        let Ok(ust) = self.ust(&name.uri).await else {
+            return vec![];
+        };
+        let type_name = ust.decls.iter().find_map(|decl| match decl {
+            Decl::Data(data) if data.ctors.iter().any(|ctor| ctor.name.id == name.id) => {
+                Some(IdBound { span: None, id: data.name.id.clone(), uri: name.uri.clone() })
+            }
+            Decl::Codata(codata) if codata.dtors.iter().any(|dtor| dtor.name.id == name.id) => {
+                Some(IdBound { span: None, id: codata.name.id.clone(), uri: name.uri.clone() })
+            }
+            Decl::Def(def) if def.name.id == name.id => Some(def.self_param.typ.name.clone()),
+            Decl::Codef(codef) if codef.name.id == name.id => Some(codef.typ.name.clone()),
+            _ => None,
+        });
+        match type_name {
+            Some(type_name) => self.hierarchy_item(&type_name).await.into_iter().collect(),
+            None => vec![],
+        }
+    }
+
+    /// The row or column of the given type in the expression-problem matrix of its module
+    ///
+    /// For a data type, these are its constructors followed by the definitions matching on it.
+    /// For a codata type, these are its destructors followed by the codefinitions producing it.
+    pub async fn subtypes(&mut self, name: &IdBound) -> Vec<HierarchyItem> {
+        let Ok(ust) = self.ust(&name.uri).await else {
+            return vec![];
+        };
+        let Ok(prg) = matrix::build(&ust) else {
+            return vec![];
+        };
+        let Some(xdata) = prg.map.get(&name.id) else {
+            return vec![];
+        };
+
+        let mut ctors: Vec<(Option<Span>, &String)> =
+            xdata.ctors.values().map(|ctor| (ctor.span, &ctor.name.id)).collect();
+        let mut dtors: Vec<(Option<Span>, &String)> =
+            xdata.dtors.values().map(|dtor| (dtor.span, &dtor.name.id)).collect();
+        ctors.sort_by_key(|(span, _)| span.map(|span| span.start));
+        dtors.sort_by_key(|(span, _)| span.map(|span| span.start));
+        let names: Vec<String> = match xdata.repr {
+            Repr::Data => ctors.into_iter().chain(dtors).map(|(_, id)| id.clone()).collect(),
+            Repr::Codata => dtors.into_iter().chain(ctors).map(|(_, id)| id.clone()).collect(),
+        };
+
+        let mut items = vec![];
+        for id in names {
+            let name = IdBound { span: None, id, uri: name.uri.clone() };
+            items.extend(self.hierarchy_item(&name).await);
+        }
+        items
+    }
+
+    /// Find the declaration of the given name, including constructors and destructors
+    pub async fn hierarchy_item(&mut self, name: &IdBound) -> Option<HierarchyItem> {
+        let ust = self.ust(&name.uri).await.ok()?;
+        let (kind, span, id, params) = ust.decls.iter().find_map(|decl| match decl {
+            Decl::Data(Data { span, name: id, typ, ctors, .. }) => {
+                if id.id == name.id {
+                    return Some((SymbolKind::ENUM, *span, id, Some(&**typ)));
+                }
+                ctors.iter().find(|ctor| ctor.name.id == name.id).map(|ctor| {
+                    (SymbolKind::ENUM_MEMBER, ctor.span, &ctor.name, Some(&ctor.params))
+                })
+            }
+            Decl::Codata(Codata { span, name: id, typ, dtors, .. }) => {
+                if id.id == name.id {
+                    return Some((SymbolKind::INTERFACE, *span, id, Some(&**typ)));
+                }
+                dtors
+                    .iter()
+                    .find(|dtor| dtor.name.id == name.id)
+                    .map(|dtor| (SymbolKind::METHOD, dtor.span, &dtor.name, Some(&dtor.params)))
+            }
+            Decl::Def(Def { span, name: id, params, .. }) if id.id == name.id => {
+                Some((SymbolKind::METHOD, *span, id, Some(params)))
+            }
+            Decl::Codef(Codef { span, name: id, params, .. }) if id.id == name.id => {
+                Some((SymbolKind::CONSTRUCTOR, *span, id, Some(params)))
+            }
+            Decl::Let(Let { span, name: id, params, .. }) if id.id == name.id => {
+                Some((SymbolKind::FUNCTION, *span, id, Some(params)))
+            }
+            _ => None,
+        })?;
+
+        let range = self.span_to_locations(&name.uri, span?)?;
+        let selection_range = self.span_to_locations(&name.uri, id.span?)?;
+        let detail =
+            params.map(|params| params.print_to_string(None)).filter(|params| !params.is_empty());
+        Some(HierarchyItem {
+            name: IdBound { span: id.span, id: id.id.clone(), uri: name.uri.clone() },
+            kind,
+            detail,
+            range,
+            selection_range,
+        })
+    }
+}
+
+/// Whether items of this kind can appear in the call hierarchy
+///
+/// These are definitions and destructors, codefinitions and let bindings.
+fn is_callable(kind: SymbolKind) -> bool {
+    kind == SymbolKind::METHOD || kind == SymbolKind::CONSTRUCTOR || kind == SymbolKind::FUNCTION
+}
+
+/// The name of the top-level declaration with a body which contains the given span
+fn callable_decl(decl: &Decl, span: Span) -> Option<&IdBind> {
+    let (decl_span, name) = match decl {
+        Decl::Def(Def { span, name, .. })
+        | Decl::Codef(Codef { span, name, .. })
+        | Decl::Let(Let { span, name, .. }) => ((*span)?, name),
+        _ => return None,
+    };
+    (decl_span.start <= span.start && span.end <= decl_span.end).then_some(name)
+}
@@ -0,0 +1,164 @@
+//! Goals and code actions for holes
+//!
+//! Both only rely on the information which elaboration records for each hole:
+//! its expected type, the variables in scope and, possibly, the solution of its metavariable.
+
+use polarity_lang_ast::*;
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use polarity_lang_printer::tokens::COLON;
+use polarity_lang_printer::{Alloc, Builder, DocAllocator, Print, PrintCfg};
+use url::Url;
+
+use crate::database::Database;
+use crate::info::HoleInfo;
+use crate::result::AppResult;
+
+use super::Edit;
+
+/// An unsolved hole `?` together with its expected type and the typed local context
+pub struct Goal {
+    pub span: Span,
+    pub metavar: MetaVar,
+    pub goal: Option<Box<Exp>>,
+    /// The named variables in scope, innermost last
+    pub vars: Vec<(String, Box<Exp>)>,
+}
+
+pub struct HoleAction {
+    pub title: String,
+    pub edit: Edit,
+}
+
+impl Database {
+    /// The unsolved holes `?` of a module, ordered by their position
+    pub async fn goals(&mut self, uri: &Url) -> AppResult<Vec<Goal>> {
+        let holes = self.holes_by_id(uri).await?;
+        let mut goals: Vec<Goal> = holes
+            .iter()
+            .filter(|interval| {
+                matches!(interval.val.metavar.kind, MetaVarKind::CanSolve)
+                    && interval.val.solution.is_none()
+            })
+            .map(|interval| {
+                let HoleInfo { metavar, goal, vars, .. } = interval.val.clone();
+                let span = Span { start: ByteIndex(interval.start), end: ByteIndex(interval.stop) };
+                Goal { span, metavar, goal, vars }
+            })
+            .collect();
+        goals.sort_by_key(|goal| goal.span.start);
+        // The body of a case with nested patterns can occur on several paths of the compiled match.
+        goals.dedup_by_key(|goal| goal.span);
+        Ok(goals)
+    }
+
+    /// The code actions which replace the hole at the given span
+    pub async fn hole_actions(&mut self, uri: &Url, span: Span) -> AppResult<Vec<HoleAction>> {
+        let Some((span, hole)) = self.hole_at_span(uri, span).await else {
+            return Ok(vec![]);
+        };
+        let info_table = self.type_info_table(uri).await?;
+        let HoleInfo { goal, vars, solution, .. } = hole;
+
+        let mut actions = vec![];
+
+        if let Some(solution) = solution {
+            let text = solution.print_to_string(None);
+            actions.push(HoleAction {
+                title: format!("Fill hole with {text}"),
+                edit: Edit { span, text },
+            });
+        }
+
+        if let Some(goal) = goal.and_then(|goal| (*goal).to_typctor())
+            && let Ok(codata) = info_table.lookup_codata(&goal.name)
+        {
+            let cases = codata.dtors.iter().map(|dtor| {
+                format!(".{}{} => ?", dtor.name.id, pattern_params(&dtor.params, &vars))
+            });
+            actions.push(HoleAction {
+                title: "Introduce comatch".to_owned(),
+                edit: Edit { span, text: format!("comatch {{ {} }}", comma_separated(cases)) },
+            });
+        }
+
+        for (idx, (name, typ)) in vars.iter().enumerate().rev() {
+            // Shadowed variables cannot be referred to at the hole.
+            if vars[idx + 1..].iter().any(|(other, _)| other == name) {
+                continue;
+            }
+            let Some(typ) = (**typ).clone().to_typctor() else {
+                continue;
+            };
+            let Ok(data) = info_table.lookup_data(&typ.name) else {
+                continue;
+            };
+            let cases = data.ctors.iter().map(|ctor| {
+                format!("{}{} => ?", ctor.name.id, pattern_params(&ctor.params, &vars))
+            });
+            actions.push(HoleAction {
+                title: format!("Case split on {name}"),
+                edit: Edit { span, text: format!("{name}.match {{ {} }}", comma_separated(cases)) },
+            });
+        }
+
+        Ok(actions)
+    }
+}
+
+impl Goal {
+    pub fn name(&self) -> String {
+        format!("?{}", self.metavar.id)
+    }
+}
+
+/// Goals are printed like in Agda's goal buffer: the expected type is followed by a rule
+/// and the local context, starting with the innermost variable.
+impl Print for Goal {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let Goal { goal, vars, .. } = self;
+        let goal = match goal {
+            Some(goal) => goal.print(cfg, alloc),
+            None => alloc.text("?"),
+        };
+        let head = alloc.text(format!("Goal {}", self.name())).append(COLON).append(alloc.space());
+        let ctx = vars.iter().rev().map(|(name, typ)| {
+            alloc
+                .hardline()
+                .append(alloc.text(name.as_str()))
+                .append(alloc.space())
+                .append(COLON)
+                .append(alloc.space())
+                .append(typ.print(cfg, alloc))
+        });
+        head.append(goal)
+            .append(alloc.hardline())
+            .append(alloc.text(RULE))
+            .append(alloc.concat(ctx))
+    }
+}
+
+const RULE: &str = "————————————————————————————————————————";
+
+/// Print the parameters of a constructor or destructor as the parameters of a (co)pattern
+///
+/// Names which are already bound at the hole are primed so that they do not shadow these variables.
+pub(crate) fn pattern_params(params: &Telescope, vars: &[(String, Box<Exp>)]) -> String {
+    if params.params.is_empty() {
+        return String::new();
+    }
+    let mut names: Vec<String> = vec![];
+    for param in params.params.iter() {
+        let mut name = param.name.to_string();
+        if let VarBind::Var { .. } = param.name {
+            while vars.iter().any(|(var, _)| *var == name) || names.contains(&name) {
+                name.push('\'');
+            }
+        }
+        names.push(name);
+    }
+    format!("({})", names.join(", "))
+}
+
+fn comma_separated(iter: impl Iterator<Item = String>) -> String {
+    iter.collect::<Vec<_>>().join(", ")
+}
@@ -10,18 +10,39 @@ use crate::Database;
 use crate::info::lookup::lookup_extern;
 use crate::result::AppResult;
 
-use super::item::Item;
+use super::item::{HoleInfo, Item, MatchInfo, Reference};
 use super::lookup::{lookup_codef, lookup_ctor, lookup_decl, lookup_def, lookup_dtor, lookup_let};
 use super::{Binder, Ctx};
 
+/// Source-code indexed information about a module, collected for the LSP server.
+pub struct Info {
+    /// Hover information for spans
+    pub hover: Lapper<u32, HoverContents>,
+    /// Definition sites of the names used at spans
+    pub goto: Lapper<u32, (Url, Span)>,
+    /// Spans of top-level items
+    pub items: Lapper<u32, Item>,
+    /// Inferred types of the expressions at spans
+    pub types: Lapper<u32, Box<Exp>>,
+    /// Spans of pattern matches
+    pub matches: Lapper<u32, MatchInfo>,
+    /// Occurrences of names of top-level declarations
+    pub references: Lapper<u32, Reference>,
+    /// Spans of holes
+    pub holes: Lapper<u32, HoleInfo>,
+}
+
 /// Traverse the program and collect information for the LSP server.
-#[allow(clippy::type_complexity)]
-pub async fn collect_info(
-    db: &mut Database,
-    uri: &Url,
-) -> AppResult<(Lapper<u32, HoverContents>, Lapper<u32, (Url, Span)>, Lapper<u32, Item>)> {
+pub async fn collect_info(db: &mut Database, uri: &Url) -> AppResult<Info> {
     let module = db.ast(uri).await?;
-    let mut collector = InfoCollector::new(module.meta_vars.clone());
+    collect_module_info(db, uri, &module)
+}
+
+/// Traverse the given module and collect information for the LSP server.
+///
+/// In contrast to [collect_info], the module does not have to be the one stored in the database for `uri`.
+pub fn collect_module_info(db: &Database, uri: &Url, module: &Module) -> AppResult<Info> {
+    let mut collector = InfoCollector::new(uri.clone(), module.meta_vars.clone());
 
     for use_decl in module.use_decls.iter() {
         // Add hover info
@@ -38,10 +59,15 @@ pub async fn collect_info(
         decl.collect_info(db, &mut collector)
     }
 
-    let hover_lapper = Lapper::new(collector.hover_spans);
-    let location_lapper = Lapper::new(collector.location_spans);
-    let item_lapper = Lapper::new(collector.item_spans);
-    Ok((hover_lapper, location_lapper, item_lapper))
+    Ok(Info {
+        hover: Lapper::new(collector.hover_spans),
+        goto: Lapper::new(collector.location_spans),
+        items: Lapper::new(collector.item_spans),
+        types: Lapper::new(collector.type_spans),
+        matches: Lapper::new(collector.match_spans),
+        references: Lapper::new(collector.reference_spans),
+        holes: Lapper::new(collector.hole_spans),
+    })
 }
 
 fn string_to_language_string(s: String) -> MarkedString {
@@ -67,15 +93,30 @@ fn separated<I: IntoIterator<Item = String>>(s: &str, iter: I) -> String {
 }
 
 struct InfoCollector {
+    uri: Url,
     meta_vars: HashMap<MetaVar, MetaVarState>,
     hover_spans: Vec<Interval<u32, HoverContents>>,
     location_spans: Vec<Interval<u32, (Url, Span)>>,
     item_spans: Vec<Interval<u32, Item>>,
+    type_spans: Vec<Interval<u32, Box<Exp>>>,
+    match_spans: Vec<Interval<u32, MatchInfo>>,
+    reference_spans: Vec<Interval<u32, Reference>>,
+    hole_spans: Vec<Interval<u32, HoleInfo>>,
 }
 
 impl InfoCollector {
-    fn new(meta_vars: HashMap<MetaVar, MetaVarState>) -> Self {
-        InfoCollector { meta_vars, hover_spans: vec![], location_spans: vec![], item_spans: vec![] }
+    fn new(uri: Url, meta_vars: HashMap<MetaVar, MetaVarState>) -> Self {
+        InfoCollector {
+            uri,
+            meta_vars,
+            hover_spans: vec![],
+            location_spans: vec![],
+            item_spans: vec![],
+            type_spans: vec![],
+            match_spans: vec![],
+            reference_spans: vec![],
+            hole_spans: vec![],
+        }
     }
 
     fn add_hover(&mut self, span: Span, hover: HoverContents) {
@@ -83,6 +124,41 @@ impl InfoCollector {
         self.hover_spans.push(hover)
     }
 
+    /// Render the hover of a type constructor, call or dot-call as markdown
+    ///
+    /// The optional type is the inferred type of the call.
+    fn signature_hover(
+        &self,
+        title: String,
+        signature: Option<Signature>,
+        typ: Option<String>,
+    ) -> HoverContents {
+        let mut value = title;
+        if let Some(Signature { uri, header, doc, parent }) = signature {
+            value.push_str(&format!("\n\n```pol\n{header}\n```\n\n"));
+            let module = if uri == self.uri {
+                "this module".to_owned()
+            } else {
+                let path = self.uri.make_relative(&uri).unwrap_or_else(|| uri.to_string());
+                format!("`{path}`")
+            };
+            match parent {
+                Some(parent) => {
+                    value.push_str(&format!("Belongs to {parent}, defined in {module}"))
+                }
+                None => value.push_str(&format!("Defined in {module}")),
+            }
+            if let Some(doc) = doc {
+                value.push_str("\n\n---\n\n");
+                value.push_str(&doc.join("\n"));
+            }
+        }
+        if let Some(typ) = typ {
+            value.push_str(&format!("\n\n---\n\n```pol\n{typ}\n```"));
+        }
+        HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value })
+    }
+
     fn add_goto(&mut self, span: Span, location: (Url, Span)) {
         let goto = Interval { start: span.start.0, stop: span.end.0, val: location };
         self.location_spans.push(goto)
@@ -92,6 +168,49 @@ impl InfoCollector {
         let item = Interval { start: span.start.0, stop: span.end.0, val: item };
         self.item_spans.push(item)
     }
+
+    fn add_type(&mut self, span: Span, typ: Box<Exp>) {
+        let typ = Interval { start: span.start.0, stop: span.end.0, val: typ };
+        self.type_spans.push(typ)
+    }
+
+    fn add_match(&mut self, span: Span, info: MatchInfo) {
+        let info = Interval { start: span.start.0, stop: span.end.0, val: info };
+        self.match_spans.push(info)
+    }
+
+    fn add_hole(&mut self, span: Span, info: HoleInfo) {
+        let info = Interval { start: span.start.0, stop: span.end.0, val: info };
+        self.hole_spans.push(info)
+    }
+
+    /// Add the occurrence of a top-level name in its declaration
+    fn add_declaration(&mut self, name: &IdBind) {
+        if let Some(span) = name.span {
+            let name = IdBound { span: name.span, id: name.id.clone(), uri: self.uri.clone() };
+            let reference = Reference { name, is_declaration: true, is_pattern: false };
+            let reference = Interval { start: span.start.0, stop: span.end.0, val: reference };
+            self.reference_spans.push(reference)
+        }
+    }
+
+    /// Add the occurrence of a top-level name outside of its declaration
+    fn add_reference(&mut self, name: &IdBound) {
+        self.add_occurrence(name, false)
+    }
+
+    /// Add the occurrence of a constructor or destructor as the name of a (co)pattern
+    fn add_pattern_reference(&mut self, name: &IdBound) {
+        self.add_occurrence(name, true)
+    }
+
+    fn add_occurrence(&mut self, name: &IdBound, is_pattern: bool) {
+        if let Some(span) = name.span {
+            let reference = Reference { name: name.clone(), is_declaration: false, is_pattern };
+            let reference = Interval { start: span.start.0, stop: span.end.0, val: reference };
+            self.reference_spans.push(reference)
+        }
+    }
 }
 
 /// Every syntax node which implements this trait can be traversed and
@@ -149,6 +268,7 @@ impl CollectInfo for Decl {
 impl CollectInfo for Data {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let Data { name, span, doc, typ, ctors, .. } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add item
             let item = Item::Data(name.clone().id);
@@ -178,6 +298,7 @@ impl CollectInfo for Data {
 impl CollectInfo for Codata {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let Codata { name, doc, typ, span, dtors, .. } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add item
             let item = Item::Codata(name.clone().id);
@@ -207,6 +328,7 @@ impl CollectInfo for Codata {
 impl CollectInfo for Def {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let Def { name, span, self_param, cases, params, ret_typ, .. } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add Item
             let item =
@@ -216,6 +338,9 @@ impl CollectInfo for Def {
             let header = MarkedString::String("Definition".to_owned());
             let hover_contents = HoverContents::Scalar(header);
             collector.add_hover(*span, hover_contents);
+            // Add match info
+            let info = MatchInfo::new(Some(self_param.typ.name.clone()), cases);
+            collector.add_match(*span, info);
         };
 
         self_param.collect_info(db, collector);
@@ -228,6 +353,7 @@ impl CollectInfo for Def {
 impl CollectInfo for Codef {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let Codef { name, span, typ, cases, params, .. } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add item
             let item = Item::Codef { name: name.clone().id, type_name: typ.name.clone().id };
@@ -245,7 +371,8 @@ impl CollectInfo for Codef {
 
 impl CollectInfo for Ctor {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Ctor { span, name, doc, typ, params } = self;
+        let Ctor { span, name, doc, comments: _, typ_comments: _, typ, params } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add info
             let doc = doc.clone().map(|doc| doc.docs);
@@ -262,7 +389,9 @@ impl CollectInfo for Ctor {
 
 impl CollectInfo for Dtor {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Dtor { span, name, doc, self_param, params, ret_typ } = self;
+        let Dtor { span, name, doc, comments: _, self_param, params, ret_typ_comments: _, ret_typ } =
+            self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add info
             let doc = doc.clone().map(|doc| doc.docs);
@@ -280,7 +409,8 @@ impl CollectInfo for Dtor {
 
 impl CollectInfo for Let {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Let { span, typ, body, params, .. } = self;
+        let Let { span, name, typ, body, params, .. } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add hover info
             let header = MarkedString::String("Let-binding".to_owned());
@@ -295,7 +425,8 @@ impl CollectInfo for Let {
 
 impl CollectInfo for Extern {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Extern { span, typ, params, .. } = self;
+        let Extern { span, name, typ, params, .. } = self;
+        collector.add_declaration(name);
         if let Some(span) = span {
             // Add hover info
             let header = MarkedString::String("Extern declaration".to_owned());
@@ -325,6 +456,9 @@ impl CollectInfo for Infix {
 
 impl CollectInfo for Exp {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
+        if let (Some(span), Some(typ)) = (self.span(), self.typ()) {
+            collector.add_type(span, typ);
+        }
         match self {
             Exp::Variable(e) => e.collect_info(db, collector),
             Exp::TypCtor(e) => e.collect_info(db, collector),
@@ -334,6 +468,7 @@ impl CollectInfo for Exp {
             Exp::TypeUniv(e) => e.collect_info(db, collector),
             Exp::Anno(e) => e.collect_info(db, collector),
             Exp::LocalMatch(e) => e.collect_info(db, collector),
+            Exp::CompiledMatch(e) => e.compiled.collect_info(db, collector),
             Exp::LocalComatch(e) => e.collect_info(db, collector),
             Exp::LocalLet(e) => e.collect_info(db, collector),
             Exp::DoBlock(e) => e.collect_info(db, collector),
@@ -357,28 +492,33 @@ impl CollectInfo for Variable {
 
 impl CollectInfo for TypCtor {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let TypCtor { span, args, name, is_bin_op: _ } = self;
+        let TypCtor { span, args, name, is_bin_op } = self;
+        // For binary operator applications, the span of the name lies in the infix declaration.
+        if is_bin_op.is_none() {
+            collector.add_reference(name);
+        }
         if let Some(span) = span {
-            let decl = lookup_decl(db, name);
-            let (definition_site, doc) = match decl {
+            let (definition_site, signature) = match lookup_decl(db, name) {
                 Some((uri, Decl::Data(d))) => {
-                    let definition_site = d.span.map(|span| (uri.clone(), span));
-                    let doc = d.doc.clone().map(|doc| doc.docs);
-                    (definition_site, doc)
+                    let header = format!("data {}{}", d.name, d.typ.print_to_string(None));
+                    (
+                        d.span.map(|span| (uri.clone(), span)),
+                        Some(Signature::new(uri, header, &d.doc)),
+                    )
                 }
                 Some((uri, Decl::Codata(d))) => {
-                    let definition_site = d.span.map(|span| (uri.clone(), span));
-                    let doc = d.doc.clone().map(|doc| doc.docs);
-                    (definition_site, doc)
+                    let header = format!("codata {}{}", d.name, d.typ.print_to_string(None));
+                    (
+                        d.span.map(|span| (uri.clone(), span)),
+                        Some(Signature::new(uri, header, &d.doc)),
+                    )
                 }
                 _ => (None, None),
             };
 
             // Add hover info
-            let mut content: Vec<MarkedString> = Vec::new();
-            content.push(MarkedString::String(format!("Type constructor: `{name}`")));
-            add_doc_comment(&mut content, doc);
-            let hover_contents = HoverContents::Array(content);
+            let title = format!("Type constructor: `{name}`");
+            let hover_contents = collector.signature_hover(title, signature, None);
             collector.add_hover(*span, hover_contents);
 
             // Add goto info
@@ -392,63 +532,63 @@ impl CollectInfo for TypCtor {
 
 impl CollectInfo for Call {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let Call { span, kind, args, inferred_type, name, is_bin_op: _ } = self;
+        let Call { span, kind, args, inferred_type, name, is_bin_op } = self;
+        if is_bin_op.is_none() {
+            collector.add_reference(name);
+        }
         if let (Some(span), Some(typ)) = (span, inferred_type) {
-            let (definition_site, doc) = match kind {
+            let (definition_site, signature) = match kind {
                 CallKind::Constructor => match lookup_ctor(db, name) {
-                    Some((uri, ctor)) => {
+                    Some((uri, data, ctor)) => {
                         let uri_span = ctor.span.map(|span| (uri.clone(), span));
-                        let doc = ctor.doc.clone().map(|doc| doc.docs);
-                        (uri_span, doc)
+                        let header = function_header(None, &ctor.name, &ctor.params, &ctor.typ);
+                        let signature = Signature::new(uri, header, &ctor.doc)
+                            .with_parent(format!("data type `{}`", data.name));
+                        (uri_span, Some(signature))
                     }
                     None => (None, None),
                 },
                 CallKind::Codefinition => match lookup_codef(db, name) {
                     Some((uri, codef)) => {
                         let uri_span = codef.span.map(|span| (uri.clone(), span));
-                        let doc = codef.doc.clone().map(|doc| doc.docs);
-                        (uri_span, doc)
+                        let header =
+                            function_header(Some("codef"), &codef.name, &codef.params, &codef.typ);
+                        (uri_span, Some(Signature::new(uri, header, &codef.doc)))
                     }
                     None => (None, None),
                 },
                 CallKind::LetBound => match lookup_let(db, name) {
                     Some((uri, let_)) => {
                         let uri_span = let_.span.map(|span| (uri.clone(), span));
-                        let doc = let_.doc.clone().map(|doc| doc.docs);
-                        (uri_span, doc)
+                        let header =
+                            function_header(Some("let"), &let_.name, &let_.params, &let_.typ);
+                        (uri_span, Some(Signature::new(uri, header, &let_.doc)))
                     }
                     None => (None, None),
                 },
                 CallKind::Extern => match lookup_extern(db, name) {
                     Some((uri, extern_decl)) => {
                         let uri_span = extern_decl.span.map(|span| (uri.clone(), span));
-                        let doc = extern_decl.doc.clone().map(|doc| doc.docs);
-                        (uri_span, doc)
+                        let header = function_header(
+                            Some("extern"),
+                            &extern_decl.name,
+                            &extern_decl.params,
+                            &extern_decl.typ,
+                        );
+                        (uri_span, Some(Signature::new(uri, header, &extern_decl.doc)))
                     }
                     None => (None, None),
                 },
             };
             // Add hover info
+            let title = match kind {
+                CallKind::Constructor => format!("Constructor: `{}`", name.id),
+                CallKind::Codefinition => format!("Codefinition: `{}`", name.id),
+                CallKind::LetBound => format!("Let-bound definition: `{}`", name.id),
+                CallKind::Extern => format!("Extern declaration: `{}`", name.id),
+            };
             let typ = typ.print_to_string(None);
-            let mut content: Vec<MarkedString> = Vec::new();
-            content.push(match kind {
-                CallKind::Constructor => {
-                    MarkedString::String(format!("Constructor: `{}`", name.id))
-                }
-                CallKind::Codefinition => {
-                    MarkedString::String(format!("Codefinition: `{}`", name.id))
-                }
-                CallKind::LetBound => {
-                    MarkedString::String(format!("Let-bound definition: `{}`", name.id))
-                }
-                CallKind::Extern => {
-                    MarkedString::String(format!("Extern declaration: `{}`", name.id))
-                }
-            });
-            add_doc_comment(&mut content, doc);
-            content.push(MarkedString::String("---".to_owned()));
-            content.push(string_to_language_string(typ));
-            let hover_content = HoverContents::Array(content);
+            let hover_content = collector.signature_hover(title, signature, Some(typ));
             collector.add_hover(*span, hover_content);
 
             // Add goto info
@@ -463,40 +603,47 @@ impl CollectInfo for Call {
 impl CollectInfo for DotCall {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let DotCall { span, kind, exp, args, inferred_type, name } = self;
+        collector.add_reference(name);
         if let (Some(span), Some(typ)) = (span, inferred_type) {
-            let (definition_site, doc) = match kind {
+            let (definition_site, signature) = match kind {
                 DotCallKind::Destructor => match lookup_dtor(db, name) {
-                    Some((uri, dtor)) => {
+                    Some((uri, codata, dtor)) => {
                         let uri_span = dtor.span.map(|span| (uri.clone(), span));
-                        let doc = dtor.doc.clone().map(|doc| doc.docs);
-                        (uri_span, doc)
+                        let header = method_header(
+                            None,
+                            &dtor.self_param,
+                            &dtor.name,
+                            &dtor.params,
+                            &dtor.ret_typ,
+                        );
+                        let signature = Signature::new(uri, header, &dtor.doc)
+                            .with_parent(format!("codata type `{}`", codata.name));
+                        (uri_span, Some(signature))
                     }
                     None => (None, None),
                 },
                 DotCallKind::Definition => match lookup_def(db, name) {
                     Some((uri, def)) => {
                         let uri_span = def.span.map(|span| (uri.clone(), span));
-                        let doc = def.doc.clone().map(|doc| doc.docs);
-                        (uri_span, doc)
+                        let header = method_header(
+                            Some("def"),
+                            &def.self_param,
+                            &def.name,
+                            &def.params,
+                            &def.ret_typ,
+                        );
+                        (uri_span, Some(Signature::new(uri, header, &def.doc)))
                     }
                     None => (None, None),
                 },
             };
             // Add hover info
+            let title = match kind {
+                DotCallKind::Destructor => format!("Destructor: `{}`", name.id),
+                DotCallKind::Definition => format!("Definition: `{}`", name.id),
+            };
             let typ = typ.print_to_string(None);
-            let mut content: Vec<MarkedString> = Vec::new();
-            content.push(match kind {
-                DotCallKind::Destructor => {
-                    MarkedString::String(format!("Destructor: `{}`", name.id))
-                }
-                DotCallKind::Definition => {
-                    MarkedString::String(format!("Definition: `{}`", name.id))
-                }
-            });
-            add_doc_comment(&mut content, doc);
-            content.push(MarkedString::String("---".to_owned()));
-            content.push(string_to_language_string(typ));
-            let hover_content = HoverContents::Array(content);
+            let hover_content = collector.signature_hover(title, signature, Some(typ));
             collector.add_hover(*span, hover_content);
 
             // Add goto info
@@ -509,6 +656,55 @@ impl CollectInfo for DotCall {
     }
 }
 
+/// The declaration referenced by a type constructor, call or dot-call, as shown on hover
+struct Signature {
+    /// The module in which the declaration is defined
+    uri: Url,
+    /// The head of the declaration with all of its parameters, including the implicit ones
+    header: String,
+    doc: Option<Vec<String>>,
+    /// The data or codata type to which a constructor or destructor belongs
+    parent: Option<String>,
+}
+
+impl Signature {
+    fn new(uri: Url, header: String, doc: &Option<DocComment>) -> Self {
+        Signature { uri, header, doc: doc.clone().map(|doc| doc.docs), parent: None }
+    }
+
+    fn with_parent(self, parent: String) -> Self {
+        Signature { parent: Some(parent), ..self }
+    }
+}
+
+/// The head of a constructor, codefinition, let binding or extern declaration
+fn function_header<T: Print>(
+    keyword: Option<&str>,
+    name: &IdBind,
+    params: &Telescope,
+    typ: &T,
+) -> String {
+    let keyword = keyword.map(|keyword| format!("{keyword} ")).unwrap_or_default();
+    format!("{keyword}{name}{}: {}", params.print_to_string(None), typ.print_to_string(None))
+}
+
+/// The head of a destructor or definition
+fn method_header(
+    keyword: Option<&str>,
+    self_param: &SelfParam,
+    name: &IdBind,
+    params: &Telescope,
+    ret_typ: &Exp,
+) -> String {
+    let keyword = keyword.map(|keyword| format!("{keyword} ")).unwrap_or_default();
+    format!(
+        "{keyword}{}.{name}{}: {}",
+        self_param.print_to_string(None),
+        params.print_to_string(None),
+        ret_typ.print_to_string(None)
+    )
+}
+
 fn ctx_to_markdown(ctx: &Ctx, value: &mut String) {
     value.push_str("**Context**\n\n");
     value.push_str("| | |\n");
@@ -536,8 +732,24 @@ fn goal_to_markdown(goal_type: &str, value: &mut String) {
 
 impl CollectInfo for Hole {
     fn collect_info(&self, _db: &Database, collector: &mut InfoCollector) {
-        let Hole { span, kind: _, metavar, inferred_type, inferred_ctx, args, solution: _ } = self;
+        let Hole { span, kind: _, metavar, inferred_type, inferred_ctx, args, solution } = self;
         if let Some(span) = span {
+            let vars = inferred_ctx
+                .iter()
+                .flat_map(|ctx| ctx.bound.iter().flatten())
+                .filter_map(|binder| match &binder.name {
+                    VarBind::Var { id, .. } => Some((id.clone(), binder.content.typ.clone())),
+                    VarBind::Wildcard { .. } => None,
+                })
+                .collect();
+            let hole_info = HoleInfo {
+                metavar: *metavar,
+                goal: inferred_type.clone(),
+                vars,
+                solution: solution.clone(),
+            };
+            collector.add_hole(*span, hole_info);
+
             let metavar_state = collector
                 .meta_vars
                 .get(metavar)
@@ -632,6 +844,13 @@ impl CollectInfo for LocalMatch {
             let hover_contents = HoverContents::Array(vec![header, typ]);
             collector.add_hover(*span, hover_contents)
         }
+        if let Some(span) = span {
+            // Add match info
+            let type_name =
+                on_exp.typ().and_then(|typ| typ.to_typctor()).map(|typctor| typctor.name);
+            let info = MatchInfo::new(type_name, cases);
+            collector.add_match(*span, info);
+        }
         on_exp.collect_info(db, collector);
         ret_typ.collect_info(db, collector);
         cases.collect_info(db, collector)
@@ -656,6 +875,7 @@ impl CollectInfo for LocalComatch {
 impl CollectInfo for Pattern {
     fn collect_info(&self, _db: &Database, collector: &mut InfoCollector) {
         let Pattern { span, name, is_copattern, .. } = self;
+        collector.add_pattern_reference(name);
         if let Some(span) = span {
             let hover_contents = if *is_copattern {
                 HoverContents::Array(vec![MarkedString::String(format!(
@@ -685,7 +905,7 @@ impl CollectInfo for Case {
 
 impl CollectInfo for LocalLet {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
-        let LocalLet { span, name, typ, bound, body, inferred_type } = self;
+        let LocalLet { span, comments: _, name, typ, bound, body, inferred_type } = self;
         typ.collect_info(db, collector);
         bound.collect_info(db, collector);
         body.collect_info(db, collector);
@@ -718,7 +938,7 @@ impl CollectInfo for DoBlock {
 impl CollectInfo for DoStatements {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         match self {
-            DoStatements::Bind { span, name, bound, body, inferred_type } => {
+            DoStatements::Bind { span, comments: _, name, bound, body, inferred_type } => {
                 bound.collect_info(db, collector);
                 body.collect_info(db, collector);
                 if let Some(typ) = inferred_type {
@@ -730,7 +950,7 @@ impl CollectInfo for DoStatements {
                     collector.add_hover(*span, hover_content)
                 }
             }
-            DoStatements::Let { span, name, typ, bound, body, inferred_type } => {
+            DoStatements::Let { span, comments: _, name, typ, bound, body, inferred_type } => {
                 typ.collect_info(db, collector);
                 bound.collect_info(db, collector);
                 body.collect_info(db, collector);
@@ -743,7 +963,7 @@ impl CollectInfo for DoStatements {
                     collector.add_hover(*span, hover_content)
                 }
             }
-            DoStatements::Return { span, exp, inferred_type } => {
+            DoStatements::Return { span, comments: _, exp, inferred_type } => {
                 exp.collect_info(db, collector);
                 if let Some(typ) = inferred_type {
                     // Add info
@@ -760,7 +980,7 @@ impl CollectInfo for DoStatements {
 
 impl CollectInfo for Note {
     fn collect_info(&self, _db: &Database, collector: &mut InfoCollector) {
-        let Note { name, span, doc, attr: _ } = self;
+        let Note { name, span, doc, comments: _, attr: _ } = self;
         if let Some(span) = span {
             // Add hover info
             let mut content: Vec<MarkedString> = Vec::new();
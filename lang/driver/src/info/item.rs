@@ -1,3 +1,6 @@
+use polarity_lang_ast::{Case, Exp, IdBound, MetaVar};
+use polarity_lang_miette_util::codespan::Span;
+
 // Item
 //
 //
@@ -20,3 +23,60 @@ impl Item {
         }
     }
 }
+
+// MatchInfo
+//
+//
+
+/// A pattern match, used to find the constructors that are not covered yet
+#[derive(PartialEq, Eq, Clone)]
+pub struct MatchInfo {
+    /// The name of the type being matched on, if it is known
+    pub type_name: Option<IdBound>,
+    /// The names of the constructors for which there is already a case
+    pub covered: Vec<String>,
+    /// The spans of the cases
+    pub case_spans: Vec<Span>,
+}
+
+impl MatchInfo {
+    pub fn new(type_name: Option<IdBound>, cases: &[Case]) -> Self {
+        MatchInfo {
+            type_name,
+            covered: cases.iter().map(|case| case.pattern.name.id.clone()).collect(),
+            case_spans: cases.iter().filter_map(|case| case.span).collect(),
+        }
+    }
+}
+
+// Reference
+//
+//
+
+/// An occurrence of the name of a top-level declaration
+#[derive(PartialEq, Eq, Clone)]
+pub struct Reference {
+    /// The name of the declaration, together with the module in which it is declared
+    pub name: IdBound,
+    /// Whether this is the occurrence in the declaration itself
+    pub is_declaration: bool,
+    /// Whether this is the name of a pattern or copattern
+    pub is_pattern: bool,
+}
+
+// HoleInfo
+//
+//
+
+/// A hole written by the user, used for the code actions which fill it and to list the open goals
+#[derive(PartialEq, Eq, Clone)]
+pub struct HoleInfo {
+    /// The metavariable which stands for the hole
+    pub metavar: MetaVar,
+    /// The expected type of the hole
+    pub goal: Option<Box<Exp>>,
+    /// The named variables in scope at the hole together with their types, innermost last
+    pub vars: Vec<(String, Box<Exp>)>,
+    /// The solution of the metavariable, if one has been found during elaboration
+    pub solution: Option<Box<Exp>>,
+}
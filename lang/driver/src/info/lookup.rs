@@ -1,4 +1,4 @@
-use polarity_lang_ast::{Codef, Ctor, Decl, Def, Dtor, Extern, IdBound, Let};
+use polarity_lang_ast::{Codata, Codef, Ctor, Data, Decl, Def, Dtor, Extern, IdBound, Let};
 use url::Url;
 
 use crate::Database;
@@ -12,13 +12,16 @@ pub fn lookup_decl<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Dec
     Some((name.uri.clone(), decl))
 }
 
-pub fn lookup_ctor<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Ctor)> {
+/// Look up a constructor together with the data type to which it belongs
+pub fn lookup_ctor<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Data, &'a Ctor)> {
     let module = db.ust.get_unless_stale(&name.uri)?.as_ref().ok()?;
-    let ctor = module.decls.iter().find_map(|decl| match decl {
-        Decl::Data(data) => data.ctors.iter().find(|ctor| &ctor.name == name),
+    let (data, ctor) = module.decls.iter().find_map(|decl| match decl {
+        Decl::Data(data) => {
+            data.ctors.iter().find(|ctor| &ctor.name == name).map(|ctor| (data, ctor))
+        }
         _ => None,
     })?;
-    Some((name.uri.clone(), ctor))
+    Some((name.uri.clone(), data, ctor))
 }
 
 pub fn lookup_codef<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Codef)> {
@@ -48,13 +51,16 @@ pub fn lookup_extern<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a E
     Some((name.uri.clone(), extern_decl))
 }
 
-pub fn lookup_dtor<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Dtor)> {
+/// Look up a destructor together with the codata type to which it belongs
+pub fn lookup_dtor<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Codata, &'a Dtor)> {
     let module = db.ust.get_unless_stale(&name.uri)?.as_ref().ok()?;
-    let dtor = module.decls.iter().find_map(|decl| match decl {
-        Decl::Codata(codata) => codata.dtors.iter().find(|dtor| &dtor.name == name),
+    let (codata, dtor) = module.decls.iter().find_map(|decl| match decl {
+        Decl::Codata(codata) => {
+            codata.dtors.iter().find(|dtor| &dtor.name == name).map(|dtor| (codata, dtor))
+        }
         _ => None,
     })?;
-    Some((name.uri.clone(), dtor))
+    Some((name.uri.clone(), codata, dtor))
 }
 
 pub fn lookup_def<'a>(db: &'a Database, name: &IdBound) -> Option<(Url, &'a Def)> {
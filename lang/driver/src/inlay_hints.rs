@@ -0,0 +1,312 @@
+//! Inlay hints for the LSP server
+//!
+//! Inlay hints make information visible which is inferred during elaboration:
+//! the solutions of implicit arguments which have been omitted, the types of variables
+//! bound by patterns and the types of local let bindings without a type annotation.
+
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel};
+use polarity_lang_ast::*;
+use polarity_lang_elaborator::TypeInfoTable;
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use polarity_lang_printer::Print;
+use url::Url;
+
+use crate::database::Database;
+use crate::result::AppResult;
+
+impl Database {
+    /// Compute the inlay hints of a module whose position lies within the given span
+    pub async fn inlay_hints(&mut self, uri: &Url, span: Span) -> AppResult<Vec<InlayHint>> {
+        let module = self.ast(uri).await?;
+        let type_info_table = self.type_info_table(uri).await?;
+
+        let mut collector = HintCollector { type_info_table, hints: vec![] };
+        module.collect_hints(&mut collector);
+
+        let mut hints = collector.hints;
+        hints.retain(|hint| span.start <= hint.position && hint.position <= span.end);
+        hints.sort_by_key(|hint| hint.position);
+
+        Ok(hints
+            .into_iter()
+            .filter_map(|Hint { position, label, kind }| {
+                let position = self.index_to_location(uri, position)?;
+                Some(InlayHint {
+                    position,
+                    label: InlayHintLabel::String(label),
+                    kind: Some(kind),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: None,
+                    data: None,
+                })
+            })
+            .collect())
+    }
+}
+
+struct Hint {
+    position: ByteIndex,
+    label: String,
+    kind: InlayHintKind,
+}
+
+struct HintCollector {
+    /// Used to look up the names of the omitted implicit parameters
+    type_info_table: TypeInfoTable,
+    hints: Vec<Hint>,
+}
+
+impl HintCollector {
+    fn add_type(&mut self, name: &VarBind, typ: Option<Box<Exp>>) {
+        if let (VarBind::Var { span: Some(span), .. }, Some(typ)) = (name, typ) {
+            self.hints.push(Hint {
+                position: span.end,
+                label: format!(": {}", typ.print_to_string(None)),
+                kind: InlayHintKind::TYPE,
+            });
+        }
+    }
+
+    fn telescope(&mut self, telescope: &Telescope) {
+        for Param { typ, .. } in telescope.params.iter() {
+            typ.collect_hints(self);
+        }
+    }
+
+    /// Add hints for the solved implicit arguments which have been inserted during lowering
+    ///
+    /// Consecutive inserted arguments are shown together in front of the next given argument,
+    /// after the previous given argument or, if no argument is given, after the name of the callee.
+    fn args(&mut self, span: Option<Span>, name: &IdBound, args: &Args, params: Option<Telescope>) {
+        for arg in args.args.iter() {
+            if let Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } = arg {
+                arg.collect_hints(self);
+            }
+        }
+
+        let (Some(params), Some(name_span)) = (params, name.span) else {
+            return;
+        };
+        if params.params.len() != args.args.len() {
+            return;
+        }
+
+        let mut idx = 0;
+        while idx < args.args.len() {
+            if !args.args[idx].is_inserted_implicit() {
+                idx += 1;
+                continue;
+            }
+            let start = idx;
+            while idx < args.args.len() && args.args[idx].is_inserted_implicit() {
+                idx += 1;
+            }
+
+            let solutions: Vec<String> = args.args[start..idx]
+                .iter()
+                .zip(params.params[start..idx].iter())
+                .filter_map(|(arg, param)| {
+                    let Arg::InsertedImplicitArg { hole, .. } = arg else { return None };
+                    let solution = hole.solution.as_ref()?;
+                    let VarBind::Var { id, .. } = &param.name else { return None };
+                    Some(format!("{id} := {}", solution.print_to_string(None)))
+                })
+                .collect();
+            if solutions.is_empty() {
+                continue;
+            }
+            let solutions = solutions.join(", ");
+
+            let next = args.args[idx..].iter().find_map(|arg| arg.span());
+            let prev = args.args[..start].iter().rev().find_map(|arg| arg.span());
+            let (position, label) = match (next, prev) {
+                (Some(next), _) => (next.start, format!("{solutions}, ")),
+                (None, Some(prev)) => (prev.end, format!(", {solutions}")),
+                (None, None) => match span {
+                    // The argument list is given, but empty
+                    Some(span) if span.end > name_span.end => {
+                        (ByteIndex(span.end.0 - 1), solutions)
+                    }
+                    _ => (name_span.end, format!("({solutions})")),
+                },
+            };
+            self.hints.push(Hint { position, label, kind: InlayHintKind::PARAMETER });
+        }
+    }
+
+    fn call_params(&self, kind: &CallKind, name: &IdBound) -> Option<Telescope> {
+        match kind {
+            CallKind::Constructor | CallKind::Codefinition => {
+                self.type_info_table.lookup_ctor_or_codef(name).ok().map(|meta| meta.params)
+            }
+            CallKind::LetBound => {
+                self.type_info_table.lookup_let(name).ok().map(|let_| let_.params.clone())
+            }
+            CallKind::Extern => {
+                self.type_info_table.lookup_extern(name).ok().map(|ext| ext.params.clone())
+            }
+        }
+    }
+}
+
+trait CollectHints {
+    fn collect_hints(&self, collector: &mut HintCollector);
+}
+
+impl<T: CollectHints> CollectHints for Vec<T> {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        for x in self {
+            x.collect_hints(collector)
+        }
+    }
+}
+
+impl<T: CollectHints> CollectHints for Box<T> {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        (**self).collect_hints(collector)
+    }
+}
+
+impl<T: CollectHints> CollectHints for Option<T> {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        if let Some(x) = self {
+            x.collect_hints(collector)
+        }
+    }
+}
+
+impl CollectHints for Module {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        self.decls.collect_hints(collector)
+    }
+}
+
+impl CollectHints for Decl {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        match self {
+            Decl::Data(Data { typ, ctors, .. }) => {
+                collector.telescope(typ);
+                for Ctor { params, typ, .. } in ctors.iter() {
+                    collector.telescope(params);
+                    typ.collect_hints(collector);
+                }
+            }
+            Decl::Codata(Codata { typ, dtors, .. }) => {
+                collector.telescope(typ);
+                for Dtor { params, self_param, ret_typ, .. } in dtors.iter() {
+                    collector.telescope(params);
+                    self_param.typ.collect_hints(collector);
+                    ret_typ.collect_hints(collector);
+                }
+            }
+            Decl::Def(Def { params, self_param, ret_typ, cases, .. }) => {
+                collector.telescope(params);
+                self_param.typ.collect_hints(collector);
+                ret_typ.collect_hints(collector);
+                cases.collect_hints(collector);
+            }
+            Decl::Codef(Codef { params, typ, cases, .. }) => {
+                collector.telescope(params);
+                typ.collect_hints(collector);
+                cases.collect_hints(collector);
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                collector.telescope(params);
+                typ.collect_hints(collector);
+                body.collect_hints(collector);
+            }
+            Decl::Extern(Extern { params, typ, .. }) => {
+                collector.telescope(params);
+                typ.collect_hints(collector);
+            }
+            Decl::Infix(_) | Decl::Note(_) => {}
+        }
+    }
+}
+
+impl CollectHints for Exp {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) | Exp::Literal(_) => {}
+            Exp::TypCtor(e) => e.collect_hints(collector),
+            Exp::Call(Call { span, kind, name, args, is_bin_op, .. }) => {
+                // For binary operator applications, the span of the name lies in the infix declaration.
+                let params =
+                    if is_bin_op.is_none() { collector.call_params(kind, name) } else { None };
+                collector.args(*span, name, args, params);
+            }
+            Exp::DotCall(DotCall { span, exp, name, args, .. }) => {
+                exp.collect_hints(collector);
+                let params =
+                    collector.type_info_table.lookup_dtor_or_def(name).ok().map(|meta| meta.params);
+                collector.args(*span, name, args, params);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.collect_hints(collector);
+                typ.collect_hints(collector);
+            }
+            Exp::CompiledMatch(CompiledMatch { compiled, .. }) => compiled.collect_hints(collector),
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.collect_hints(collector);
+                if let Some(Motive { ret_typ, .. }) = motive {
+                    ret_typ.collect_hints(collector);
+                }
+                cases.collect_hints(collector);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.collect_hints(collector),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                match typ {
+                    Some(typ) => typ.collect_hints(collector),
+                    None => collector.add_type(name, bound.typ()),
+                }
+                bound.collect_hints(collector);
+                body.collect_hints(collector);
+            }
+            Exp::DoBlock(DoBlock { statements, .. }) => statements.collect_hints(collector),
+        }
+    }
+}
+
+impl CollectHints for TypCtor {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        let TypCtor { span, name, args, is_bin_op } = self;
+        let params = if is_bin_op.is_none() {
+            collector.type_info_table.lookup_tyctor(name).ok().map(|meta| (*meta.params).clone())
+        } else {
+            None
+        };
+        collector.args(*span, name, args, params);
+    }
+}
+
+impl CollectHints for Case {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        let Case { pattern: Pattern { params, .. }, body, .. } = self;
+        for ParamInst { name, typ, .. } in params.params.iter() {
+            collector.add_type(name, typ.clone());
+        }
+        body.collect_hints(collector);
+    }
+}
+
+impl CollectHints for DoStatements {
+    fn collect_hints(&self, collector: &mut HintCollector) {
+        match self {
+            DoStatements::Bind { bound, body, .. } => {
+                bound.collect_hints(collector);
+                body.collect_hints(collector);
+            }
+            DoStatements::Let { name, typ, bound, body, .. } => {
+                match typ {
+                    Some(typ) => typ.collect_hints(collector),
+                    None => collector.add_type(name, bound.typ()),
+                }
+                bound.collect_hints(collector);
+                body.collect_hints(collector);
+            }
+            DoStatements::Return { exp, .. } => exp.collect_hints(collector),
+        }
+    }
+}
@@ -1,24 +1,39 @@
 mod asserts;
 mod cache;
 mod codespan;
+mod completion;
 mod database;
 mod dependency_graph;
 mod edit;
 mod fs;
+mod hierarchy;
+mod holes;
 mod info;
+mod inlay_hints;
 mod lift;
 pub mod paths;
+mod quick_fixes;
+mod ranges;
+mod rename;
 mod render_reports;
 mod result;
+mod selection;
+mod semantic_tokens;
+mod signature_help;
 mod spans;
+mod symbols;
 mod xfunc;
 
 pub use database::Database;
 
 pub use edit::*;
 pub use fs::*;
+pub use hierarchy::*;
+pub use holes::*;
 pub use info::*;
 pub use paths::*;
+pub use quick_fixes::*;
 pub use render_reports::*;
 pub use result::*;
+pub use semantic_tokens::*;
 pub use xfunc::*;
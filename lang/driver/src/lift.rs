@@ -18,7 +18,7 @@ impl Database {
                 None => false,
                 Some(id) => id.id == type_name,
             })
-            .and_then(|x| x.span())
+            .and_then(|x| x.span_with_comments())
             .ok_or(DriverError::Impossible(format!("Could not resolve {type_name}")))?;
 
         let LiftResult { module: prg, modified_decls, new_decls } =
@@ -76,7 +76,7 @@ fn generate_edits(
                 Some(id) => id == &name,
             })
             .unwrap();
-        let span = decl.span().unwrap();
+        let span = decl.span_with_comments().unwrap();
         let text = decl.print_to_string(None);
         edits.push(Edit { span, text });
     }
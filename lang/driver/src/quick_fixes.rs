@@ -0,0 +1,391 @@
+//! Quick-fixes for errors reported during lowering and type checking
+//!
+//! Every quick-fix records the code and the span of the error it resolves,
+//! which allows the LSP server to associate it with the corresponding diagnostic.
+
+use miette::Diagnostic;
+use polarity_lang_ast::*;
+use polarity_lang_elaborator::TypeInfoTable;
+use polarity_lang_elaborator::result::TypeError;
+use polarity_lang_lowering::LoweringError;
+use polarity_lang_miette_util::FromMiette;
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use url::Url;
+
+use crate::database::Database;
+use crate::holes::pattern_params;
+use crate::result::AppError;
+
+use super::Edit;
+
+/// A code action which resolves an error
+pub struct QuickFix {
+    pub title: String,
+    /// The code of the resolved error, e.g. `T-005`
+    pub code: String,
+    /// The span of the label of the resolved error
+    pub span: Span,
+    pub edits: Vec<Edit>,
+}
+
+impl Database {
+    /// The quick-fixes for the errors of a module whose label overlaps the given span
+    pub async fn quick_fixes(&mut self, uri: &Url, span: Span) -> Vec<QuickFix> {
+        let Err(errors) = self.ast(uri).await else {
+            return vec![];
+        };
+
+        let mut fixes = vec![];
+        for error in errors.into_errors() {
+            let Some(code) = error.code().map(|code| code.to_string()) else {
+                continue;
+            };
+            let (title_edits, error_span) = match error {
+                AppError::Lowering(err) => match *err {
+                    LoweringError::UndefinedIdent { name, span: error_span } => {
+                        let error_span = error_span.from_miette();
+                        if !overlaps(error_span, span) {
+                            continue;
+                        }
+                        (self.import_fixes(uri, &name.id).await, error_span)
+                    }
+                    _ => continue,
+                },
+                AppError::Type(err) => {
+                    let Some(error_span) = label_span(&*err) else {
+                        continue;
+                    };
+                    if !overlaps(error_span, span) {
+                        continue;
+                    }
+                    (self.type_error_fixes(uri, *err).await, error_span)
+                }
+                _ => continue,
+            };
+            fixes.extend(title_edits.into_iter().map(|(title, edits)| QuickFix {
+                title,
+                code: code.clone(),
+                span: error_span,
+                edits,
+            }));
+        }
+        fixes
+    }
+
+    /// Import one of the other known modules which declares the undefined identifier
+    async fn import_fixes(&mut self, uri: &Url, name: &str) -> Vec<(String, Vec<Edit>)> {
+        let Ok(cst) = self.cst(uri).await else {
+            return vec![];
+        };
+        let mut modules: Vec<Url> =
+            self.files.keys().filter(|module| *module != uri).cloned().collect();
+        modules.sort();
+
+        let mut fixes = vec![];
+        for module in modules {
+            let Ok(symbol_table) = self.symbol_table(&module).await else {
+                continue;
+            };
+            if !symbol_table.idents.keys().any(|ident| ident.id == name) {
+                continue;
+            }
+            let Some(path) = uri.make_relative(&module) else {
+                continue;
+            };
+            let edit = match cst.use_decls.last() {
+                Some(last) => Edit {
+                    span: Span { start: last.span.end, end: last.span.end },
+                    text: format!("\nuse \"{path}\""),
+                },
+                None => Edit {
+                    span: Span { start: ByteIndex(0), end: ByteIndex(0) },
+                    text: format!("use \"{path}\"\n\n"),
+                },
+            };
+            fixes.push((format!("Import {name} from \"{path}\""), vec![edit]));
+        }
+        fixes
+    }
+
+    async fn type_error_fixes(&mut self, uri: &Url, error: TypeError) -> Vec<(String, Vec<Edit>)> {
+        let (Ok(module), Ok(source), Ok(info_table)) =
+            (self.ust(uri).await, self.source(uri).await, self.type_info_table(uri).await)
+        else {
+            return vec![];
+        };
+        let mut matches = vec![];
+        module.collect_matches(&mut matches);
+        let case_at = |span: Span| {
+            matches.iter().flat_map(|m| m.cases.iter()).find(|case| case.span == Some(span))
+        };
+
+        let fix = match error {
+            TypeError::InvalidMatch { missing, match_span: Some(match_span), .. }
+                if !missing.is_empty() =>
+            {
+                let match_span = match_span.from_miette();
+                let Some(m) = matches.iter().find(|m| m.span == match_span) else {
+                    return vec![];
+                };
+                let Some(cases) = missing
+                    .iter()
+                    .map(|name| missing_case(&info_table, name))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return vec![];
+                };
+                let title = if cases.len() == 1 {
+                    "Add the missing case".to_owned()
+                } else {
+                    format!("Add the {} missing cases", cases.len())
+                };
+                add_cases(&source, m, cases).map(|edit| (title, edit))
+            }
+            TypeError::PatternIsAbsurd { name, span: Some(span) } => {
+                let Some(case) = case_at(span.from_miette()) else {
+                    return vec![];
+                };
+                replace_body(&source, case, " absurd")
+                    .map(|edit| (format!("Mark the case for {} as absurd", name.id), edit))
+            }
+            TypeError::PatternIsNotAbsurd { name, span: Some(span) } => {
+                let Some(case) = case_at(span.from_miette()) else {
+                    return vec![];
+                };
+                replace_body(&source, case, " => ?")
+                    .map(|edit| (format!("Replace absurd by a hole for {}", name.id), edit))
+            }
+            TypeError::ArgLenMismatch { expected, actual, span: Some(span), .. } => {
+                let Some(case) = case_at(span.from_miette()) else {
+                    return vec![];
+                };
+                let title = if expected > actual {
+                    "Insert the missing pattern arguments"
+                } else {
+                    "Remove the superfluous pattern arguments"
+                };
+                pattern_args(&source, &info_table, case, expected)
+                    .map(|edit| (title.to_owned(), edit))
+            }
+            _ => None,
+        };
+        fix.map(|(title, edit)| (title, vec![edit])).into_iter().collect()
+    }
+}
+
+/// The span of the first label of an error
+fn label_span(error: &dyn Diagnostic) -> Option<Span> {
+    error.labels()?.next().map(|label| label.inner().from_miette())
+}
+
+fn overlaps(lhs: Span, rhs: Span) -> bool {
+    lhs.start <= rhs.end && rhs.start <= lhs.end
+}
+
+/// Print a case for a missing constructor or destructor whose body is a hole
+fn missing_case(info_table: &TypeInfoTable, name: &IdBound) -> Option<String> {
+    if let Ok(ctor) = info_table.lookup_ctor(name) {
+        return Some(format!("{}{} => ?", name.id, pattern_params(&ctor.params, &[])));
+    }
+    let dtor = info_table.lookup_dtor(name).ok()?;
+    Some(format!(".{}{} => ?", name.id, pattern_params(&dtor.params, &[])))
+}
+
+/// Insert cases after the last case of a (co)pattern match
+///
+/// The new cases are separated from each other like the last case is separated from the text before it,
+/// i.e. they are placed on separate lines if the last case starts on a line of its own.
+fn add_cases(source: &str, m: &Match<'_>, cases: Vec<String>) -> Option<Edit> {
+    let Some(last) = m.cases.last() else {
+        // The match has no cases yet, so insert them between the braces.
+        let text = &source[m.span.start.0 as usize..m.span.end.0 as usize];
+        let close = text.rfind('}')?;
+        let open = text[..close].rfind('{')?;
+        let span = Span {
+            start: ByteIndex(m.span.start.0 + open as u32 + 1),
+            end: ByteIndex(m.span.start.0 + close as u32),
+        };
+        return Some(Edit { span, text: format!(" {} ", cases.join(", ")) });
+    };
+    let last = last.span?;
+    let before = &source[..last.start.0 as usize];
+    let indent = &before[before.trim_end_matches([' ', '\t']).len()..];
+    let separator = if before.trim_end_matches([' ', '\t']).ends_with('\n') {
+        format!(",\n{indent}")
+    } else {
+        ", ".to_owned()
+    };
+    let text = cases.iter().map(|case| format!("{separator}{case}")).collect();
+    Some(Edit { span: Span { start: last.end, end: last.end }, text })
+}
+
+/// Replace everything after the pattern of a case, i.e. its body or the `absurd` marker
+fn replace_body(source: &str, case: &Case, text: &str) -> Option<Edit> {
+    let span = Span { start: pattern_end(source, &case.pattern)?, end: case.span?.end };
+    Some(Edit { span, text: text.to_owned() })
+}
+
+/// Rewrite the parameters of a pattern such that there are `expected` many of them
+///
+/// The given parameters are kept, missing parameters are named after the declaration.
+fn pattern_args(
+    source: &str,
+    info_table: &TypeInfoTable,
+    case: &Case,
+    expected: usize,
+) -> Option<Edit> {
+    let Pattern { is_copattern, name, params, .. } = &case.pattern;
+    let declared = if *is_copattern {
+        &info_table.lookup_dtor(name).ok()?.params
+    } else {
+        &info_table.lookup_ctor(name).ok()?.params
+    };
+
+    let mut args: Vec<String> = params
+        .params
+        .iter()
+        .take(expected)
+        .map(|param| {
+            param.span.map(|span| source[span.start.0 as usize..span.end.0 as usize].to_owned())
+        })
+        .collect::<Option<_>>()?;
+    for param in declared.params.iter().skip(args.len()).take(expected - args.len()) {
+        let mut arg = param.name.to_string();
+        if let VarBind::Var { .. } = param.name {
+            while args.contains(&arg) {
+                arg.push('\'');
+            }
+        }
+        args.push(arg);
+    }
+
+    let span = Span { start: name.span?.end, end: pattern_end(source, &case.pattern)? };
+    let text = if args.is_empty() { String::new() } else { format!("({})", args.join(", ")) };
+    Some(Edit { span, text })
+}
+
+/// The end of the text of a pattern
+///
+/// The span of a pattern may include the whitespace which follows it.
+fn pattern_end(source: &str, pattern: &Pattern) -> Option<ByteIndex> {
+    let span = pattern.span?;
+    let text = &source[span.start.0 as usize..span.end.0 as usize];
+    Some(ByteIndex(span.start.0 + text.trim_end().len() as u32))
+}
+
+/// A (co)pattern match: a definition, codefinition, local match or local comatch
+struct Match<'a> {
+    span: Span,
+    cases: &'a [Case],
+}
+
+trait CollectMatches {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>);
+}
+
+impl<T: CollectMatches> CollectMatches for Vec<T> {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        for x in self {
+            x.collect_matches(matches)
+        }
+    }
+}
+
+impl<T: CollectMatches> CollectMatches for Box<T> {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        (**self).collect_matches(matches)
+    }
+}
+
+impl<T: CollectMatches> CollectMatches for Option<T> {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        if let Some(x) = self {
+            x.collect_matches(matches)
+        }
+    }
+}
+
+fn add_match<'a>(matches: &mut Vec<Match<'a>>, span: Option<Span>, cases: &'a [Case]) {
+    if let Some(span) = span {
+        matches.push(Match { span, cases });
+    }
+    for Case { body, .. } in cases.iter() {
+        body.collect_matches(matches);
+    }
+}
+
+impl CollectMatches for Module {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        self.decls.collect_matches(matches)
+    }
+}
+
+impl CollectMatches for Decl {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        match self {
+            Decl::Def(Def { span, cases, .. }) | Decl::Codef(Codef { span, cases, .. }) => {
+                add_match(matches, *span, cases)
+            }
+            Decl::Let(Let { body, .. }) => body.collect_matches(matches),
+            Decl::Data(_) | Decl::Codata(_) | Decl::Extern(_) | Decl::Infix(_) | Decl::Note(_) => {}
+        }
+    }
+}
+
+impl CollectMatches for Exp {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) | Exp::Literal(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+                args.collect_matches(matches)
+            }
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                exp.collect_matches(matches);
+                args.collect_matches(matches);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.collect_matches(matches);
+                typ.collect_matches(matches);
+            }
+            Exp::CompiledMatch(CompiledMatch { compiled, .. }) => compiled.collect_matches(matches),
+            Exp::LocalMatch(LocalMatch { span, on_exp, cases, .. }) => {
+                on_exp.collect_matches(matches);
+                add_match(matches, *span, cases);
+            }
+            Exp::LocalComatch(LocalComatch { span, cases, .. }) => add_match(matches, *span, cases),
+            Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+                typ.collect_matches(matches);
+                bound.collect_matches(matches);
+                body.collect_matches(matches);
+            }
+            Exp::DoBlock(DoBlock { statements, .. }) => statements.collect_matches(matches),
+        }
+    }
+}
+
+impl CollectMatches for Args {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        for arg in self.args.iter() {
+            if let Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } = arg {
+                arg.collect_matches(matches);
+            }
+        }
+    }
+}
+
+impl CollectMatches for DoStatements {
+    fn collect_matches<'a>(&'a self, matches: &mut Vec<Match<'a>>) {
+        match self {
+            DoStatements::Bind { bound, body, .. } => {
+                bound.collect_matches(matches);
+                body.collect_matches(matches);
+            }
+            DoStatements::Let { typ, bound, body, .. } => {
+                typ.collect_matches(matches);
+                bound.collect_matches(matches);
+                body.collect_matches(matches);
+            }
+            DoStatements::Return { exp, .. } => exp.collect_matches(matches),
+        }
+    }
+}
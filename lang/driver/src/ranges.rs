@@ -0,0 +1,438 @@
+//! Folding ranges and selection ranges for the LSP server
+//!
+//! Both are computed from the CST, so they are available as soon as a module can be parsed,
+//! even if it cannot be lowered or typechecked.
+
+use lsp_types::{FoldingRange, FoldingRangeKind, SelectionRange};
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use polarity_lang_parser::cst::decls::*;
+use polarity_lang_parser::cst::exp::*;
+use polarity_lang_parser::cst::ident::Ident;
+use polarity_lang_parser::lexer::{Lexer, Token};
+use url::Url;
+
+use crate::database::Database;
+use crate::result::AppResult;
+
+impl Database {
+    /// The regions of a module which can be folded
+    ///
+    /// These are the bodies of (co)data types and (co)definitions, matches, comatches,
+    /// do blocks, block comments and consecutive lines of doc comments.
+    pub async fn folding_ranges(&mut self, uri: &Url) -> AppResult<Vec<FoldingRange>> {
+        let module = self.cst(uri).await?;
+        let source = self.source(uri).await?;
+
+        let mut nodes = vec![];
+        module.collect_nodes(&mut nodes);
+
+        let mut ranges = vec![];
+        for node in nodes.into_iter().filter(|node| node.foldable) {
+            ranges.extend(self.folding_range(uri, node.span, None));
+        }
+        for span in comment_runs(&source) {
+            ranges.extend(self.folding_range(uri, span, Some(FoldingRangeKind::Comment)));
+        }
+        ranges.sort_by_key(|range| (range.start_line, range.end_line));
+        Ok(ranges)
+    }
+
+    /// For each of the given positions, the chain of syntax nodes which contain it
+    ///
+    /// The innermost node comes first, each further node is the parent of the previous one.
+    pub async fn selection_ranges(
+        &mut self,
+        uri: &Url,
+        positions: &[ByteIndex],
+    ) -> AppResult<Vec<SelectionRange>> {
+        let module = self.cst(uri).await?;
+        let source = self.source(uri).await?;
+
+        let mut nodes = vec![];
+        module.collect_nodes(&mut nodes);
+        // The whole module is the outermost node.
+        add(&mut nodes, Span::from_string(&source));
+
+        let mut selection_ranges = vec![];
+        for idx in positions {
+            let mut spans: Vec<Span> = nodes
+                .iter()
+                .filter(|node| node.selectable)
+                .map(|node| node.span)
+                .filter(|span| span.start <= *idx && *idx <= span.end)
+                .collect();
+            // Nodes which contain the same position are nested, so they are ordered by their length.
+            spans.sort_by_key(|span| span.end.0 - span.start.0);
+            spans.dedup();
+
+            let mut selection_range: Option<SelectionRange> = None;
+            for span in spans.into_iter().rev() {
+                let Some(range) = self.span_to_locations(uri, span) else {
+                    continue;
+                };
+                selection_range =
+                    Some(SelectionRange { range, parent: selection_range.map(Box::new) });
+            }
+
+            let selection_range = match selection_range {
+                Some(selection_range) => selection_range,
+                None => {
+                    let position = self.index_to_location(uri, *idx).unwrap_or_default();
+                    SelectionRange {
+                        range: lsp_types::Range { start: position, end: position },
+                        parent: None,
+                    }
+                }
+            };
+            selection_ranges.push(selection_range);
+        }
+        Ok(selection_ranges)
+    }
+
+    fn folding_range(
+        &self,
+        uri: &Url,
+        span: Span,
+        kind: Option<FoldingRangeKind>,
+    ) -> Option<FoldingRange> {
+        let range = self.span_to_locations(uri, span)?;
+        if range.start.line >= range.end.line {
+            return None;
+        }
+        Some(FoldingRange {
+            start_line: range.start.line,
+            start_character: None,
+            end_line: range.end.line,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        })
+    }
+}
+
+/// The spans of consecutive lines which consist of doc comments or block comments
+fn comment_runs(source: &str) -> Vec<Span> {
+    let mut runs: Vec<Span> = vec![];
+    let mut prev_end = None;
+    for (start, _, end) in
+        Lexer::with_trivia(source).filter_map(Result::ok).filter(|(_, token, _)| match token {
+            Token::DocComment(_) => true,
+            Token::Comment(text) => text.starts_with("/*"),
+            _ => false,
+        })
+    {
+        // A doc comment token includes the line break which terminates the comment.
+        let end = start + source[start..end].trim_end().len();
+        // Comments belong to the same run if only whitespace separates them.
+        let continues_run =
+            prev_end.is_some_and(|prev_end| source[prev_end..start].trim().is_empty());
+        match runs.last_mut() {
+            Some(run) if continues_run => run.end = ByteIndex(end as u32),
+            _ => runs.push(Span { start: ByteIndex(start as u32), end: ByteIndex(end as u32) }),
+        }
+        prev_end = Some(end);
+    }
+    runs
+}
+
+/// A syntax node of the CST
+struct Node {
+    span: Span,
+    /// Whether the node is shown as a folding range
+    foldable: bool,
+    /// Whether the node is used for selection ranges
+    selectable: bool,
+}
+
+trait CollectNodes {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>);
+}
+
+impl<T: CollectNodes> CollectNodes for Vec<T> {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        for x in self {
+            x.collect_nodes(nodes)
+        }
+    }
+}
+
+impl<T: CollectNodes> CollectNodes for Box<T> {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        (**self).collect_nodes(nodes)
+    }
+}
+
+impl<T: CollectNodes> CollectNodes for Option<T> {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        if let Some(x) = self {
+            x.collect_nodes(nodes)
+        }
+    }
+}
+
+fn add(nodes: &mut Vec<Node>, span: Span) {
+    nodes.push(Node { span, foldable: false, selectable: true });
+}
+
+fn add_foldable(nodes: &mut Vec<Node>, span: Span) {
+    nodes.push(Node { span, foldable: true, selectable: true });
+}
+
+/// Add a declaration whose folding range starts at its name, so that its doc comment stays visible
+fn add_decl(nodes: &mut Vec<Node>, span: Span, name: &Ident) {
+    add(nodes, span);
+    nodes.push(Node {
+        span: Span { start: name.span.start, end: span.end },
+        foldable: true,
+        selectable: false,
+    });
+    add(nodes, name.span);
+}
+
+impl CollectNodes for Module {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        for UseDecl { span, .. } in self.use_decls.iter() {
+            add(nodes, *span);
+        }
+        self.decls.collect_nodes(nodes)
+    }
+}
+
+impl CollectNodes for Decl {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        match self {
+            Decl::Data(Data { span, name, params, ctors, .. }) => {
+                add_decl(nodes, *span, name);
+                params.collect_nodes(nodes);
+                for Ctor { span, name, params, typ, .. } in ctors.iter() {
+                    add(nodes, *span);
+                    add(nodes, name.span);
+                    params.collect_nodes(nodes);
+                    typ.collect_nodes(nodes);
+                }
+            }
+            Decl::Codata(Codata { span, name, params, dtors, .. }) => {
+                add_decl(nodes, *span, name);
+                params.collect_nodes(nodes);
+                for Dtor { span, name, params, destructee, ret_typ, .. } in dtors.iter() {
+                    add(nodes, *span);
+                    add(nodes, name.span);
+                    params.collect_nodes(nodes);
+                    add(nodes, destructee.span);
+                    destructee.typ.collect_nodes(nodes);
+                    ret_typ.collect_nodes(nodes);
+                }
+            }
+            Decl::Def(Def { span, name, params, scrutinee, ret_typ, cases, .. }) => {
+                add_decl(nodes, *span, name);
+                params.collect_nodes(nodes);
+                add(nodes, scrutinee.span);
+                scrutinee.typ.collect_nodes(nodes);
+                ret_typ.collect_nodes(nodes);
+                cases.collect_nodes(nodes);
+            }
+            Decl::Codef(Codef { span, name, params, typ, cases, .. }) => {
+                add_decl(nodes, *span, name);
+                params.collect_nodes(nodes);
+                typ.collect_nodes(nodes);
+                cases.collect_nodes(nodes);
+            }
+            Decl::Let(Let { span, name, params, typ, body, .. }) => {
+                add(nodes, *span);
+                add(nodes, name.span);
+                params.collect_nodes(nodes);
+                typ.collect_nodes(nodes);
+                body.collect_nodes(nodes);
+            }
+            Decl::Extern(Extern { span, name, params, typ, .. }) => {
+                add(nodes, *span);
+                add(nodes, name.span);
+                params.collect_nodes(nodes);
+                typ.collect_nodes(nodes);
+            }
+            Decl::Infix(Infix { span, pattern, rhs, .. }) => {
+                add(nodes, *span);
+                pattern.collect_nodes(nodes);
+                rhs.collect_nodes(nodes);
+            }
+            Decl::Note(Note { span, name, .. }) => {
+                add(nodes, *span);
+                add(nodes, name.span);
+            }
+            Decl::Error => {}
+        }
+    }
+}
+
+impl CollectNodes for Telescope {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        for Param { name, names, typ, .. } in self.0.iter() {
+            name.collect_nodes(nodes);
+            names.collect_nodes(nodes);
+            typ.collect_nodes(nodes);
+        }
+    }
+}
+
+impl CollectNodes for BindingSite {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        add(nodes, self.span())
+    }
+}
+
+impl<P: CollectNodes> CollectNodes for Case<P> {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        let Case { span, comments: _, pattern, body } = self;
+        add(nodes, *span);
+        pattern.collect_nodes(nodes);
+        body.collect_nodes(nodes);
+    }
+}
+
+impl CollectNodes for Pattern {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        add(nodes, self.span());
+        match self {
+            Pattern::Ctor { name, params, .. } => {
+                add(nodes, name.span);
+                params.collect_nodes(nodes);
+            }
+            Pattern::Literal(_) | Pattern::Wildcard { .. } => {}
+        }
+    }
+}
+
+impl CollectNodes for MultiPattern {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        let MultiPattern { span, params } = self;
+        add(nodes, *span);
+        params.collect_nodes(nodes);
+    }
+}
+
+impl CollectNodes for Copattern {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        let Copattern { span, name, params } = self;
+        add(nodes, *span);
+        add(nodes, name.span);
+        params.collect_nodes(nodes);
+    }
+}
+
+impl CollectNodes for Arg {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        match self {
+            Arg::UnnamedArg(exp, _) => exp.collect_nodes(nodes),
+            Arg::NamedArg(name, exp, _) => {
+                add(nodes, Span { start: name.span.start, end: exp.span().end });
+                add(nodes, name.span);
+                exp.collect_nodes(nodes);
+            }
+        }
+    }
+}
+
+impl CollectNodes for Call {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        let Call { span, name, args } = self;
+        add(nodes, *span);
+        add(nodes, name.span);
+        args.collect_nodes(nodes);
+    }
+}
+
+impl CollectNodes for BinOp {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        let BinOp { span, lhs, rhs } = self;
+        add(nodes, *span);
+        lhs.collect_nodes(nodes);
+        for (operator, exp) in rhs.iter() {
+            add(nodes, operator.span);
+            exp.collect_nodes(nodes);
+        }
+    }
+}
+
+impl CollectNodes for Exp {
+    fn collect_nodes(&self, nodes: &mut Vec<Node>) {
+        match self {
+            Exp::Call(call) => call.collect_nodes(nodes),
+            Exp::DotCall(DotCall { span, exp, name, args }) => {
+                add(nodes, *span);
+                exp.collect_nodes(nodes);
+                add(nodes, name.span);
+                args.collect_nodes(nodes);
+            }
+            Exp::Anno(Anno { span, exp, typ }) => {
+                add(nodes, *span);
+                exp.collect_nodes(nodes);
+                typ.collect_nodes(nodes);
+            }
+            Exp::LocalMatch(LocalMatch { span, name, on_exp, motive, cases }) => {
+                add_foldable(nodes, *span);
+                if let Some(name) = name {
+                    add(nodes, name.span);
+                }
+                on_exp.collect_nodes(nodes);
+                if let Some(Motive { span, param, ret_typ }) = motive {
+                    add(nodes, *span);
+                    param.collect_nodes(nodes);
+                    ret_typ.collect_nodes(nodes);
+                }
+                cases.collect_nodes(nodes);
+            }
+            Exp::MultiMatch(MultiMatch { span, on_exps, cases }) => {
+                add_foldable(nodes, *span);
+                on_exps.collect_nodes(nodes);
+                cases.collect_nodes(nodes);
+            }
+            Exp::LocalComatch(LocalComatch { span, name, cases, .. }) => {
+                add_foldable(nodes, *span);
+                if let Some(name) = name {
+                    add(nodes, name.span);
+                }
+                cases.collect_nodes(nodes);
+            }
+            Exp::Hole(Hole { span, .. }) | Exp::Literal(Literal { span, .. }) => add(nodes, *span),
+            Exp::BinOp(binop) => binop.collect_nodes(nodes),
+            Exp::Lam(Lam { span, case }) => {
+                add(nodes, *span);
+                case.collect_nodes(nodes);
+            }
+            Exp::LocalLet(LocalLet { span, comments: _, name, typ, bound, body }) => {
+                add(nodes, *span);
+                name.collect_nodes(nodes);
+                typ.collect_nodes(nodes);
+                bound.collect_nodes(nodes);
+                body.collect_nodes(nodes);
+            }
+            Exp::DoBlock(DoBlock { span, statements }) => {
+                add_foldable(nodes, *span);
+                for statement in statements.iter() {
+                    match statement {
+                        DoStatement::Exp { span, comments: _, exp } => {
+                            add(nodes, *span);
+                            exp.collect_nodes(nodes);
+                        }
+                        DoStatement::Bind { span, comments: _, name, bound } => {
+                            add(nodes, *span);
+                            name.collect_nodes(nodes);
+                            bound.collect_nodes(nodes);
+                        }
+                        DoStatement::Let { span, comments: _, name, typ, bound } => {
+                            add(nodes, *span);
+                            name.collect_nodes(nodes);
+                            typ.collect_nodes(nodes);
+                            bound.collect_nodes(nodes);
+                        }
+                    }
+                }
+            }
+            Exp::Parens(Parens { span, exp }) => {
+                add(nodes, *span);
+                exp.collect_nodes(nodes);
+            }
+            Exp::Error(span) => add(nodes, *span),
+        }
+    }
+}
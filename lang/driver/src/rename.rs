@@ -0,0 +1,624 @@
+//! Renaming of top-level names, infix operators and local binders
+//!
+//! Top-level names and operators are renamed in the declaring module and in all modules which import it.
+//! Local binders are resolved by their name in the same way as during lowering, i.e. a variable refers
+//! to the innermost binder with the same name which is in scope.
+
+use polarity_lang_ast::*;
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+use polarity_lang_parser::cst::ident::{Ident, Operator};
+use polarity_lang_parser::lexer::{Lexer, Token};
+use url::Url;
+
+use crate::database::Database;
+use crate::edit::Edit;
+use crate::result::{AppResult, DriverError};
+
+/// A symbol which can be renamed
+enum Symbol {
+    /// The name of a top-level declaration
+    Global(IdBound),
+    /// An infix operator together with the module in which it is declared
+    Operator { id: String, uri: Url },
+    /// A local binder, given by its position in `Locals::binders`
+    Local(usize),
+}
+
+impl Database {
+    /// Check whether the symbol at the given index can be renamed
+    ///
+    /// Returns the span of the symbol and its current name.
+    pub async fn prepare_rename(&mut self, uri: &Url, idx: ByteIndex) -> Option<(Span, String)> {
+        let (_, span, name) = self.symbol_at_index(uri, idx).await?;
+        Some((span, name))
+    }
+
+    /// Compute the edits which rename the symbol at the given index to `new_name`
+    ///
+    /// Returns `None` if there is no symbol at the given index which can be renamed.
+    pub async fn rename(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+        new_name: &str,
+    ) -> AppResult<Option<HashMap<Url, Vec<Edit>>>> {
+        let Some((symbol, _, name)) = self.symbol_at_index(uri, idx).await else {
+            return Ok(None);
+        };
+        if name == new_name {
+            return Ok(Some(HashMap::default()));
+        }
+
+        let edits = match symbol {
+            Symbol::Global(name) => self.rename_global(&name, new_name).await?,
+            Symbol::Operator { id, uri: decl_uri } => {
+                self.rename_operator(&id, &decl_uri, new_name).await?
+            }
+            Symbol::Local(binder) => self.rename_local(uri, binder, new_name).await?,
+        };
+
+        let mut result: HashMap<Url, Vec<Edit>> = HashMap::default();
+        for (uri, span) in edits {
+            result.entry(uri).or_default().push(Edit { span, text: new_name.to_owned() });
+        }
+        for edits in result.values_mut() {
+            edits.sort();
+            edits.dedup();
+        }
+        Ok(Some(result))
+    }
+
+    async fn symbol_at_index(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> Option<(Symbol, Span, String)> {
+        // As for completion, a cursor directly behind a symbol still refers to it.
+        let contains = |span: &Span| span.start <= idx && idx <= span.end;
+
+        if let Ok(locals) = self.locals(uri).await {
+            for (i, binder) in locals.binders.iter().enumerate() {
+                if let Some(span) = std::iter::once(&binder.span)
+                    .chain(binder.occurrences.iter())
+                    .find(|s| contains(s))
+                {
+                    return Some((Symbol::Local(i), *span, binder.id.clone()));
+                }
+            }
+        }
+
+        if let Ok(lapper) = self.references_by_id(uri).await {
+            // Symbols containing the index take precedence over symbols ending at it.
+            let smallest_interval = lapper
+                .find(idx.0.saturating_sub(1), idx.0 + 1)
+                .filter(|interval| interval.start <= idx.0 && idx.0 <= interval.stop)
+                .min_by_key(|interval| (interval.stop == idx.0, interval.stop - interval.start));
+            if let Some(interval) = smallest_interval {
+                let span = Span { start: ByteIndex(interval.start), end: ByteIndex(interval.stop) };
+                let name = interval.val.name.clone();
+                let id = name.id.clone();
+                return Some((Symbol::Global(name), span, id));
+            }
+        }
+
+        let source = self.source(uri).await.ok()?;
+        let (span, id) = operators(&source).into_iter().find(|(span, _)| contains(span))?;
+        let symbol_table = self.symbol_table_in_scope(uri).await.ok()?;
+        let operator = Operator { span, id: id.clone() };
+        let (_, decl_uri) = symbol_table.lookup_operator(&operator).ok()?;
+        Some((Symbol::Operator { id: id.clone(), uri: decl_uri.clone() }, span, id))
+    }
+
+    async fn rename_global(
+        &mut self,
+        name: &IdBound,
+        new_name: &str,
+    ) -> AppResult<Vec<(Url, Span)>> {
+        check_identifier(new_name)?;
+
+        let modules = self.importing_modules(&name.uri).await?;
+        for module in modules.iter() {
+            // Make sure that all occurrences can be found
+            self.references_by_id(module).await?;
+
+            let symbol_table = self.symbol_table_in_scope(module).await?;
+            let ident = Ident { span: Span::default(), id: new_name.to_owned() };
+            if symbol_table.lookup_exists(&ident) {
+                return Err(DriverError::NameAlreadyDefined { name: new_name.to_owned() }.into());
+            }
+        }
+
+        let references = self.references(name, true).await;
+
+        // Local variables take precedence over top-level names during lowering.
+        // We therefore have to check that no reference is captured by a local binder.
+        for (module, span) in references.iter() {
+            let locals = self.locals(module).await?;
+            let captured = locals
+                .scopes
+                .iter()
+                .any(|(scope_span, visible)| scope_span == span && visible.contains(new_name));
+            if captured {
+                return Err(DriverError::NameShadowsLocal { name: new_name.to_owned() }.into());
+            }
+        }
+
+        Ok(references)
+    }
+
+    async fn rename_operator(
+        &mut self,
+        id: &str,
+        decl_uri: &Url,
+        new_name: &str,
+    ) -> AppResult<Vec<(Url, Span)>> {
+        if !matches!(lex_single(new_name), Some(Token::Operator(_))) {
+            return Err(DriverError::InvalidOperator { name: new_name.to_owned() }.into());
+        }
+
+        let mut edits = vec![];
+        for module in self.importing_modules(decl_uri).await? {
+            let symbol_table = self.symbol_table_in_scope(&module).await?;
+            let operator = Operator { span: Span::default(), id: new_name.to_owned() };
+            if symbol_table.lookup_operator_exists(&operator) {
+                return Err(DriverError::NameAlreadyDefined { name: new_name.to_owned() }.into());
+            }
+
+            let source = self.source(&module).await?;
+            edits.extend(
+                operators(&source)
+                    .into_iter()
+                    .filter(|(_, op)| op == id)
+                    .map(|(span, _)| (module.clone(), span)),
+            );
+        }
+        Ok(edits)
+    }
+
+    async fn rename_local(
+        &mut self,
+        uri: &Url,
+        binder: usize,
+        new_name: &str,
+    ) -> AppResult<Vec<(Url, Span)>> {
+        check_identifier(new_name)?;
+
+        let locals = self.locals(uri).await?;
+        let binder = &locals.binders[binder];
+
+        let symbol_table = self.symbol_table_in_scope(uri).await?;
+        let ident = Ident { span: Span::default(), id: new_name.to_owned() };
+        if symbol_table.lookup_exists(&ident) {
+            return Err(DriverError::NameAlreadyDefined { name: new_name.to_owned() }.into());
+        }
+        if binder.visible.contains(new_name) {
+            return Err(DriverError::NameShadowsLocal { name: new_name.to_owned() }.into());
+        }
+
+        let mut edits: Vec<(Url, Span)> = std::iter::once(binder.span)
+            .chain(binder.occurrences.iter().cloned())
+            .map(|span| (uri.clone(), span))
+            .collect();
+
+        // Parameters of top-level declarations can also be referred to by named arguments.
+        if let Some(owner) = &binder.owner {
+            for module in self.importing_modules(&owner.uri).await? {
+                let module_locals = self.locals(&module).await?;
+                edits.extend(
+                    module_locals
+                        .named_args
+                        .iter()
+                        .filter(|(callee, id, _)| callee == owner && *id == binder.id)
+                        .map(|(_, _, span)| (module.clone(), *span)),
+                );
+            }
+        }
+
+        Ok(edits)
+    }
+
+    /// The modules which can refer to the top-level names declared in the given module
+    ///
+    /// These are the module itself and all modules which import it directly.
+    async fn importing_modules(&mut self, uri: &Url) -> AppResult<Vec<Url>> {
+        let mut modules = vec![];
+        for module in self.dependent_modules(uri).await {
+            if module == *uri || self.deps(&module).await?.contains(uri) {
+                modules.push(module);
+            }
+        }
+        Ok(modules)
+    }
+
+    async fn locals(&mut self, uri: &Url) -> AppResult<Locals> {
+        let ust = self.ust(uri).await?;
+        let mut collector =
+            LocalsCollector { uri: uri.clone(), locals: Locals::default(), scope: vec![] };
+        ust.collect_locals(&mut collector);
+        Ok(collector.locals)
+    }
+}
+
+/// Check that the new name is a valid identifier
+fn check_identifier(name: &str) -> AppResult {
+    match lex_single(name) {
+        Some(Token::Ident(_)) if name != "Type" => Ok(()),
+        _ => Err(DriverError::InvalidIdentifier { name: name.to_owned() }.into()),
+    }
+}
+
+/// Lex a string which consists of exactly one token
+fn lex_single(input: &str) -> Option<Token> {
+    let mut tokens = Lexer::new(input);
+    match (tokens.next(), tokens.next()) {
+        (Some(Ok((0, token, end))), None) if end == input.len() => Some(token),
+        _ => None,
+    }
+}
+
+/// All occurrences of operators in the given source
+///
+/// Operators only occur in infix declarations and binary operator applications,
+/// so it suffices to look at the tokens of the source.
+fn operators(source: &str) -> Vec<(Span, String)> {
+    Lexer::new(source)
+        .filter_map(|token| match token {
+            Ok((start, Token::Operator(id), end)) => {
+                Some((Span { start: ByteIndex(start as u32), end: ByteIndex(end as u32) }, id))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Resolution of local variables
+//
+//
+
+/// A local binder together with all variables which refer to it
+struct LocalBinder {
+    id: String,
+    span: Span,
+    /// The top-level declaration whose telescope binds this variable
+    owner: Option<IdBound>,
+    /// The spans of all variables which refer to this binder
+    occurrences: Vec<Span>,
+    /// The names of all other local variables which are in scope at the binder or at one of its occurrences
+    visible: HashSet<String>,
+}
+
+#[derive(Default)]
+struct Locals {
+    binders: Vec<LocalBinder>,
+    /// Named arguments, given by the called declaration, the name of the parameter and the span of the name
+    named_args: Vec<(IdBound, String, Span)>,
+    /// The names of the local variables in scope at every call of a top-level declaration
+    scopes: Vec<(Span, HashSet<String>)>,
+}
+
+struct LocalsCollector {
+    uri: Url,
+    locals: Locals,
+    /// The binders which are currently in scope, innermost last
+    ///
+    /// Binders without a span are not part of the source and cannot be renamed.
+    scope: Vec<(String, Option<usize>)>,
+}
+
+impl LocalsCollector {
+    fn visible(&self) -> HashSet<String> {
+        self.scope.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    fn bind(&mut self, name: &VarBind, owner: Option<&IdBind>) {
+        let VarBind::Var { span, id } = name else {
+            return;
+        };
+        let binder = span.map(|span| {
+            self.locals.binders.push(LocalBinder {
+                id: id.clone(),
+                span,
+                owner: owner.map(|owner| IdBound {
+                    span: owner.span,
+                    id: owner.id.clone(),
+                    uri: self.uri.clone(),
+                }),
+                occurrences: vec![],
+                visible: self.visible(),
+            });
+            self.locals.binders.len() - 1
+        });
+        self.scope.push((id.clone(), binder));
+    }
+
+    fn occurrence(&mut self, name: &VarBound) {
+        let Some(span) = name.span else {
+            return;
+        };
+        let visible = self.visible();
+        let binder = self.scope.iter().rev().find(|(id, _)| *id == name.id);
+        if let Some((_, Some(binder))) = binder {
+            let binder = &mut self.locals.binders[*binder];
+            binder.occurrences.push(span);
+            binder.visible.extend(visible.into_iter().filter(|id| *id != name.id));
+        }
+    }
+
+    /// A call of a top-level declaration whose name could be captured by a local variable
+    fn call(&mut self, name: &IdBound, args: &Args) {
+        if let Some(span) = name.span {
+            let visible = self.visible();
+            self.locals.scopes.push((span, visible));
+        }
+        self.args(name, args);
+    }
+
+    fn args(&mut self, callee: &IdBound, args: &Args) {
+        for arg in args.args.iter() {
+            if let Arg::NamedArg { name: VarBound { span: Some(span), id }, .. } = arg {
+                self.locals.named_args.push((callee.clone(), id.clone(), *span));
+            }
+        }
+        args.collect_locals(self);
+    }
+
+    /// Bind the parameters of a telescope
+    ///
+    /// The binders stay in scope until the scope is truncated by the caller.
+    fn telescope(&mut self, telescope: &Telescope, owner: Option<&IdBind>) {
+        for Param { name, typ, .. } in telescope.params.iter() {
+            typ.collect_locals(self);
+            self.bind(name, owner);
+        }
+    }
+
+    fn self_param(&mut self, self_param: &SelfParam) {
+        let SelfParam { name, typ, .. } = self_param;
+        typ.collect_locals(self);
+        self.bind(name, None);
+    }
+}
+
+trait CollectLocals {
+    fn collect_locals(&self, collector: &mut LocalsCollector);
+}
+
+impl<T: CollectLocals> CollectLocals for Vec<T> {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        for x in self {
+            x.collect_locals(collector)
+        }
+    }
+}
+
+impl<T: CollectLocals> CollectLocals for Box<T> {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        (**self).collect_locals(collector)
+    }
+}
+
+impl<T: CollectLocals> CollectLocals for Option<T> {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        if let Some(x) = self {
+            x.collect_locals(collector)
+        }
+    }
+}
+
+impl CollectLocals for Module {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        self.decls.collect_locals(collector)
+    }
+}
+
+impl CollectLocals for Decl {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        let outer = collector.scope.len();
+        match self {
+            Decl::Data(Data { name, typ, ctors, .. }) => {
+                collector.telescope(typ, Some(name));
+                collector.scope.truncate(outer);
+                ctors.collect_locals(collector);
+            }
+            Decl::Codata(Codata { name, typ, dtors, .. }) => {
+                collector.telescope(typ, Some(name));
+                collector.scope.truncate(outer);
+                dtors.collect_locals(collector);
+            }
+            Decl::Def(Def { name, params, self_param, ret_typ, cases, .. }) => {
+                collector.telescope(params, Some(name));
+                cases.collect_locals(collector);
+                collector.self_param(self_param);
+                ret_typ.collect_locals(collector);
+            }
+            Decl::Codef(Codef { name, params, typ, cases, .. }) => {
+                collector.telescope(params, Some(name));
+                typ.collect_locals(collector);
+                cases.collect_locals(collector);
+            }
+            Decl::Let(Let { name, params, typ, body, .. }) => {
+                collector.telescope(params, Some(name));
+                typ.collect_locals(collector);
+                body.collect_locals(collector);
+            }
+            Decl::Extern(Extern { name, params, typ, .. }) => {
+                collector.telescope(params, Some(name));
+                typ.collect_locals(collector);
+            }
+            Decl::Infix(_) | Decl::Note(_) => {}
+        }
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectLocals for Ctor {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        let Ctor { name, params, typ, .. } = self;
+        let outer = collector.scope.len();
+        collector.telescope(params, Some(name));
+        typ.collect_locals(collector);
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectLocals for Dtor {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        let Dtor { name, params, self_param, ret_typ, .. } = self;
+        let outer = collector.scope.len();
+        collector.telescope(params, Some(name));
+        collector.self_param(self_param);
+        ret_typ.collect_locals(collector);
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectLocals for Exp {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        match self {
+            Exp::Variable(Variable { name, .. }) => collector.occurrence(name),
+            Exp::TypCtor(e) => e.collect_locals(collector),
+            Exp::Call(Call { name, args, is_bin_op, .. }) => match is_bin_op {
+                // For binary operator applications, the name does not occur in the source.
+                Some(_) => args.collect_locals(collector),
+                None => collector.call(name, args),
+            },
+            Exp::DotCall(DotCall { exp, name, args, .. }) => {
+                // Destructors and definitions are never resolved in the local context.
+                exp.collect_locals(collector);
+                collector.args(name, args);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.collect_locals(collector);
+                typ.collect_locals(collector);
+            }
+            Exp::CompiledMatch(CompiledMatch { compiled, .. }) => {
+                compiled.collect_locals(collector)
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.collect_locals(collector);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    let outer = collector.scope.len();
+                    collector.bind(&param.name, None);
+                    ret_typ.collect_locals(collector);
+                    collector.scope.truncate(outer);
+                }
+                cases.collect_locals(collector);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.collect_locals(collector),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                typ.collect_locals(collector);
+                bound.collect_locals(collector);
+                let outer = collector.scope.len();
+                collector.bind(name, None);
+                body.collect_locals(collector);
+                collector.scope.truncate(outer);
+            }
+            Exp::DoBlock(DoBlock { statements, .. }) => statements.collect_locals(collector),
+            Exp::Hole(_) | Exp::TypeUniv(_) | Exp::Literal(_) => {}
+        }
+    }
+}
+
+impl CollectLocals for TypCtor {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        let TypCtor { name, args, is_bin_op, .. } = self;
+        match is_bin_op {
+            Some(_) => args.collect_locals(collector),
+            None => collector.call(name, args),
+        }
+    }
+}
+
+impl CollectLocals for Args {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        for arg in self.args.iter() {
+            match arg {
+                Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => {
+                    arg.collect_locals(collector)
+                }
+                Arg::InsertedImplicitArg { .. } => {}
+            }
+        }
+    }
+}
+
+impl CollectLocals for Case {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        let Case { pattern, body, .. } = self;
+        let outer = collector.scope.len();
+        for param in pattern.params.params.iter() {
+            collector.bind(&param.name, None);
+        }
+        body.collect_locals(collector);
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectLocals for DoStatements {
+    fn collect_locals(&self, collector: &mut LocalsCollector) {
+        let outer = collector.scope.len();
+        match self {
+            DoStatements::Bind { name, bound, body, .. } => {
+                bound.collect_locals(collector);
+                collector.bind(name, None);
+                body.collect_locals(collector);
+            }
+            DoStatements::Let { name, typ, bound, body, .. } => {
+                typ.collect_locals(collector);
+                bound.collect_locals(collector);
+                collector.bind(name, None);
+                body.collect_locals(collector);
+            }
+            DoStatements::Return { exp, .. } => exp.collect_locals(collector),
+        }
+        collector.scope.truncate(outer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use crate::fs::InMemorySource;
+
+    use super::*;
+
+    const SOURCE: &str = "data Nat { Z, S(n: Nat) }\n\ndef Nat.pred: Nat { Z => Z, S(m) => m }\n";
+
+    fn prepare_rename_at(idx: u32) -> Option<(Span, String)> {
+        let uri = Url::parse("inmemory:///rename.pol").unwrap();
+        let mut source = InMemorySource::new();
+        source.insert(uri.clone(), SOURCE.to_owned());
+        let mut db = Database::from_source(source);
+        block_on(db.prepare_rename(&uri, ByteIndex(idx)))
+    }
+
+    fn span_of(needle: &str, nth: usize) -> Span {
+        let (start, _) = SOURCE.match_indices(needle).nth(nth).unwrap();
+        Span { start: ByteIndex(start as u32), end: ByteIndex((start + needle.len()) as u32) }
+    }
+
+    #[test]
+    fn cursor_inside_global() {
+        let span = span_of("Nat", 0);
+        assert_eq!(prepare_rename_at(span.start.0 + 1), Some((span, "Nat".to_owned())));
+    }
+
+    #[test]
+    fn cursor_after_global() {
+        let span = span_of("pred", 0);
+        assert_eq!(prepare_rename_at(span.end.0), Some((span, "pred".to_owned())));
+    }
+
+    #[test]
+    fn cursor_after_local() {
+        let span = span_of("m", 0);
+        assert_eq!(prepare_rename_at(span.end.0), Some((span, "m".to_owned())));
+    }
+
+    #[test]
+    fn cursor_after_whitespace() {
+        let span = span_of("Nat", 0);
+        assert_eq!(prepare_rename_at(span.end.0 + 1), None);
+    }
+}
@@ -64,6 +64,38 @@ impl From<polarity_lang_elaborator::result::TypeError> for AppError {
     }
 }
 
+/// The sum of all warnings that can occur in the pipeline
+#[derive(Error, Diagnostic, Debug, Clone)]
+#[error(transparent)]
+#[diagnostic(transparent)]
+pub enum AppWarning {
+    Lowering(#[from] polarity_lang_lowering::LoweringWarning),
+    Type(#[from] polarity_lang_elaborator::result::TypeWarning),
+}
+
+impl AppWarning {
+    /// Whether the warning reports a top-level declaration which is not used in its module
+    pub fn is_unused_decl(&self) -> bool {
+        matches!(
+            self,
+            AppWarning::Lowering(polarity_lang_lowering::LoweringWarning::UnusedDecl { .. })
+        )
+    }
+
+    /// Whether the warning marks code which can be removed without changing the program
+    pub fn is_unnecessary(&self) -> bool {
+        matches!(
+            self,
+            AppWarning::Lowering(
+                polarity_lang_lowering::LoweringWarning::UnusedImport { .. }
+                    | polarity_lang_lowering::LoweringWarning::UnusedVariable { .. }
+                    | polarity_lang_lowering::LoweringWarning::UnusedDecl { .. }
+                    | polarity_lang_lowering::LoweringWarning::UnreachableCase { .. }
+            )
+        )
+    }
+}
+
 /// An error that can occur in the driver itself
 #[derive(Error, Debug, Diagnostic, Clone)]
 pub enum DriverError {
@@ -92,6 +124,14 @@ pub enum DriverError {
         "The given index is contained in the file, but is not a boundary of a UTF-8 code point."
     )]
     InvalidCharBoundary { given: usize },
+    #[error("{name} is not a valid identifier")]
+    InvalidIdentifier { name: String },
+    #[error("{name} is not a valid operator")]
+    InvalidOperator { name: String },
+    #[error("{name} is already defined")]
+    NameAlreadyDefined { name: String },
+    #[error("{name} would shadow a local variable")]
+    NameShadowsLocal { name: String },
     #[error("Impossible: {0}")]
     Impossible(String),
 }
@@ -0,0 +1,249 @@
+//! Normalization and type inference of a selected expression
+//!
+//! The selected expression is the smallest expression of the typechecked module which encloses
+//! the selection. Its local variables are bound in the same telescopes as during typechecking,
+//! so the expression can be normalized with every local variable treated as a neutral term.
+
+use std::rc::Rc;
+
+use polarity_lang_ast::ctx::LevelCtx;
+use polarity_lang_ast::*;
+use polarity_lang_elaborator::normalizer::env::ToEnv;
+use polarity_lang_elaborator::normalizer::normalize::Normalize;
+use polarity_lang_miette_util::codespan::Span;
+use url::Url;
+
+use crate::database::Database;
+use crate::result::{AppErrors, AppResult};
+
+impl Database {
+    /// Normalize the smallest expression which encloses the given span
+    ///
+    /// Returns `None` if no expression encloses the span.
+    pub async fn normalize_selection(
+        &mut self,
+        uri: &Url,
+        span: Span,
+    ) -> AppResult<Option<Box<Exp>>> {
+        let Some((exp, ctx)) = self.selected_exp(uri, span).await? else {
+            return Ok(None);
+        };
+        let info_table = self.type_info_table(uri).await?;
+        let nf = exp.normalize(&Rc::new(info_table), &mut ctx.env()).map_err(AppErrors::from)?;
+        Ok(Some(nf))
+    }
+
+    /// The type of the smallest expression which encloses the given span, as inferred during elaboration
+    pub async fn infer_selection_type(
+        &mut self,
+        uri: &Url,
+        span: Span,
+    ) -> AppResult<Option<Box<Exp>>> {
+        let exp = self.selected_exp(uri, span).await?;
+        Ok(exp.and_then(|(exp, _)| exp.typ()))
+    }
+
+    /// The smallest expression which encloses the given span, together with its local context
+    async fn selected_exp(&mut self, uri: &Url, span: Span) -> AppResult<Option<(Exp, LevelCtx)>> {
+        let module = self.ast(uri).await?;
+        let mut finder = SelectionFinder { selection: span, scope: vec![], selected: None };
+        module.find_selection(&mut finder);
+        Ok(finder.selected)
+    }
+}
+
+struct SelectionFinder {
+    selection: Span,
+    /// The telescopes which are currently bound, outermost first
+    scope: Vec<Vec<VarBind>>,
+    selected: Option<(Exp, LevelCtx)>,
+}
+
+impl SelectionFinder {
+    fn encloses(&self, span: Option<Span>) -> bool {
+        span.is_some_and(|span| {
+            span.start <= self.selection.start && self.selection.end <= span.end
+        })
+    }
+
+    /// Visit an expression in the current scope
+    ///
+    /// Expressions are visited from the outside in, so the last enclosing expression is the smallest one.
+    fn exp(&mut self, exp: &Exp) {
+        let span = exp.span();
+        if span.is_some() && !self.encloses(span) {
+            return;
+        }
+        if span.is_some() {
+            self.selected = Some((exp.clone(), self.scope.clone().into()));
+        }
+        exp.find_selection(self);
+    }
+
+    /// Run `f` with one more telescope in scope
+    fn bind<F: FnOnce(&mut Self)>(&mut self, names: Vec<VarBind>, f: F) {
+        self.scope.push(names);
+        f(self);
+        self.scope.pop();
+    }
+
+    /// Bind a telescope, whose parameter types can refer to the previous parameters
+    ///
+    /// The telescope stays in scope until it is popped by the caller.
+    fn telescope(&mut self, telescope: &Telescope) {
+        self.scope.push(vec![]);
+        for Param { name, typ, .. } in telescope.params.iter() {
+            self.exp(typ);
+            self.scope.last_mut().unwrap().push(name.clone());
+        }
+    }
+
+    /// Visit the type of a constructor, destructee or codefinition
+    fn typ_ctor(&mut self, typ: &TypCtor) {
+        if self.encloses(typ.span) {
+            self.selected = Some((typ.clone().into(), self.scope.clone().into()));
+            self.args(&typ.args);
+        }
+    }
+
+    fn args(&mut self, args: &Args) {
+        for arg in args.args.iter() {
+            match arg {
+                Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => self.exp(arg),
+                Arg::InsertedImplicitArg { .. } => {}
+            }
+        }
+    }
+}
+
+trait FindSelection {
+    fn find_selection(&self, finder: &mut SelectionFinder);
+}
+
+impl<T: FindSelection> FindSelection for Vec<T> {
+    fn find_selection(&self, finder: &mut SelectionFinder) {
+        for x in self {
+            x.find_selection(finder)
+        }
+    }
+}
+
+impl FindSelection for Module {
+    fn find_selection(&self, finder: &mut SelectionFinder) {
+        self.decls.find_selection(finder)
+    }
+}
+
+impl FindSelection for Decl {
+    fn find_selection(&self, finder: &mut SelectionFinder) {
+        match self {
+            Decl::Data(Data { typ, ctors, .. }) => {
+                finder.telescope(typ);
+                finder.scope.pop();
+                for Ctor { params, typ, .. } in ctors.iter() {
+                    finder.telescope(params);
+                    finder.typ_ctor(typ);
+                    finder.scope.pop();
+                }
+            }
+            Decl::Codata(Codata { typ, dtors, .. }) => {
+                finder.telescope(typ);
+                finder.scope.pop();
+                for Dtor { params, self_param, ret_typ, .. } in dtors.iter() {
+                    finder.telescope(params);
+                    finder.typ_ctor(&self_param.typ);
+                    finder.bind(vec![self_param.name.clone()], |finder| finder.exp(ret_typ));
+                    finder.scope.pop();
+                }
+            }
+            Decl::Def(Def { params, self_param, ret_typ, cases, .. }) => {
+                finder.telescope(params);
+                finder.typ_ctor(&self_param.typ);
+                finder.bind(vec![self_param.name.clone()], |finder| finder.exp(ret_typ));
+                cases.find_selection(finder);
+                finder.scope.pop();
+            }
+            Decl::Codef(Codef { params, typ, cases, .. }) => {
+                finder.telescope(params);
+                finder.typ_ctor(typ);
+                cases.find_selection(finder);
+                finder.scope.pop();
+            }
+            Decl::Let(Let { params, typ, body, .. }) => {
+                finder.telescope(params);
+                finder.exp(typ);
+                finder.exp(body);
+                finder.scope.pop();
+            }
+            Decl::Extern(Extern { params, typ, .. }) => {
+                finder.telescope(params);
+                finder.exp(typ);
+                finder.scope.pop();
+            }
+            Decl::Infix(_) | Decl::Note(_) => {}
+        }
+    }
+}
+
+impl FindSelection for Exp {
+    fn find_selection(&self, finder: &mut SelectionFinder) {
+        match self {
+            Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) | Exp::Literal(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => finder.args(args),
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                finder.exp(exp);
+                finder.args(args);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                finder.exp(exp);
+                finder.exp(typ);
+            }
+            Exp::CompiledMatch(CompiledMatch { compiled, .. }) => finder.exp(compiled),
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                finder.exp(on_exp);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    finder.bind(vec![param.name.clone()], |finder| finder.exp(ret_typ));
+                }
+                cases.find_selection(finder);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.find_selection(finder),
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                if let Some(typ) = typ {
+                    finder.exp(typ);
+                }
+                finder.exp(bound);
+                finder.bind(vec![name.clone()], |finder| finder.exp(body));
+            }
+            Exp::DoBlock(DoBlock { statements, .. }) => statements.find_selection(finder),
+        }
+    }
+}
+
+impl FindSelection for Case {
+    fn find_selection(&self, finder: &mut SelectionFinder) {
+        let Case { pattern: Pattern { params, .. }, body, .. } = self;
+        if let Some(body) = body {
+            let names = params.params.iter().map(|param| param.name.clone()).collect();
+            finder.bind(names, |finder| finder.exp(body));
+        }
+    }
+}
+
+impl FindSelection for DoStatements {
+    fn find_selection(&self, finder: &mut SelectionFinder) {
+        match self {
+            DoStatements::Bind { name, bound, body, .. } => {
+                finder.exp(bound);
+                finder.bind(vec![name.clone()], |finder| body.find_selection(finder));
+            }
+            DoStatements::Let { name, typ, bound, body, .. } => {
+                if let Some(typ) = typ {
+                    finder.exp(typ);
+                }
+                finder.exp(bound);
+                finder.bind(vec![name.clone()], |finder| body.find_selection(finder));
+            }
+            DoStatements::Return { exp, .. } => finder.exp(exp),
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Semantic tokens for the LSP server
+//!
+//! The parser cannot distinguish between variables, calls of constructors, calls of let-bound
+//! definitions etc. We therefore classify the tokens using the lowered module.
+
+use lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+use polarity_lang_ast::*;
+use polarity_lang_lowering::{DeclMeta, SymbolTable};
+use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_parser::cst::exp::BindingSite;
+use polarity_lang_parser::cst::ident::Ident;
+use url::Url;
+
+use crate::database::Database;
+use crate::result::AppResult;
+
+// The token types and modifiers are identified by their index in the legend.
+const TYPE: u32 = 0;
+const CONSTRUCTOR: u32 = 1;
+const DESTRUCTOR: u32 = 2;
+const DEFINITION: u32 = 3;
+const FUNCTION: u32 = 4;
+const VARIABLE: u32 = 5;
+const PARAMETER: u32 = 6;
+const IMPLICIT_PARAMETER: u32 = 7;
+const HOLE: u32 = 8;
+
+const DECLARATION: u32 = 1 << 0;
+
+pub fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: vec![
+            SemanticTokenType::TYPE,
+            SemanticTokenType::ENUM_MEMBER,
+            SemanticTokenType::PROPERTY,
+            SemanticTokenType::METHOD,
+            SemanticTokenType::FUNCTION,
+            SemanticTokenType::VARIABLE,
+            SemanticTokenType::PARAMETER,
+            SemanticTokenType::TYPE_PARAMETER,
+            // There is no token type for holes in the LSP specification.
+            // Macros are highlighted differently from everything else by most themes.
+            SemanticTokenType::MACRO,
+        ],
+        token_modifiers: vec![SemanticTokenModifier::DECLARATION],
+    }
+}
+
+impl Database {
+    /// Compute the semantic tokens of a module
+    ///
+    /// If a span is given, only the tokens within this span are returned.
+    /// The tokens are encoded relative to each other as required by the LSP specification.
+    pub async fn semantic_tokens(
+        &mut self,
+        uri: &Url,
+        span: Option<Span>,
+    ) -> AppResult<Vec<SemanticToken>> {
+        let module = self.ust(uri).await?;
+        let symbol_table = self.symbol_table_in_scope(uri).await?;
+
+        let mut collector = TokenCollector { symbol_table, tokens: vec![], scope: vec![] };
+        module.collect_tokens(&mut collector);
+
+        let mut tokens = collector.tokens;
+        tokens.sort_by_key(|token| token.span.start);
+        // Elaboration of omitted types refers back to the name of the declaration,
+        // which would result in overlapping tokens. We keep the first one.
+        tokens.dedup_by(|next, prev| next.span.start < prev.span.end);
+        if let Some(span) = span {
+            tokens.retain(|token| span.start <= token.span.start && token.span.end <= span.end);
+        }
+
+        let mut encoded = Vec::with_capacity(tokens.len());
+        let (mut prev_line, mut prev_start) = (0, 0);
+        for Token { span, token_type, modifiers } in tokens {
+            let Some(range) = self.span_to_locations(uri, span) else {
+                continue;
+            };
+            let (line, start) = (range.start.line, range.start.character);
+            let delta_line = line - prev_line;
+            let delta_start = if delta_line == 0 { start - prev_start } else { start };
+            encoded.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length: span.end.0 - span.start.0,
+                token_type,
+                token_modifiers_bitset: modifiers,
+            });
+            (prev_line, prev_start) = (line, start);
+        }
+        Ok(encoded)
+    }
+}
+
+struct Token {
+    span: Span,
+    token_type: u32,
+    modifiers: u32,
+}
+
+struct TokenCollector {
+    /// Used to determine whether named arguments are given for implicit parameters
+    symbol_table: SymbolTable,
+    tokens: Vec<Token>,
+    /// The token types of the local binders in scope, innermost last
+    scope: Vec<(String, u32)>,
+}
+
+impl TokenCollector {
+    fn add(&mut self, span: Option<Span>, token_type: u32, modifiers: u32) {
+        if let Some(span) = span {
+            self.tokens.push(Token { span, token_type, modifiers });
+        }
+    }
+
+    fn bind(&mut self, name: &VarBind, token_type: u32) {
+        if let VarBind::Var { span, id } = name {
+            self.add(*span, token_type, DECLARATION);
+            self.scope.push((id.clone(), token_type));
+        }
+    }
+
+    fn variable(&mut self, name: &VarBound) {
+        let token_type = self
+            .scope
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == name.id)
+            .map(|(_, token_type)| *token_type)
+            .unwrap_or(VARIABLE);
+        self.add(name.span, token_type, 0)
+    }
+
+    fn telescope(&mut self, telescope: &Telescope) {
+        for Param { implicit, name, typ, .. } in telescope.params.iter() {
+            typ.collect_tokens(self);
+            self.bind(name, if *implicit { IMPLICIT_PARAMETER } else { PARAMETER });
+        }
+    }
+
+    fn self_param(&mut self, self_param: &SelfParam) {
+        let SelfParam { name, typ, .. } = self_param;
+        typ.collect_tokens(self);
+        self.bind(name, PARAMETER);
+    }
+
+    fn args(&mut self, callee: &IdBound, args: &Args) {
+        for arg in args.args.iter() {
+            match arg {
+                Arg::UnnamedArg { arg, .. } => arg.collect_tokens(self),
+                Arg::NamedArg { name, arg, .. } => {
+                    let token_type = if self.is_implicit_param(callee, &name.id) {
+                        IMPLICIT_PARAMETER
+                    } else {
+                        PARAMETER
+                    };
+                    self.add(name.span, token_type, 0);
+                    arg.collect_tokens(self);
+                }
+                Arg::InsertedImplicitArg { .. } => {}
+            }
+        }
+    }
+
+    fn is_implicit_param(&self, callee: &IdBound, param: &str) -> bool {
+        let ident = Ident { span: Default::default(), id: callee.id.clone() };
+        let params = match self.symbol_table.lookup(&ident) {
+            Ok((
+                DeclMeta::Data { params, .. }
+                | DeclMeta::Codata { params }
+                | DeclMeta::Def { params }
+                | DeclMeta::Codef { params }
+                | DeclMeta::Ctor { params, .. }
+                | DeclMeta::Dtor { params }
+                | DeclMeta::Let { params, .. }
+                | DeclMeta::Extern { params, .. },
+                _,
+            )) => params,
+            _ => return false,
+        };
+        params.0.iter().any(|p| {
+            p.implicit
+                && std::iter::once(&p.name).chain(p.names.iter()).any(|name| match name {
+                    BindingSite::Var { name, .. } => name.id == param,
+                    BindingSite::Wildcard { .. } => false,
+                })
+        })
+    }
+}
+
+trait CollectTokens {
+    fn collect_tokens(&self, collector: &mut TokenCollector);
+}
+
+impl<T: CollectTokens> CollectTokens for Vec<T> {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        for x in self {
+            x.collect_tokens(collector)
+        }
+    }
+}
+
+impl<T: CollectTokens> CollectTokens for Box<T> {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        (**self).collect_tokens(collector)
+    }
+}
+
+impl<T: CollectTokens> CollectTokens for Option<T> {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        if let Some(x) = self {
+            x.collect_tokens(collector)
+        }
+    }
+}
+
+impl CollectTokens for Module {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        self.decls.collect_tokens(collector)
+    }
+}
+
+impl CollectTokens for Decl {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        let outer = collector.scope.len();
+        match self {
+            Decl::Data(Data { name, typ, ctors, .. }) => {
+                collector.add(name.span, TYPE, DECLARATION);
+                collector.telescope(typ);
+                collector.scope.truncate(outer);
+                ctors.collect_tokens(collector);
+            }
+            Decl::Codata(Codata { name, typ, dtors, .. }) => {
+                collector.add(name.span, TYPE, DECLARATION);
+                collector.telescope(typ);
+                collector.scope.truncate(outer);
+                dtors.collect_tokens(collector);
+            }
+            Decl::Def(Def { name, params, self_param, ret_typ, cases, .. }) => {
+                collector.add(name.span, DEFINITION, DECLARATION);
+                collector.telescope(params);
+                cases.collect_tokens(collector);
+                collector.self_param(self_param);
+                ret_typ.collect_tokens(collector);
+            }
+            Decl::Codef(Codef { name, params, typ, cases, .. }) => {
+                collector.add(name.span, FUNCTION, DECLARATION);
+                collector.telescope(params);
+                typ.collect_tokens(collector);
+                cases.collect_tokens(collector);
+            }
+            Decl::Let(Let { name, params, typ, body, .. }) => {
+                collector.add(name.span, FUNCTION, DECLARATION);
+                collector.telescope(params);
+                typ.collect_tokens(collector);
+                body.collect_tokens(collector);
+            }
+            Decl::Extern(Extern { name, params, typ, .. }) => {
+                collector.add(name.span, FUNCTION, DECLARATION);
+                collector.telescope(params);
+                typ.collect_tokens(collector);
+            }
+            Decl::Infix(_) | Decl::Note(_) => {}
+        }
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectTokens for Ctor {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        let Ctor { name, params, typ, .. } = self;
+        let outer = collector.scope.len();
+        collector.add(name.span, CONSTRUCTOR, DECLARATION);
+        collector.telescope(params);
+        typ.collect_tokens(collector);
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectTokens for Dtor {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        let Dtor { name, params, self_param, ret_typ, .. } = self;
+        let outer = collector.scope.len();
+        collector.add(name.span, DESTRUCTOR, DECLARATION);
+        collector.telescope(params);
+        collector.self_param(self_param);
+        ret_typ.collect_tokens(collector);
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectTokens for Exp {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        match self {
+            Exp::Variable(Variable { name, .. }) => collector.variable(name),
+            Exp::TypCtor(e) => e.collect_tokens(collector),
+            Exp::Call(Call { kind, name, args, is_bin_op, .. }) => {
+                // For binary operator applications, the span of the name lies in the infix declaration.
+                if is_bin_op.is_none() {
+                    let token_type = match kind {
+                        CallKind::Constructor => CONSTRUCTOR,
+                        CallKind::Codefinition | CallKind::LetBound | CallKind::Extern => FUNCTION,
+                    };
+                    collector.add(name.span, token_type, 0);
+                }
+                collector.args(name, args);
+            }
+            Exp::DotCall(DotCall { kind, exp, name, args, .. }) => {
+                exp.collect_tokens(collector);
+                let token_type = match kind {
+                    DotCallKind::Destructor => DESTRUCTOR,
+                    DotCallKind::Definition => DEFINITION,
+                };
+                collector.add(name.span, token_type, 0);
+                collector.args(name, args);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                exp.collect_tokens(collector);
+                typ.collect_tokens(collector);
+            }
+            Exp::TypeUniv(TypeUniv { span }) => collector.add(*span, TYPE, 0),
+            Exp::CompiledMatch(CompiledMatch { compiled, .. }) => {
+                compiled.collect_tokens(collector)
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                on_exp.collect_tokens(collector);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    let outer = collector.scope.len();
+                    collector.bind(&param.name, VARIABLE);
+                    ret_typ.collect_tokens(collector);
+                    collector.scope.truncate(outer);
+                }
+                cases.collect_tokens(collector);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => cases.collect_tokens(collector),
+            Exp::Hole(Hole { span, kind, .. }) => {
+                if !matches!(kind, MetaVarKind::Inserted) {
+                    collector.add(*span, HOLE, 0);
+                }
+            }
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                typ.collect_tokens(collector);
+                bound.collect_tokens(collector);
+                let outer = collector.scope.len();
+                collector.bind(name, VARIABLE);
+                body.collect_tokens(collector);
+                collector.scope.truncate(outer);
+            }
+            Exp::DoBlock(DoBlock { statements, .. }) => statements.collect_tokens(collector),
+            Exp::Literal(_) => {}
+        }
+    }
+}
+
+impl CollectTokens for TypCtor {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        let TypCtor { name, args, is_bin_op, .. } = self;
+        if is_bin_op.is_none() {
+            collector.add(name.span, TYPE, 0);
+        }
+        collector.args(name, args);
+    }
+}
+
+impl CollectTokens for Case {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        let Case { pattern: Pattern { is_copattern, name, params, .. }, body, .. } = self;
+        let outer = collector.scope.len();
+        collector.add(name.span, if *is_copattern { DESTRUCTOR } else { CONSTRUCTOR }, 0);
+        for param in params.params.iter() {
+            collector.bind(&param.name, VARIABLE);
+        }
+        body.collect_tokens(collector);
+        collector.scope.truncate(outer);
+    }
+}
+
+impl CollectTokens for DoStatements {
+    fn collect_tokens(&self, collector: &mut TokenCollector) {
+        let outer = collector.scope.len();
+        match self {
+            DoStatements::Bind { name, bound, body, .. } => {
+                bound.collect_tokens(collector);
+                collector.bind(name, VARIABLE);
+                body.collect_tokens(collector);
+            }
+            DoStatements::Let { name, typ, bound, body, .. } => {
+                typ.collect_tokens(collector);
+                bound.collect_tokens(collector);
+                collector.bind(name, VARIABLE);
+                body.collect_tokens(collector);
+            }
+            DoStatements::Return { exp, .. } => exp.collect_tokens(collector),
+        }
+        collector.scope.truncate(outer);
+    }
+}
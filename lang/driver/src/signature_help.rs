@@ -0,0 +1,280 @@
+//! Signature help for the LSP server
+//!
+//! Signature help is requested while the arguments of a call are typed, so the module usually
+//! cannot be parsed. We find the enclosing call with the lexer and look up its signature in the
+//! type info table of the last well-formed version of the module.
+
+use lsp_types::{ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation};
+use polarity_lang_ast::*;
+use polarity_lang_elaborator::TypeInfoTable;
+use polarity_lang_elaborator::typechecker::type_info_table::{CtorMeta, DtorMeta};
+use polarity_lang_miette_util::codespan::ByteIndex;
+use polarity_lang_parser::lexer::{Lexer, Token};
+use polarity_lang_printer::Print;
+use url::Url;
+
+use crate::completion::{documentation, scan_use_paths};
+use crate::database::{Database, OpenClosed};
+use crate::result::AppResult;
+
+impl Database {
+    /// The signature of the call whose argument list encloses the given index
+    pub async fn signature_help_at_index(
+        &mut self,
+        uri: &Url,
+        idx: ByteIndex,
+    ) -> AppResult<Option<SignatureHelp>> {
+        let source = self.source(uri).await?;
+        let idx = idx.to_usize().min(source.len());
+        if !source.is_char_boundary(idx) {
+            return Ok(None);
+        }
+        let Some(call) = enclosing_call(&source[..idx]) else {
+            return Ok(None);
+        };
+
+        let (info_table, modules) = self.type_info_tables_in_scope(uri, &source).await;
+        let Some(signature) = modules.into_iter().find_map(|uri| {
+            let name = IdBound { span: None, id: call.name.clone(), uri };
+            if call.is_dot_call {
+                dot_call_signature(&info_table, &name)
+            } else {
+                call_signature(&info_table, &name)
+            }
+        }) else {
+            return Ok(None);
+        };
+
+        let active_parameter = active_parameter(&signature.params, &call.args);
+        Ok(Some(SignatureHelp {
+            signatures: vec![signature.to_information()],
+            active_signature: Some(0),
+            active_parameter,
+        }))
+    }
+
+    /// The type info tables of the module and of all the modules it imports
+    ///
+    /// If the module cannot be lowered, the type info table of its last well-formed version is used.
+    /// The URIs of the modules are returned in the order in which names are looked up.
+    async fn type_info_tables_in_scope(
+        &mut self,
+        uri: &Url,
+        source: &str,
+    ) -> (TypeInfoTable, Vec<Url>) {
+        let mut info_table = TypeInfoTable::default();
+        let mut modules = vec![];
+
+        let mod_info_table = match self.module_type_info_table(uri, false).await {
+            Ok(mod_info_table) => Some(mod_info_table),
+            Err(_) => {
+                self.type_info_table.get_even_if_stale(uri).map(|open_closed| match open_closed {
+                    OpenClosed::Open(table) | OpenClosed::Closed(table) => table.clone(),
+                })
+            }
+        };
+        if let Some(mod_info_table) = mod_info_table {
+            info_table.insert(uri.clone(), mod_info_table);
+            modules.push(uri.clone());
+        }
+
+        for path in scan_use_paths(source) {
+            let Ok(dep) = self.resolve_module_name(&path, uri) else {
+                continue;
+            };
+            if let Ok(mod_info_table) = self.module_type_info_table(&dep, true).await {
+                info_table.insert(dep.clone(), mod_info_table);
+                modules.push(dep);
+            }
+        }
+
+        (info_table, modules)
+    }
+}
+
+/// A call whose argument list is not yet closed
+struct OpenCall {
+    name: String,
+    is_dot_call: bool,
+    /// For each argument given so far, the name if it is a named argument
+    args: Vec<Option<String>>,
+}
+
+/// Find the innermost call whose argument list is still open at the end of `source`
+fn enclosing_call(source: &str) -> Option<OpenCall> {
+    // Parentheses which do not belong to a call, braces and brackets are pushed as `None`.
+    let mut stack: Vec<Option<OpenCall>> = vec![];
+    let mut prev: [Option<Token>; 2] = [None, None];
+
+    for token in Lexer::new(source).filter_map(Result::ok).map(|(_, token, _)| token) {
+        match &token {
+            Token::LParen => {
+                let call = match &prev {
+                    [Some(Token::Ident(name)), before] => Some(OpenCall {
+                        name: name.clone(),
+                        is_dot_call: matches!(before, Some(Token::Dot)),
+                        args: vec![None],
+                    }),
+                    _ => None,
+                };
+                stack.push(call);
+            }
+            Token::LBrace | Token::LBracket => stack.push(None),
+            Token::RParen | Token::RBrace | Token::RBracket => {
+                stack.pop();
+            }
+            Token::Comma => {
+                if let Some(Some(call)) = stack.last_mut() {
+                    call.args.push(None);
+                }
+            }
+            Token::ColonEq => {
+                if let [Some(Token::Ident(name)), Some(Token::LParen | Token::Comma)] = &prev
+                    && let Some(Some(call)) = stack.last_mut()
+                {
+                    *call.args.last_mut().unwrap() = Some(name.clone());
+                }
+            }
+            _ => {}
+        }
+        prev = [Some(token), prev[0].take()];
+    }
+
+    stack.pop().flatten()
+}
+
+/// Determine the parameter which corresponds to the last given argument
+///
+/// Implicit parameters can only be given as named arguments, unnamed arguments are matched
+/// with the next explicit parameter.
+fn active_parameter(params: &Telescope, args: &[Option<String>]) -> Option<u32> {
+    let mut next = 0;
+    let mut active = None;
+    for arg in args {
+        let idx = match arg {
+            Some(name) => params.params.iter().position(|param| match &param.name {
+                VarBind::Var { id, .. } => id == name,
+                VarBind::Wildcard { .. } => false,
+            })?,
+            None => (next..params.params.len()).find(|idx| !params.params[*idx].implicit)?,
+        };
+        active = Some(idx as u32);
+        next = idx + 1;
+    }
+    active
+}
+
+struct Signature {
+    /// The self parameter of destructors and definitions
+    self_typ: Option<TypCtor>,
+    name: String,
+    params: Telescope,
+    ret_typ: Option<Box<Exp>>,
+    doc: Option<DocComment>,
+}
+
+impl Signature {
+    fn to_information(&self) -> SignatureInformation {
+        let Signature { self_typ, name, params, ret_typ, doc } = self;
+
+        let mut label = String::new();
+        if let Some(self_typ) = self_typ {
+            label.push_str(&self_typ.print_to_string(None));
+            label.push('.');
+        }
+        label.push_str(name);
+        label.push('(');
+
+        // The offsets of the parameters are given in UTF-16 code units.
+        let mut parameters = vec![];
+        for (idx, param) in params.params.iter().enumerate() {
+            if idx > 0 {
+                label.push_str(", ");
+            }
+            let start = label.encode_utf16().count() as u32;
+            label.push_str(&param.print_to_string(None));
+            let end = label.encode_utf16().count() as u32;
+            parameters.push(ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, end]),
+                documentation: None,
+            });
+        }
+
+        label.push(')');
+        if let Some(ret_typ) = ret_typ {
+            label.push_str(": ");
+            label.push_str(&ret_typ.print_to_string(None));
+        }
+
+        SignatureInformation {
+            label,
+            documentation: documentation(doc),
+            parameters: Some(parameters),
+            active_parameter: None,
+        }
+    }
+}
+
+fn call_signature(info_table: &TypeInfoTable, name: &IdBound) -> Option<Signature> {
+    let signature = |params: &Telescope, ret_typ: Option<Box<Exp>>, doc: &Option<DocComment>| {
+        Some(Signature {
+            self_typ: None,
+            name: name.id.clone(),
+            params: params.clone(),
+            ret_typ,
+            doc: doc.clone(),
+        })
+    };
+
+    if let Ok(CtorMeta { params, typ }) = info_table.lookup_ctor(name) {
+        let doc = info_table.lookup_data(&typ.name).ok().and_then(|data| {
+            data.ctors.iter().find(|ctor| ctor.name.id == name.id).and_then(|ctor| ctor.doc.clone())
+        });
+        return signature(params, Some(Box::new(typ.clone().into())), &doc);
+    }
+    if let Ok(Codef { params, typ, doc, .. }) = info_table.lookup_codef(name) {
+        return signature(params, Some(Box::new(typ.clone().into())), doc);
+    }
+    if let Ok(Let { params, typ, doc, .. }) = info_table.lookup_let(name) {
+        return signature(params, Some(typ.clone()), doc);
+    }
+    if let Ok(Extern { params, typ, doc, .. }) = info_table.lookup_extern(name) {
+        return signature(params, Some(typ.clone()), doc);
+    }
+    if let Ok(Data { typ, doc, .. }) = info_table.lookup_data(name) {
+        return signature(typ, None, doc);
+    }
+    if let Ok(Codata { typ, doc, .. }) = info_table.lookup_codata(name) {
+        return signature(typ, None, doc);
+    }
+    None
+}
+
+fn dot_call_signature(info_table: &TypeInfoTable, name: &IdBound) -> Option<Signature> {
+    if let Ok(DtorMeta { params, self_param, ret_typ }) = info_table.lookup_dtor(name) {
+        let doc = info_table.lookup_codata(&self_param.typ.name).ok().and_then(|codata| {
+            codata
+                .dtors
+                .iter()
+                .find(|dtor| dtor.name.id == name.id)
+                .and_then(|dtor| dtor.doc.clone())
+        });
+        return Some(Signature {
+            self_typ: Some(self_param.typ.clone()),
+            name: name.id.clone(),
+            params: params.clone(),
+            ret_typ: Some(ret_typ.clone()),
+            doc,
+        });
+    }
+    if let Ok(Def { params, self_param, ret_typ, doc, .. }) = info_table.lookup_def(name) {
+        return Some(Signature {
+            self_typ: Some(self_param.typ.clone()),
+            name: name.id.clone(),
+            params: params.clone(),
+            ret_typ: Some(ret_typ.clone()),
+            doc: doc.clone(),
+        });
+    }
+    None
+}
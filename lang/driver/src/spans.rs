@@ -1,10 +1,12 @@
 use lsp_types::{HoverContents, Position, Range};
+use polarity_lang_ast::IdBound;
 use polarity_lang_miette_util::codespan::{ByteIndex, LineIndex, Span};
+use polarity_lang_parser::cst;
 use url::Url;
 
 use crate::database::Database;
 
-use super::info::Item;
+use super::info::{HoleInfo, Item, Reference};
 
 impl Database {
     pub fn location_to_index(&self, uri: &Url, location: Position) -> Option<ByteIndex> {
@@ -59,4 +61,123 @@ impl Database {
             intervals.max_by(|i1, i2| (i1.stop - i1.start).cmp(&(i2.stop - i1.start)));
         largest_interval.map(|interval| interval.val.clone())
     }
+
+    pub async fn hole_at_span(&mut self, uri: &Url, span: Span) -> Option<(Span, HoleInfo)> {
+        let lapper = self.holes_by_id(uri).await.ok()?;
+        // An empty span still selects the hole starting at it.
+        let intervals = lapper.find(span.start.0, span.end.0.max(span.start.0 + 1));
+        let smallest_interval =
+            intervals.min_by(|i1, i2| (i1.stop - i1.start).cmp(&(i2.stop - i2.start)));
+        smallest_interval.map(|interval| {
+            (
+                Span { start: ByteIndex(interval.start), end: ByteIndex(interval.stop) },
+                interval.val.clone(),
+            )
+        })
+    }
+
+    pub async fn reference_at_index(&mut self, uri: &Url, idx: ByteIndex) -> Option<Reference> {
+        let span = Span { start: idx, end: ByteIndex(idx.0 + 1) };
+        let lapper = self.references_by_id(uri).await.ok()?;
+        let intervals = lapper.find(span.start.0, span.end.0);
+        let smallest_interval =
+            intervals.min_by(|i1, i2| (i1.stop - i1.start).cmp(&(i2.stop - i2.start)));
+        smallest_interval.map(|interval| interval.val.clone())
+    }
+
+    /// Find all occurrences of the name of a top-level declaration
+    ///
+    /// Occurrences are searched in the module declaring the name and in all of its reverse dependencies.
+    pub async fn references(
+        &mut self,
+        name: &IdBound,
+        include_declaration: bool,
+    ) -> Vec<(Url, Span)> {
+        let modules = self.dependent_modules(&name.uri).await;
+
+        let mut references = vec![];
+        for module in modules.iter() {
+            let spans = self.references_in_module(module, name, include_declaration).await;
+            references.extend(spans.into_iter().map(|span| (module.clone(), span)));
+        }
+        references
+    }
+
+    /// Find the occurrences of the name of a top-level declaration within a single module
+    ///
+    /// Unlike `references`, this neither rebuilds the dependency graph nor looks at other modules.
+    pub async fn references_in_module(
+        &mut self,
+        module: &Url,
+        name: &IdBound,
+        include_declaration: bool,
+    ) -> Vec<Span> {
+        let Ok(lapper) = self.references_by_id(module).await else {
+            return vec![];
+        };
+
+        // Elaboration may insert names which point back to the declaration, e.g. the return type of constructors.
+        // These do not correspond to occurrences in the source and are skipped.
+        let declaration = lapper
+            .iter()
+            .find(|interval| interval.val.name == *name && interval.val.is_declaration)
+            .map(|interval| (interval.start, interval.stop));
+
+        let mut references: Vec<Span> = lapper
+            .iter()
+            .filter(|interval| interval.val.name == *name)
+            .filter(|interval| {
+                interval.val.is_declaration
+                    || *module != name.uri
+                    || declaration != Some((interval.start, interval.stop))
+            })
+            .filter(|interval| include_declaration || !interval.val.is_declaration)
+            .map(|interval| Span {
+                start: ByteIndex(interval.start),
+                end: ByteIndex(interval.stop),
+            })
+            .collect();
+        references.extend(self.infix_references(module, name).await);
+        references.dedup();
+        references
+    }
+
+    /// The module itself together with all modules which (transitively) import it
+    pub async fn dependent_modules(&mut self, uri: &Url) -> Vec<Url> {
+        // Make sure the dependency graph knows about all loaded modules.
+        // If some module is not well-formed, we fall back to the last dependency graph.
+        let _ = self.build_dependency_dag().await;
+
+        let mut modules = vec![uri.clone()];
+        modules.extend(
+            self.deps.reverse_dependencies(uri).into_iter().filter(|dep| *dep != uri).cloned(),
+        );
+        modules
+    }
+
+    /// Find the occurrences of a name on the right-hand side of infix declarations
+    ///
+    /// These are not part of the AST, so we have to look them up in the CST.
+    async fn infix_references(&mut self, module: &Url, name: &IdBound) -> Vec<Span> {
+        let (Ok(cst), Ok(symbol_table)) =
+            (self.cst(module).await, self.symbol_table_in_scope(module).await)
+        else {
+            return vec![];
+        };
+        let mut references = vec![];
+        for decl in cst.decls.iter() {
+            let cst::decls::Decl::Infix(infix) = decl else {
+                continue;
+            };
+            let rhs = &infix.rhs.name;
+            if !rhs.quals.is_empty() || rhs.id != name.id {
+                continue;
+            }
+            let ident = cst::ident::Ident { span: rhs.span, id: rhs.id.clone() };
+            if symbol_table.lookup(&ident).is_ok_and(|(_, bound)| bound.uri == name.uri) {
+                references.push(rhs.span);
+            }
+        }
+        references
+    }
 }
@@ -0,0 +1,143 @@
+use lsp_types::{DocumentSymbol, SymbolKind};
+use polarity_lang_ast::*;
+use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_printer::Print;
+use url::Url;
+
+use crate::database::Database;
+use crate::result::AppResult;
+
+impl Database {
+    /// The declarations of a module, arranged as an outline
+    ///
+    /// Constructors and destructors are listed as children of their type.
+    /// Definitions are grouped under their self type if it is declared in the same module.
+    pub async fn document_symbols(&mut self, uri: &Url) -> AppResult<Vec<DocumentSymbol>> {
+        let module = self.ust(uri).await?;
+
+        let mut symbols: Vec<DocumentSymbol> = vec![];
+        // Position of the (co)data types declared in this module in `symbols`
+        let mut types: HashMap<String, usize> = HashMap::default();
+        // Definitions which are grouped under their self type
+        let mut defs: Vec<(String, DocumentSymbol)> = vec![];
+
+        for decl in module.decls.iter() {
+            match decl {
+                Decl::Data(Data { span, name, typ, ctors, .. }) => {
+                    let Some(mut symbol) =
+                        self.symbol(uri, name, *span, SymbolKind::ENUM, Some(typ))
+                    else {
+                        continue;
+                    };
+                    let children = ctors
+                        .iter()
+                        .filter_map(|Ctor { span, name, params, .. }| {
+                            self.symbol(uri, name, *span, SymbolKind::ENUM_MEMBER, Some(params))
+                        })
+                        .collect();
+                    symbol.children = Some(children);
+                    types.insert(name.id.clone(), symbols.len());
+                    symbols.push(symbol);
+                }
+                Decl::Codata(Codata { span, name, typ, dtors, .. }) => {
+                    let Some(mut symbol) =
+                        self.symbol(uri, name, *span, SymbolKind::INTERFACE, Some(typ))
+                    else {
+                        continue;
+                    };
+                    let children = dtors
+                        .iter()
+                        .filter_map(|Dtor { span, name, params, .. }| {
+                            self.symbol(uri, name, *span, SymbolKind::METHOD, Some(params))
+                        })
+                        .collect();
+                    symbol.children = Some(children);
+                    types.insert(name.id.clone(), symbols.len());
+                    symbols.push(symbol);
+                }
+                Decl::Def(Def { span, name, params, self_param, .. }) => {
+                    let Some(symbol) =
+                        self.symbol(uri, name, *span, SymbolKind::METHOD, Some(params))
+                    else {
+                        continue;
+                    };
+                    let type_name = &self_param.typ.name;
+                    if type_name.uri == *uri {
+                        defs.push((type_name.id.clone(), symbol));
+                    } else {
+                        // The self type is declared in another module, so we qualify the name instead.
+                        symbols.push(DocumentSymbol {
+                            name: format!("{}.{}", type_name.id, symbol.name),
+                            ..symbol
+                        });
+                    }
+                }
+                Decl::Codef(Codef { span, name, params, .. }) => {
+                    symbols.extend(self.symbol(
+                        uri,
+                        name,
+                        *span,
+                        SymbolKind::CONSTRUCTOR,
+                        Some(params),
+                    ));
+                }
+                Decl::Let(Let { span, name, params, .. }) => {
+                    symbols.extend(self.symbol(
+                        uri,
+                        name,
+                        *span,
+                        SymbolKind::FUNCTION,
+                        Some(params),
+                    ));
+                }
+                Decl::Extern(Extern { span, name, params, .. }) => {
+                    symbols.extend(self.symbol(
+                        uri,
+                        name,
+                        *span,
+                        SymbolKind::FUNCTION,
+                        Some(params),
+                    ));
+                }
+                Decl::Note(Note { span, name, .. }) => {
+                    symbols.extend(self.symbol(uri, name, *span, SymbolKind::STRING, None));
+                }
+                Decl::Infix(_) => {}
+            }
+        }
+
+        for (type_name, def) in defs {
+            // Definitions can only be declared on types of the same module if these types exist.
+            if let Some(idx) = types.get(&type_name) {
+                symbols[*idx].children.get_or_insert_with(Vec::new).push(def);
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    #[allow(deprecated)]
+    fn symbol(
+        &self,
+        uri: &Url,
+        name: &IdBind,
+        span: Option<Span>,
+        kind: SymbolKind,
+        params: Option<&Telescope>,
+    ) -> Option<DocumentSymbol> {
+        let range = self.span_to_locations(uri, span?)?;
+        let selection_range = self.span_to_locations(uri, name.span?)?;
+        let detail =
+            params.map(|params| params.print_to_string(None)).filter(|params| !params.is_empty());
+        Some(DocumentSymbol {
+            name: name.id.clone(),
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range,
+            selection_range,
+            children: None,
+        })
+    }
+}
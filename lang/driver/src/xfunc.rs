@@ -43,7 +43,7 @@ impl Database {
             match decl.ident() {
                 None => continue,
                 Some(id) => {
-                    decl_spans.insert(id.clone(), decl.span().unwrap());
+                    decl_spans.insert(id.clone(), decl.span_with_comments().unwrap());
                 }
             }
         }
@@ -63,8 +63,11 @@ impl Database {
 
         let mat = polarity_lang_transformations::as_matrix(&module)?;
 
-        let type_span =
-            mat.map.get(type_name).and_then(|x| x.span).ok_or(XfuncError::Impossible {
+        let type_span = mat
+            .map
+            .get(type_name)
+            .and_then(|x| x.span.map(|span| x.comments.span_with(span)))
+            .ok_or(XfuncError::Impossible {
                 message: format!("Could not resolve {type_name}"),
                 span: None,
             })?;
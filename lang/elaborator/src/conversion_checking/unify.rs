@@ -576,8 +576,8 @@ mod tests {
             name,
             args: Args {
                 args: vec![
-                    Arg::UnnamedArg { arg: a, erased: false },
-                    Arg::UnnamedArg { arg: b, erased: false },
+                    Arg::UnnamedArg { arg: a, erased: false, comments: Default::default() },
+                    Arg::UnnamedArg { arg: b, erased: false, comments: Default::default() },
                 ],
             },
             is_bin_op: None,
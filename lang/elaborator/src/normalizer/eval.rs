@@ -40,6 +40,7 @@ impl Eval for Exp {
             Exp::Anno(e) => e.eval(info_table, env),
             Exp::TypeUniv(e) => e.eval(info_table, env),
             Exp::LocalMatch(e) => e.eval(info_table, env),
+            Exp::CompiledMatch(e) => e.compiled.eval(info_table, env),
             Exp::LocalComatch(e) => e.eval(info_table, env),
             Exp::Hole(e) => e.eval(info_table, env),
             Exp::LocalLet(e) => e.eval(info_table, env),
@@ -518,7 +519,7 @@ impl Eval for Case {
     type Val = val::Case;
 
     fn eval(&self, _info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
-        let Case { span, pattern, body } = self;
+        let Case { span, comments: _, pattern, body, from_wildcard: _ } = self;
 
         let body = body.as_ref().map(|body| Closure {
             body: body.clone(),
@@ -540,7 +541,7 @@ impl Eval for LocalLet {
     type Val = Box<Val>;
 
     fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
-        let LocalLet { span: _, name, typ: _, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name, typ: _, bound, body, inferred_type: _ } = self;
         let bound_val = bound.eval(info_table, env)?;
         env.bind_iter([Binder { name: name.clone(), content: bound_val }].into_iter(), |env| {
             body.eval(info_table, env)
@@ -565,7 +566,7 @@ impl Eval for DoStatements {
 
     fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
         match self {
-            DoStatements::Bind { span, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span, comments: _, name, bound, body, inferred_type: _ } => {
                 let bound = bound.eval(info_table, env)?;
                 let binder = Binder {
                     name: name.clone(),
@@ -585,7 +586,7 @@ impl Eval for DoStatements {
                     body: Box::new(body),
                 })
             }
-            DoStatements::Let { span, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let { span, comments: _, name, typ, bound, body, inferred_type: _ } => {
                 let bound = bound.eval(info_table, env)?;
                 let typ = typ.eval(info_table, env)?;
                 let binder = Binder {
@@ -607,7 +608,7 @@ impl Eval for DoStatements {
                     body: Box::new(body),
                 })
             }
-            DoStatements::Return { span, exp, inferred_type: _ } => {
+            DoStatements::Return { span, comments: _, exp, inferred_type: _ } => {
                 Ok(val::DoStatements::Return { span: *span, exp: exp.eval(info_table, env)? })
             }
         }
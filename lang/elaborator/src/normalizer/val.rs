@@ -3,6 +3,8 @@ use std::rc::Rc;
 use log::trace;
 use polarity_lang_ast;
 use polarity_lang_ast::Idx;
+use polarity_lang_ast::InfixOp;
+use polarity_lang_ast::InfixOperand;
 use polarity_lang_ast::Literal;
 use polarity_lang_ast::MetaVar;
 use polarity_lang_ast::Shift;
@@ -112,10 +114,15 @@ impl Shift for Val {
 }
 
 impl Print for Val {
-    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
         match self {
             Val::TypCtor(e) => e.print(cfg, alloc),
-            Val::Call(e) => e.print(cfg, alloc),
+            Val::Call(e) => e.print_prec(cfg, alloc, prec),
             Val::TypeUniv(e) => e.print(cfg, alloc),
             Val::LocalComatch(e) => e.print(cfg, alloc),
             Val::Anno(e) => e.print(cfg, alloc),
@@ -154,7 +161,7 @@ pub struct TypCtor {
     pub span: Option<Span>,
     pub name: polarity_lang_ast::IdBound,
     pub args: Args,
-    pub is_bin_op: Option<String>,
+    pub is_bin_op: Option<polarity_lang_ast::InfixOp>,
 }
 
 impl Shift for TypCtor {
@@ -201,7 +208,7 @@ pub struct Call {
     pub kind: polarity_lang_ast::CallKind,
     pub name: polarity_lang_ast::IdBound,
     pub args: Args,
-    pub is_bin_op: Option<String>,
+    pub is_bin_op: Option<polarity_lang_ast::InfixOp>,
 }
 
 impl Shift for Call {
@@ -221,10 +228,7 @@ impl Print for Call {
         match is_bin_op {
             Some(op) => {
                 assert!(args.0.len() == 2);
-                let arg = args.0[0].print_prec(cfg, alloc, Precedence::Ops);
-                let res = args.0[1].print_prec(cfg, alloc, Precedence::Exp);
-                let fun = arg.append(alloc.space()).append(op).append(alloc.space()).append(res);
-                fun.parens_if(prec > Precedence::NonLet)
+                op.print_app(&args.0[0], &args.0[1], cfg, alloc, prec)
             }
             _ => {
                 let psubst =
@@ -545,6 +549,7 @@ impl ReadBack for DoStatements {
             DoStatements::Bind { span, name, bound, body } => {
                 Ok(polarity_lang_ast::DoStatements::Bind {
                     span: *span,
+                    comments: Default::default(),
                     name: name.clone(),
                     bound: bound.read_back(info_table)?,
                     body: body.read_back(info_table)?,
@@ -554,6 +559,7 @@ impl ReadBack for DoStatements {
             DoStatements::Let { span, name, typ, bound, body } => {
                 Ok(polarity_lang_ast::DoStatements::Let {
                     span: *span,
+                    comments: Default::default(),
                     name: name.clone(),
                     typ: typ.read_back(info_table)?,
                     bound: bound.read_back(info_table)?,
@@ -563,6 +569,7 @@ impl ReadBack for DoStatements {
             }
             DoStatements::Return { span, exp } => Ok(polarity_lang_ast::DoStatements::Return {
                 span: *span,
+                comments: Default::default(),
                 exp: exp.read_back(info_table)?,
                 inferred_type: None,
             }),
@@ -904,6 +911,7 @@ impl ReadBack for Case {
 
         Ok(polarity_lang_ast::Case {
             span: *span,
+            comments: Default::default(),
             pattern: polarity_lang_ast::Pattern {
                 span: None,
                 is_copattern: *is_copattern,
@@ -911,6 +919,7 @@ impl ReadBack for Case {
                 params: params.clone(),
             },
             body: body.read_back(info_table)?,
+            from_wildcard: false,
         })
     }
 }
@@ -1092,30 +1101,38 @@ impl ReadBack for Arg {
             Arg::UnnamedArg(val) => Ok(polarity_lang_ast::Arg::UnnamedArg {
                 arg: val.read_back(info_table)?,
                 erased: false,
+                comments: Default::default(),
             }),
             Arg::NamedArg(name, val) => Ok(polarity_lang_ast::Arg::NamedArg {
                 name: name.clone(),
                 arg: val.read_back(info_table)?,
                 erased: false,
+                comments: Default::default(),
             }),
             Arg::InsertedImplicitArg(val) => Ok(polarity_lang_ast::Arg::UnnamedArg {
                 arg: val.read_back(info_table)?,
                 erased: false,
+                comments: Default::default(),
             }),
         }
     }
 }
 
 impl Print for Arg {
-    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
         match self {
-            Arg::UnnamedArg(val) => val.print(cfg, alloc),
+            Arg::UnnamedArg(val) => val.print_prec(cfg, alloc, prec),
             Arg::NamedArg(name, val) => alloc
                 .text(name.to_string())
                 .append(alloc.space())
                 .append(COLONEQ)
                 .append(alloc.space())
-                .append(val.print(cfg, alloc)),
+                .append(val.print_prec(cfg, alloc, prec)),
             Arg::InsertedImplicitArg(_) => {
                 panic!("Inserted implicit arguments should not be printed")
             }
@@ -1123,6 +1140,19 @@ impl Print for Arg {
     }
 }
 
+impl InfixOperand for Arg {
+    fn infix_op(&self) -> Option<&InfixOp> {
+        match self {
+            // Type constructors are always printed in prefix notation.
+            Arg::UnnamedArg(val) | Arg::NamedArg(_, val) => match &**val {
+                Val::Call(call) => call.is_bin_op.as_ref(),
+                _ => None,
+            },
+            Arg::InsertedImplicitArg(_) => None,
+        }
+    }
+}
+
 impl Arg {
     pub fn to_val(&self) -> Box<Val> {
         match self {
@@ -1192,3 +1222,77 @@ impl ReadBack for Literal {
         Ok(self.clone())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use polarity_lang_ast::{Decl, Module};
+    use polarity_lang_lowering::{SymbolTable, build_symbol_table, lower_module_with_symbol_table};
+    use polarity_lang_printer::Print;
+    use url::Url;
+
+    use crate::TypeInfoTable;
+    use crate::normalizer::env::Env;
+    use crate::normalizer::eval::Eval;
+
+    const DECLS: &str = "
+        data T { A, B, C, Add(x y: T), Pow(x y: T), Mul(x y: T), Eq(x y: T) }
+        infixl 6 _ + _ := Add(_, _)
+        infixr 8 _ <> _ := Pow(_, _)
+        infixl 7 _ × _ := Mul(_, _)
+        infix 4 _ == _ := Eq(_, _)
+    ";
+
+    fn lower(exp: &str) -> Module {
+        let uri = Url::parse("inmemory:///val.pol").unwrap();
+        let source = format!("{DECLS}\nlet t: T {{ {exp} }}");
+        let cst = polarity_lang_parser::parse_module(uri.clone(), &source).unwrap();
+        let mut symbol_table = SymbolTable::default();
+        symbol_table.insert(uri, build_symbol_table(&cst).unwrap().into());
+        lower_module_with_symbol_table(&cst, &symbol_table).unwrap().0
+    }
+
+    /// Evaluate the expression and print the resulting value
+    fn eval_and_print(exp: &str) -> String {
+        let module = lower(exp);
+        let Some(Decl::Let(tl_let)) = module.decls.last() else {
+            panic!("Expected a top-level let");
+        };
+        let info_table = Rc::new(TypeInfoTable::default());
+        let val = tl_let.body.eval(&info_table, &mut Env::empty()).unwrap();
+        val.print_to_string(None)
+    }
+
+    /// Check that printing a value yields an expression which is parsed back to the same value
+    fn check_roundtrip(exp: &str) {
+        let printed = eval_and_print(exp);
+        assert_eq!(printed, exp);
+        assert_eq!(eval_and_print(&printed), printed);
+    }
+
+    #[test]
+    fn print_left_associative() {
+        check_roundtrip("A + B + C");
+        check_roundtrip("A + (B + C)");
+    }
+
+    #[test]
+    fn print_right_associative() {
+        check_roundtrip("A <> B <> C");
+        check_roundtrip("(A <> B) <> C");
+    }
+
+    #[test]
+    fn print_non_associative() {
+        check_roundtrip("(A == B) == C");
+        check_roundtrip("A == (B == C)");
+    }
+
+    #[test]
+    fn print_precedence() {
+        check_roundtrip("A + B × C");
+        check_roundtrip("(A + B) × C");
+        check_roundtrip("A × B <> C + (A + B) <> C");
+    }
+}
@@ -100,6 +100,10 @@ pub enum TypeError {
     #[diagnostic(code("T-005"))]
     InvalidMatch {
         msg: String,
+        /// The constructors or destructors for which a case is missing, in the order of their declaration
+        missing: Vec<IdBound>,
+        /// The span of the whole (co)pattern match, including the cases
+        match_span: Option<SourceSpan>,
         #[label]
         span: Option<SourceSpan>,
     },
@@ -265,17 +269,32 @@ pub enum TypeError {
     },
 }
 
+/// This enum contains all warnings that can be emitted during elaboration.
+///
+/// Warnings do not prevent a module from being typechecked.
+#[derive(Error, Diagnostic, Debug, Clone, PartialEq, Eq)]
+pub enum TypeWarning {
+    #[error("Unsolved hole {meta_var}")]
+    #[diagnostic(code("T-W01"), severity(Warning))]
+    UnsolvedHole {
+        meta_var: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+}
+
 impl TypeError {
     pub fn invalid_match(
-        missing: HashSet<String>,
+        missing: Vec<IdBound>,
         undeclared: HashSet<String>,
         duplicate: HashSet<String>,
         info: &Option<Span>,
+        match_span: &Option<Span>,
     ) -> Box<Self> {
         let mut msgs = Vec::new();
 
         if !missing.is_empty() {
-            msgs.push(format!("missing {}", comma_separated(missing.iter().cloned())));
+            msgs.push(format!("missing {}", comma_separated(missing.iter().map(|i| i.id.clone()))));
         }
         if !undeclared.is_empty() {
             msgs.push(format!("undeclared {}", comma_separated(undeclared.iter().cloned())));
@@ -284,7 +303,13 @@ impl TypeError {
             msgs.push(format!("duplicate {}", comma_separated(duplicate.iter().cloned())));
         }
 
-        Self::InvalidMatch { msg: separated("; ", msgs), span: info.to_miette() }.into()
+        Self::InvalidMatch {
+            msg: separated("; ", msgs),
+            missing,
+            match_span: match_span.to_miette(),
+            span: info.to_miette(),
+        }
+        .into()
     }
 
     pub fn expected_typ_app(got: &Exp) -> Self {
@@ -21,7 +21,7 @@ impl CheckToplevel for Codata {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of codata type: {}", self.name);
 
-        let Codata { span, doc, name, attr, typ, dtors } = self;
+        let Codata { span, doc, comments, name, attr, typ, dtors } = self;
 
         let typ_out = typ.infer_telescope(ctx, |_, params_out| Ok(params_out))?;
 
@@ -31,6 +31,7 @@ impl CheckToplevel for Codata {
         Ok(Codata {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             typ: Box::new(typ_out),
@@ -43,7 +44,7 @@ impl CheckToplevel for Codata {
 fn check_dtor_wf(codata_name: &IdBind, dtor: &Dtor, ctx: &mut Ctx) -> TcResult<Dtor> {
     trace!("Checking well-formedness of destructor: {}", dtor.name);
 
-    let Dtor { span, doc, name, params, self_param, ret_typ } = dtor;
+    let Dtor { span, doc, comments, name, params, self_param, ret_typ_comments, ret_typ } = dtor;
 
     // Check that the destructor lies in the codata type it is defined in
     if &self_param.typ.name != codata_name {
@@ -64,9 +65,11 @@ fn check_dtor_wf(codata_name: &IdBind, dtor: &Dtor, ctx: &mut Ctx) -> TcResult<D
             Ok(Dtor {
                 span: *span,
                 doc: doc.clone(),
+                comments: comments.clone(),
                 name: name.clone(),
                 params: params_out,
                 self_param: self_param_out,
+                ret_typ_comments: ret_typ_comments.clone(),
                 ret_typ: ret_typ_out,
             })
         })
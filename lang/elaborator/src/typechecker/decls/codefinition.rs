@@ -23,7 +23,7 @@ impl CheckToplevel for Codef {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of codefinition: {}", self.name);
 
-        let Codef { span, doc, name, attr, params, typ, cases } = self;
+        let Codef { span, doc, comments, name, attr, params, typ_comments, typ, cases } = self;
 
         let label = IdBound { span: name.span, id: name.id.clone(), uri: ctx.module.uri.clone() };
 
@@ -31,6 +31,7 @@ impl CheckToplevel for Codef {
             let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
             let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
             let with_expected_type = WithExpectedType {
+                span: *span,
                 cases,
                 signature: Some((label, params)),
                 expected_type: typ_nf.expect_typ_app()?,
@@ -44,9 +45,11 @@ impl CheckToplevel for Codef {
             Ok(Codef {
                 span: *span,
                 doc: doc.clone(),
+                comments: comments.clone(),
                 name: name.clone(),
                 attr: attr.clone(),
                 params: params_out,
+                typ_comments: typ_comments.clone(),
                 typ: typ_out,
                 cases,
             })
@@ -21,7 +21,7 @@ impl CheckToplevel for Data {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of data type: {}", self.name);
 
-        let Data { span, doc, name, attr, typ, ctors } = self;
+        let Data { span, doc, comments, name, attr, typ, ctors } = self;
 
         let typ_out = typ.infer_telescope(ctx, |_, params_out| Ok(params_out))?;
 
@@ -31,6 +31,7 @@ impl CheckToplevel for Data {
         Ok(Data {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             typ: Box::new(typ_out),
@@ -43,7 +44,7 @@ impl CheckToplevel for Data {
 fn check_ctor_wf(data_type_name: &IdBind, ctor: &Ctor, ctx: &mut Ctx) -> TcResult<Ctor> {
     trace!("Checking well-formedness of constructor: {}", ctor.name);
 
-    let Ctor { span, doc, name, params, typ } = ctor;
+    let Ctor { span, doc, comments, name, params, typ_comments, typ } = ctor;
 
     // Check that the constructor lies in the data type it is defined in
     if &typ.name != data_type_name {
@@ -63,8 +64,10 @@ fn check_ctor_wf(data_type_name: &IdBind, ctor: &Ctor, ctx: &mut Ctx) -> TcResul
         Ok(Ctor {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             params: params_out,
+            typ_comments: typ_comments.clone(),
             typ: typ_out,
         })
     })
@@ -20,7 +20,19 @@ impl CheckToplevel for Def {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of definition: {}", self.name);
 
-        let Def { span, doc, name, attr, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span,
+            doc,
+            comments,
+            name,
+            attr,
+            params,
+            self_param,
+            ret_typ_comments,
+            ret_typ,
+            cases,
+            source_cases,
+        } = self;
 
         params.infer_telescope(ctx, |ctx, mut params_out| {
             let self_param_nf = self_param.typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
@@ -33,6 +45,7 @@ impl CheckToplevel for Def {
                 })?;
 
             let with_scrutinee_type = WithScrutineeType {
+                span: *span,
                 cases,
                 scrutinee_type: self_param_nf.expect_typ_app()?,
                 scrutinee_name: self_param.name.clone(),
@@ -45,12 +58,15 @@ impl CheckToplevel for Def {
             Ok(Def {
                 span: *span,
                 doc: doc.clone(),
+                comments: comments.clone(),
                 name: name.clone(),
                 attr: attr.clone(),
                 params: params_out,
                 self_param: self_param_out,
+                ret_typ_comments: ret_typ_comments.clone(),
                 ret_typ: ret_typ_out,
                 cases,
+                source_cases: source_cases.clone(),
             })
         })
     }
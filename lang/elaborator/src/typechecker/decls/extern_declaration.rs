@@ -16,7 +16,7 @@ impl CheckToplevel for Extern {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of extern declaration: {}", self.name);
 
-        let Extern { span, doc, name, attr, params, typ } = self;
+        let Extern { span, doc, comments, name, attr, params, typ_comments, typ } = self;
 
         params.infer_telescope(ctx, |ctx, mut params_out| {
             let typ_out = typ.check(ctx, &TypeUniv::new().into())?;
@@ -26,9 +26,11 @@ impl CheckToplevel for Extern {
             Ok(Extern {
                 span: *span,
                 doc: doc.clone(),
+                comments: comments.clone(),
                 name: name.clone(),
                 attr: attr.clone(),
                 params: params_out,
+                typ_comments: typ_comments.clone(),
                 typ: typ_out,
             })
         })
@@ -18,7 +18,8 @@ impl CheckToplevel for Let {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of global let: {}", self.name);
 
-        let Let { span, doc, name, attr, params, typ, body } = self;
+        let Let { span, doc, comments, name, attr, params, typ_comments, typ, body_comments, body } =
+            self;
 
         params.infer_telescope(ctx, |ctx, mut params_out| {
             let typ_out = typ.check(ctx, &TypeUniv::new().into())?;
@@ -30,10 +31,13 @@ impl CheckToplevel for Let {
             Ok(Let {
                 span: *span,
                 doc: doc.clone(),
+                comments: comments.clone(),
                 name: name.clone(),
                 attr: attr.clone(),
                 params: params_out,
+                typ_comments: typ_comments.clone(),
                 typ: typ_out,
+                body_comments: body_comments.clone(),
                 body: body_out,
             })
         })
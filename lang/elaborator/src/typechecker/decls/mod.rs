@@ -13,17 +13,18 @@ use polarity_lang_ast::*;
 use polarity_lang_miette_util::ToMiette;
 use polarity_lang_printer::Print;
 
-use crate::result::TcResult;
+use crate::result::{TcResult, TypeWarning};
 
 use super::{TypeError, ctx::Ctx, type_info_table::TypeInfoTable};
 
 /// Check a module
 ///
 /// The caller of this function needs to resolve module dependencies, check all dependencies, and provide a info table with all symbols from these dependencies.
+/// Alongside the typechecked module, the warnings about unsolved typed holes are returned.
 pub fn check_with_lookup_table(
     prg: Rc<Module>,
     info_table: &TypeInfoTable,
-) -> Result<Module, Vec<TypeError>> {
+) -> Result<(Module, Vec<TypeWarning>), Vec<TypeError>> {
     log::debug!("Checking module: {}", prg.uri);
 
     let mut ctx = Ctx::new(prg.meta_vars.clone(), info_table.clone(), prg.clone());
@@ -53,12 +54,32 @@ pub fn check_with_lookup_table(
         return Err(errs);
     }
 
-    Ok(Module {
+    let warnings = unsolved_holes(&ctx.meta_vars);
+    let module = Module {
         uri: prg.uri.clone(),
         use_decls: prg.use_decls.clone(),
         decls,
         meta_vars: ctx.meta_vars.clone(),
-    })
+    };
+    Ok((module, warnings))
+}
+
+/// Warn about the typed holes `?` which could not be solved during typechecking.
+fn unsolved_holes(meta_vars: &HashMap<MetaVar, MetaVarState>) -> Vec<TypeWarning> {
+    let mut unsolved: Vec<MetaVar> = meta_vars
+        .iter()
+        .filter(|(var, state)| var.kind == MetaVarKind::CanSolve && !state.is_solved())
+        .map(|(var, _)| *var)
+        .collect();
+    unsolved.sort_by_key(|var| var.id);
+
+    unsolved
+        .into_iter()
+        .map(|mv| TypeWarning::UnsolvedHole {
+            span: mv.span.to_miette(),
+            meta_var: mv.print_to_string(None),
+        })
+        .collect()
 }
 
 /// Check that there are no unresolved metavariables that remain after typechecking.
@@ -30,6 +30,7 @@ pub fn is_runtime_irrelevant(typ: &polarity_lang_ast::Exp) -> bool {
         polarity_lang_ast::Exp::Anno(anno) => is_runtime_irrelevant(&anno.exp),
         polarity_lang_ast::Exp::TypeUniv(_) => true,
         polarity_lang_ast::Exp::LocalMatch(_) => false,
+        polarity_lang_ast::Exp::CompiledMatch(e) => is_runtime_irrelevant(&e.compiled),
         polarity_lang_ast::Exp::LocalComatch(_) => false,
         polarity_lang_ast::Exp::Hole(hole) => {
             hole.solution.as_ref().map(|s| is_runtime_irrelevant(s)).unwrap_or(false)
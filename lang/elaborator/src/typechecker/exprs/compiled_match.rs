@@ -0,0 +1,24 @@
+//! Bidirectional type checker
+
+use polarity_lang_ast::*;
+
+use super::super::ctx::*;
+use super::CheckInfer;
+use crate::result::TcResult;
+
+// CompiledMatch
+//
+//
+
+/// Only the compiled expression is typechecked, the match as it is written is kept for printing.
+impl CheckInfer for CompiledMatch {
+    fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
+        let compiled = self.compiled.check(ctx, t)?;
+        Ok(CompiledMatch { compiled, ..self.clone() })
+    }
+
+    fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
+        let compiled = self.compiled.infer(ctx)?;
+        Ok(CompiledMatch { compiled, ..self.clone() })
+    }
+}
@@ -32,7 +32,7 @@ impl CheckInfer for DoBlock {
 impl CheckInfer for DoStatements {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
         match self {
-            DoStatements::Bind { span, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span, comments, name, bound, body, inferred_type: _ } => {
                 let bound = bound.infer(ctx)?;
                 let typ = bound.expect_typ()?;
                 let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
@@ -48,13 +48,14 @@ impl CheckInfer for DoStatements {
 
                 Ok(DoStatements::Bind {
                     span: *span,
+                    comments: comments.clone(),
                     name: name.clone(),
                     bound,
                     body,
                     inferred_type: Some(inferred_type),
                 })
             }
-            DoStatements::Let { span, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let { span, comments, name, typ, bound, body, inferred_type: _ } => {
                 let (typ, typ_nf, bound) = match typ {
                     Some(typ) => {
                         let typ = typ.check(ctx, &TypeUniv::new().into())?;
@@ -85,6 +86,7 @@ impl CheckInfer for DoStatements {
 
                 Ok(DoStatements::Let {
                     span: *span,
+                    comments: comments.clone(),
                     name: name.clone(),
                     typ,
                     bound,
@@ -92,19 +94,24 @@ impl CheckInfer for DoStatements {
                     inferred_type: Some(inferred_type),
                 })
             }
-            DoStatements::Return { span, exp, inferred_type: _ } => {
+            DoStatements::Return { span, comments, exp, inferred_type: _ } => {
                 let _ = t.expect_io_with_span(Some(*span))?;
                 let exp = exp.check(ctx, t)?;
                 let inferred_type = exp.expect_typ()?;
 
-                Ok(DoStatements::Return { span: *span, exp, inferred_type: Some(inferred_type) })
+                Ok(DoStatements::Return {
+                    span: *span,
+                    comments: comments.clone(),
+                    exp,
+                    inferred_type: Some(inferred_type),
+                })
             }
         }
     }
 
     fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
         match self {
-            DoStatements::Bind { span, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span, comments, name, bound, body, inferred_type: _ } => {
                 let bound = bound.infer(ctx)?;
                 let typ = bound.expect_typ()?;
                 let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
@@ -120,13 +127,14 @@ impl CheckInfer for DoStatements {
 
                 Ok(DoStatements::Bind {
                     span: *span,
+                    comments: comments.clone(),
                     name: name.clone(),
                     bound,
                     body,
                     inferred_type: Some(inferred_type),
                 })
             }
-            DoStatements::Let { span, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let { span, comments, name, typ, bound, body, inferred_type: _ } => {
                 let (typ, typ_nf, bound) = match typ {
                     Some(typ) => {
                         let typ = typ.check(ctx, &TypeUniv::new().into())?;
@@ -157,6 +165,7 @@ impl CheckInfer for DoStatements {
 
                 Ok(DoStatements::Let {
                     span: *span,
+                    comments: comments.clone(),
                     name: name.clone(),
                     typ,
                     bound,
@@ -164,12 +173,17 @@ impl CheckInfer for DoStatements {
                     inferred_type: Some(inferred_type),
                 })
             }
-            DoStatements::Return { span, exp, inferred_type: _ } => {
+            DoStatements::Return { span, comments, exp, inferred_type: _ } => {
                 let exp = exp.infer(ctx)?;
                 let inferred_type = exp.expect_typ()?;
                 let _ = inferred_type.expect_io_with_span(Some(*span))?;
 
-                Ok(DoStatements::Return { span: *span, exp, inferred_type: Some(inferred_type) })
+                Ok(DoStatements::Return {
+                    span: *span,
+                    comments: comments.clone(),
+                    exp,
+                    inferred_type: Some(inferred_type),
+                })
             }
         }
     }
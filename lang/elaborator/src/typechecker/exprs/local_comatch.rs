@@ -10,6 +10,7 @@ use crate::typechecker::type_info_table::DtorMeta;
 use polarity_lang_ast::ctx::LevelCtx;
 use polarity_lang_ast::*;
 use polarity_lang_miette_util::ToMiette;
+use polarity_lang_miette_util::codespan::Span;
 
 use super::super::ctx::*;
 use super::super::util::*;
@@ -33,8 +34,12 @@ impl CheckInfer for LocalComatch {
             .into());
         }
 
-        let with_expected_type =
-            WithExpectedType { cases, signature: None, expected_type: expected_type_app.clone() };
+        let with_expected_type = WithExpectedType {
+            span: *span,
+            cases,
+            signature: None,
+            expected_type: expected_type_app.clone(),
+        };
 
         with_expected_type.check_exhaustiveness(ctx)?;
         let cases = with_expected_type.check_type(ctx)?;
@@ -58,6 +63,8 @@ impl CheckInfer for LocalComatch {
 
 /// This struct is used to share code between the typechecking of local and global comatches.
 pub struct WithExpectedType<'a> {
+    /// The span of the comatch, i.e. of the local comatch or of the codefinition
+    pub span: Option<Span>,
     pub cases: &'a Vec<Case>,
     /// Signature of the global codefinition
     /// This is needed because a codefinition call will be substituted for the destructor's self parameters
@@ -79,6 +86,7 @@ impl WithExpectedType<'_> {
         // Check exhaustiveness
         let dtors_expected: HashSet<_> =
             codata.dtors.iter().map(|dtor| dtor.name.to_owned()).collect();
+        let type_uri = &self.expected_type.name.uri;
         let mut dtors_actual = HashSet::<IdBind>::default();
         let mut dtors_duplicate = HashSet::<IdBind>::default();
 
@@ -89,18 +97,24 @@ impl WithExpectedType<'_> {
             dtors_actual.insert(name.clone().into());
         }
 
-        let mut dtors_missing = dtors_expected.difference(&dtors_actual).peekable();
+        let dtors_missing: Vec<IdBound> = codata
+            .dtors
+            .iter()
+            .filter(|dtor| !dtors_actual.contains(&dtor.name))
+            .map(|dtor| IdBound { span: None, id: dtor.name.id.clone(), uri: type_uri.clone() })
+            .collect();
         let mut dtors_exessive = dtors_actual.difference(&dtors_expected).peekable();
 
-        if (dtors_missing.peek().is_some())
+        if !dtors_missing.is_empty()
             || dtors_exessive.peek().is_some()
             || !dtors_duplicate.is_empty()
         {
             return Err(TypeError::invalid_match(
-                dtors_missing.map(|i| &i.id).cloned().collect(),
+                dtors_missing,
                 dtors_exessive.map(|i| &i.id).cloned().collect(),
                 dtors_duplicate.into_iter().map(|i| i.id).collect(),
                 &self.expected_type.span(),
+                &self.span,
             ));
         }
         Ok(())
@@ -108,7 +122,7 @@ impl WithExpectedType<'_> {
 
     /// Type-check the comatch
     pub fn check_type(&self, ctx: &mut Ctx) -> TcResult<Vec<Case>> {
-        let WithExpectedType { cases, expected_type, signature: label } = &self;
+        let WithExpectedType { cases, expected_type, signature: label, .. } = &self;
         let TypCtor { args: on_args, .. } = expected_type;
 
         // We will compare `on_args` against `def_args`. But `def_args` are defined
@@ -126,8 +140,10 @@ impl WithExpectedType<'_> {
             let SelfParam { typ: TypCtor { args: def_args, .. }, .. } = self_param;
             let Case {
                 span,
+                comments,
                 pattern: Pattern { span: pattern_span, name, params: params_inst, .. },
                 body,
+                ..
             } = &case;
             // We are in the following situation:
             //
@@ -198,6 +214,7 @@ impl WithExpectedType<'_> {
 
                             let case_out = Case {
                                 span: *span,
+                                comments: comments.clone(),
                                 pattern: Pattern {
                                     span: *pattern_span,
                                     is_copattern: true,
@@ -205,6 +222,7 @@ impl WithExpectedType<'_> {
                                     params: args_out,
                                 },
                                 body: None,
+                                from_wildcard: false,
                             };
 
                             cases_out.push(case_out);
@@ -252,6 +270,7 @@ impl WithExpectedType<'_> {
                                                     erased: false,
                                                 })),
                                                 erased: false,
+                                                comments: Default::default(),
                                             }
                                         })
                                         .collect();
@@ -347,6 +366,7 @@ impl WithExpectedType<'_> {
 
                             let case_out = Case {
                                 span: *span,
+                                comments: comments.clone(),
                                 pattern: Pattern {
                                     span: *pattern_span,
                                     is_copattern: true,
@@ -354,6 +374,7 @@ impl WithExpectedType<'_> {
                                     params: args_out,
                                 },
                                 body: body_out,
+                                from_wildcard: false,
                             };
 
                             cases_out.push(case_out);
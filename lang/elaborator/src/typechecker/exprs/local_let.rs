@@ -16,7 +16,7 @@ use super::ExpectType;
 
 impl CheckInfer for LocalLet {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
-        let LocalLet { span, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span, comments, name, typ, bound, body, inferred_type: _ } = self;
 
         let (typ, typ_nf, bound) = match typ {
             Some(typ) => {
@@ -47,6 +47,7 @@ impl CheckInfer for LocalLet {
 
         Ok(LocalLet {
             span: *span,
+            comments: comments.clone(),
             name: name.clone(),
             typ,
             bound,
@@ -56,7 +57,7 @@ impl CheckInfer for LocalLet {
     }
 
     fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
-        let LocalLet { span, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span, comments, name, typ, bound, body, inferred_type: _ } = self;
 
         let (typ, typ_nf, bound) = match typ {
             Some(typ) => {
@@ -87,6 +88,7 @@ impl CheckInfer for LocalLet {
 
         Ok(LocalLet {
             span: *span,
+            comments: comments.clone(),
             name: name.clone(),
             typ,
             bound,
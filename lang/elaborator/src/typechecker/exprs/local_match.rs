@@ -6,6 +6,7 @@ use polarity_lang_ast::ctx::values::{Binder, Binding};
 use polarity_lang_ast::ctx::{BindContext, LevelCtx};
 use polarity_lang_ast::*;
 use polarity_lang_miette_util::ToMiette;
+use polarity_lang_miette_util::codespan::Span;
 
 use crate::conversion_checking::convert;
 use crate::index_unification::constraints::Constraint;
@@ -118,6 +119,7 @@ impl CheckInfer for LocalMatch {
         };
 
         let with_scrutinee_type = WithScrutineeType {
+            span: *span,
             cases,
             scrutinee_type: on_exp_typ.clone(),
             scrutinee_name: VarBind::Wildcard { span: None },
@@ -144,6 +146,8 @@ impl CheckInfer for LocalMatch {
 }
 
 pub struct WithScrutineeType<'a> {
+    /// The span of the match, i.e. of the local match or of the definition
+    pub span: Option<Span>,
     pub cases: &'a Vec<Case>,
     pub scrutinee_type: TypCtor,
     pub scrutinee_name: VarBind,
@@ -161,6 +165,7 @@ impl WithScrutineeType<'_> {
         // Check exhaustiveness
         let ctors_expected: HashSet<_> =
             data.ctors.iter().map(|ctor| ctor.name.to_owned()).collect();
+        let type_uri = &self.scrutinee_type.name.uri;
         let mut ctors_actual: HashSet<IdBind> = HashSet::default();
         let mut ctors_duplicate: HashSet<IdBind> = HashSet::default();
 
@@ -170,18 +175,24 @@ impl WithScrutineeType<'_> {
             }
             ctors_actual.insert(name.clone().into());
         }
-        let mut ctors_missing = ctors_expected.difference(&ctors_actual).peekable();
+        let ctors_missing: Vec<IdBound> = data
+            .ctors
+            .iter()
+            .filter(|ctor| !ctors_actual.contains(&ctor.name))
+            .map(|ctor| IdBound { span: None, id: ctor.name.id.clone(), uri: type_uri.clone() })
+            .collect();
         let mut ctors_undeclared = ctors_actual.difference(&ctors_expected).peekable();
 
-        if (ctors_missing.peek().is_some())
+        if !ctors_missing.is_empty()
             || ctors_undeclared.peek().is_some()
             || !ctors_duplicate.is_empty()
         {
             return Err(TypeError::invalid_match(
-                ctors_missing.map(|i| &i.id).cloned().collect(),
+                ctors_missing,
                 ctors_undeclared.map(|i| &i.id).cloned().collect(),
                 ctors_duplicate.into_iter().map(|i| i.id).collect(),
                 &self.scrutinee_type.span(),
+                &self.span,
             ));
         }
         Ok(())
@@ -199,8 +210,10 @@ impl WithScrutineeType<'_> {
 
             let Case {
                 span,
+                comments,
                 pattern: Pattern { name, params: args, span: pattern_span, .. },
                 body,
+                from_wildcard,
             } = case;
             let CtorMeta { typ: TypCtor { args: def_args, .. }, params, .. } =
                 ctx.type_info_table.lookup_ctor(&name)?;
@@ -278,6 +291,7 @@ impl WithScrutineeType<'_> {
                                 erased: false,
                             })),
                             erased: false,
+                            comments: Default::default(),
                         })
                         .collect();
                     let ctor = Call {
@@ -317,8 +331,25 @@ impl WithScrutineeType<'_> {
                             // The programmer wrote a non-absurd case. We therefore have to check
                             // that the unification succeeds.
                             let res = unify(ctx.levels(), constraint, &span)?;
-                            let unif = match res {
-                                crate::index_unification::dec::Dec::Yes(unif) => unif,
+                            match res {
+                                crate::index_unification::dec::Dec::Yes(unif) => ctx
+                                    .fork::<TcResult<_>, _>(|ctx| {
+                                        let type_info_table = ctx.type_info_table.clone();
+                                        let subst = unif.into();
+                                        ctx.subst(&type_info_table, &subst)?;
+                                        let body = body.subst(&mut ctx.levels(), &subst);
+
+                                        let t_subst = t.subst(&mut ctx.levels(), &subst);
+                                        let t_nf = t_subst
+                                            .normalize(&ctx.type_info_table, &mut ctx.env())?;
+
+                                        let body_out = body.check(ctx, &t_nf)?;
+
+                                        Ok(Some(body_out))
+                                    })?,
+                                // A case which was only generated for the wildcards of nested patterns
+                                // is absurd if its constructor cannot occur.
+                                crate::index_unification::dec::Dec::No if from_wildcard => None,
                                 crate::index_unification::dec::Dec::No => {
                                     // A right-hand side was provided in the clause, but unification fails.
                                     let err = TypeError::PatternIsAbsurd {
@@ -327,22 +358,7 @@ impl WithScrutineeType<'_> {
                                     };
                                     return Err(err.into());
                                 }
-                            };
-
-                            ctx.fork::<TcResult<_>, _>(|ctx| {
-                                let type_info_table = ctx.type_info_table.clone();
-                                let subst = unif.into();
-                                ctx.subst(&type_info_table, &subst)?;
-                                let body = body.subst(&mut ctx.levels(), &subst);
-
-                                let t_subst = t.subst(&mut ctx.levels(), &subst);
-                                let t_nf =
-                                    t_subst.normalize(&ctx.type_info_table, &mut ctx.env())?;
-
-                                let body_out = body.check(ctx, &t_nf)?;
-
-                                Ok(Some(body_out))
-                            })?
+                            }
                         }
                         None => {
                             // The programmer wrote an absurd case. We therefore have to check whether
@@ -362,6 +378,7 @@ impl WithScrutineeType<'_> {
                     };
                     let case_out = Case {
                         span,
+                        comments,
                         pattern: Pattern {
                             span: pattern_span,
                             is_copattern: false,
@@ -369,6 +386,7 @@ impl WithScrutineeType<'_> {
                             params: args_out,
                         },
                         body: body_out,
+                        from_wildcard,
                     };
                     cases_out.push(case_out);
                     Ok(())
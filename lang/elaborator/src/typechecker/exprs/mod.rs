@@ -1,5 +1,6 @@
 pub mod anno;
 pub mod call;
+pub mod compiled_match;
 pub mod do_block;
 pub mod dot_call;
 pub mod hole;
@@ -90,6 +91,7 @@ impl CheckInfer for Exp {
             Exp::TypeUniv(e) => Ok(e.check(ctx, t)?.into()),
             Exp::Hole(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalMatch(e) => Ok(e.check(ctx, t)?.into()),
+            Exp::CompiledMatch(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalComatch(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalLet(e) => Ok(e.check(ctx, t)?.into()),
             Exp::DoBlock(e) => Ok(e.check(ctx, t)?.into()),
@@ -107,6 +109,7 @@ impl CheckInfer for Exp {
             Exp::TypeUniv(e) => Ok(e.infer(ctx)?.into()),
             Exp::Hole(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalMatch(e) => Ok(e.infer(ctx)?.into()),
+            Exp::CompiledMatch(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalComatch(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalLet(e) => Ok(e.infer(ctx)?.into()),
             Exp::DoBlock(e) => Ok(e.infer(ctx)?.into()),
@@ -125,12 +128,17 @@ impl CheckInfer for Exp {
 impl CheckInfer for Arg {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
         match self {
-            Arg::UnnamedArg { arg, erased } => {
-                Ok(Arg::UnnamedArg { arg: arg.check(ctx, t)?, erased: *erased })
-            }
-            Arg::NamedArg { name, arg, erased } => {
-                Ok(Arg::NamedArg { name: name.clone(), arg: arg.check(ctx, t)?, erased: *erased })
-            }
+            Arg::UnnamedArg { arg, erased, comments } => Ok(Arg::UnnamedArg {
+                arg: arg.check(ctx, t)?,
+                erased: *erased,
+                comments: comments.clone(),
+            }),
+            Arg::NamedArg { name, arg, erased, comments } => Ok(Arg::NamedArg {
+                name: name.clone(),
+                arg: arg.check(ctx, t)?,
+                erased: *erased,
+                comments: comments.clone(),
+            }),
             Arg::InsertedImplicitArg { hole, erased } => {
                 Ok(Arg::InsertedImplicitArg { hole: hole.check(ctx, t)?, erased: *erased })
             }
@@ -139,12 +147,17 @@ impl CheckInfer for Arg {
 
     fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
         match self {
-            Arg::UnnamedArg { arg, erased } => {
-                Ok(Arg::UnnamedArg { arg: arg.infer(ctx)?, erased: *erased })
-            }
-            Arg::NamedArg { name, arg, erased } => {
-                Ok(Arg::NamedArg { name: name.clone(), arg: arg.infer(ctx)?, erased: *erased })
-            }
+            Arg::UnnamedArg { arg, erased, comments } => Ok(Arg::UnnamedArg {
+                arg: arg.infer(ctx)?,
+                erased: *erased,
+                comments: comments.clone(),
+            }),
+            Arg::NamedArg { name, arg, erased, comments } => Ok(Arg::NamedArg {
+                name: name.clone(),
+                arg: arg.infer(ctx)?,
+                erased: *erased,
+                comments: comments.clone(),
+            }),
             Arg::InsertedImplicitArg { hole, erased } => {
                 Ok(Arg::InsertedImplicitArg { hole: hole.infer(ctx)?, erased: *erased })
             }
@@ -272,7 +285,7 @@ impl InferTelescope for Telescope {
             params.iter(),
             vec![],
             |ctx, params_out, param| {
-                let Param { implicit, typ, name, erased } = param;
+                let Param { implicit, typ, name, erased, comments } = param;
                 let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
                 let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
                 let param_out = Param {
@@ -280,6 +293,7 @@ impl InferTelescope for Telescope {
                     name: name.clone(),
                     typ: typ_out,
                     erased: *erased,
+                    comments: comments.clone(),
                 };
                 params_out.push(param_out);
                 let binder =
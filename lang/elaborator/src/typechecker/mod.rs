@@ -5,5 +5,5 @@ pub mod exprs;
 pub mod type_info_table;
 pub mod util;
 
-pub use crate::result::TypeError;
+pub use crate::result::{TypeError, TypeWarning};
 pub use decls::check_with_lookup_table;
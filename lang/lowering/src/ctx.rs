@@ -1,6 +1,6 @@
 use polarity_lang_ast::ctx::values::Binder;
 use polarity_lang_ast::ctx::{BindContext, LevelCtx};
-use polarity_lang_ast::{self, MetaVar, MetaVarKind, MetaVarState};
+use polarity_lang_ast::{self, IdBound, MetaVar, MetaVarKind, MetaVarState};
 use polarity_lang_ast::{HashMap, HashSet};
 use polarity_lang_ast::{Idx, Lvl};
 use polarity_lang_miette_util::ToMiette;
@@ -8,10 +8,10 @@ use polarity_lang_miette_util::codespan::Span;
 use polarity_lang_parser::cst::ident::Ident;
 use url::Url;
 
-use crate::LoweringResult;
 use crate::symbol_table::SymbolTable;
+use crate::{DeclMeta, LoweringResult};
 
-use super::result::LoweringError;
+use super::result::{LoweringError, LoweringWarning};
 
 pub struct Ctx {
     /// Tracking local binder names
@@ -30,6 +30,18 @@ pub struct Ctx {
     pub meta_vars: HashMap<MetaVar, MetaVarState>,
     /// URI of the current module
     pub uri: Url,
+    /// The top-level declaration which is currently being lowered
+    pub current_decl: Option<String>,
+    /// Local variables bound by patterns and let bindings, checked for usage at the end
+    local_binders: Vec<(String, Span)>,
+    /// Binding sites of the local variables which are referenced
+    used_binders: HashSet<Span>,
+    /// Top-level names of the current module which are referenced outside of their own declaration
+    used_decls: HashSet<String>,
+    /// Modules with at least one referenced declaration
+    used_modules: HashSet<Url>,
+    /// Warnings which are reported while lowering, such as unreachable cases
+    warnings: Vec<LoweringWarning>,
 }
 
 impl Ctx {
@@ -42,9 +54,106 @@ impl Ctx {
             next_meta_var: 0,
             meta_vars: HashMap::default(),
             uri,
+            current_decl: None,
+            local_binders: Vec::new(),
+            used_binders: HashSet::default(),
+            used_decls: HashSet::default(),
+            used_modules: HashSet::default(),
+            warnings: Vec::new(),
         }
     }
 
+    /// Lookup a top-level name and record that it is used.
+    pub fn lookup(&mut self, name: &Ident) -> LoweringResult<(DeclMeta, IdBound)> {
+        let (meta, name) = self.symbol_table.lookup(name)?;
+        let meta = meta.clone();
+        self.use_module(&name.uri);
+        if name.uri == self.uri && self.current_decl.as_ref() != Some(&name.id) {
+            self.used_decls.insert(name.id.clone());
+        }
+        Ok((meta, name))
+    }
+
+    /// Record that a declaration of the given module is used.
+    pub fn use_module(&mut self, uri: &Url) {
+        if *uri != self.uri {
+            self.used_modules.insert(uri.clone());
+        }
+    }
+
+    /// Lookup a variable in the local variable context and record that it is used.
+    pub fn use_local(&mut self, name: &Ident) -> Option<Idx> {
+        let idx = self.lookup_local(name)?;
+        let lvl = self.binders.idx_to_lvl(idx);
+        if let polarity_lang_ast::VarBind::Var { span: Some(span), .. } =
+            &self.binders.bound[lvl.fst][lvl.snd].name
+        {
+            self.used_binders.insert(*span);
+        }
+        Some(idx)
+    }
+
+    /// Register a variable bound by a pattern or a let binding, so that it is reported if it is not used.
+    pub fn declare_local(&mut self, name: &polarity_lang_ast::VarBind) {
+        if let polarity_lang_ast::VarBind::Var { span: Some(span), id } = name {
+            self.local_binders.push((id.clone(), *span));
+        }
+    }
+
+    /// Report a warning which does not prevent the module from being lowered.
+    pub fn warn(&mut self, warning: LoweringWarning) {
+        self.warnings.push(warning);
+    }
+
+    /// Compute the warnings of the module once all declarations have been lowered.
+    ///
+    /// Besides the warnings reported during lowering, these are the warnings for the unused names of the module.
+    pub fn warnings(
+        &self,
+        use_decls: &[polarity_lang_ast::UseDecl],
+        decls: &[polarity_lang_ast::Decl],
+    ) -> Vec<LoweringWarning> {
+        let mut warnings = self.warnings.clone();
+
+        for polarity_lang_ast::UseDecl { span, path, .. } in use_decls {
+            // Imports are resolved relative to the current module, in the same way as by the driver.
+            let Ok(uri) = self.uri.join(path) else { continue };
+            if !self.used_modules.contains(&uri) {
+                warnings.push(LoweringWarning::UnusedImport {
+                    path: path.clone(),
+                    span: span.to_miette(),
+                });
+            }
+        }
+
+        for decl in decls {
+            let name = match decl {
+                polarity_lang_ast::Decl::Def(def) => &def.name,
+                polarity_lang_ast::Decl::Let(tl_let) if tl_let.name.id != "main" => &tl_let.name,
+                _ => continue,
+            };
+            if let Some(span) = name.span
+                && !self.used_decls.contains(&name.id)
+            {
+                warnings.push(LoweringWarning::UnusedDecl {
+                    name: name.id.clone(),
+                    span: span.to_miette(),
+                });
+            }
+        }
+
+        for (name, span) in &self.local_binders {
+            if !self.used_binders.contains(span) {
+                warnings.push(LoweringWarning::UnusedVariable {
+                    name: name.clone(),
+                    span: span.to_miette(),
+                });
+            }
+        }
+
+        warnings
+    }
+
     /// Lookup in the local variable context.
     pub fn lookup_local(&self, name: &Ident) -> Option<Idx> {
         for fst in (0..self.binders.len()).rev() {
@@ -101,6 +210,12 @@ impl Ctx {
         })
     }
 
+    /// Give a copy of a labelled expression a new id, keeping its user-annotated name
+    pub fn relabel(&mut self, label: &mut polarity_lang_ast::Label) {
+        label.id = self.next_label_id;
+        self.next_label_id += 1;
+    }
+
     /// Convert the given De-Bruijn level to a De-Bruijn index
     fn level_to_index(&self, lvl: Lvl) -> Idx {
         let fst = self.binders.len() - 1 - lvl.fst;
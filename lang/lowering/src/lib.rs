@@ -15,6 +15,7 @@ pub use ctx::*;
 pub use result::*;
 pub use symbol_table::DeclMeta;
 pub use symbol_table::ModuleSymbolTable;
+pub use symbol_table::OperatorMeta;
 pub use symbol_table::SymbolTable;
 pub use symbol_table::build::build_symbol_table;
 
@@ -33,19 +34,22 @@ fn expect_ident(q: QIdent) -> LoweringResult<Ident> {
 /// Lower a module
 ///
 /// The caller of this function needs to resolve module dependencies, lower all dependencies, and provide a symbol table with all symbols from these dependencies and the symbol table of the current module.
+/// Alongside the lowered module, the warnings about unreachable cases and unused imports, declarations and local variables are returned.
 pub fn lower_module_with_symbol_table(
     prg: &cst::decls::Module,
     symbol_table: &SymbolTable,
-) -> LoweringResult<polarity_lang_ast::Module> {
+) -> LoweringResult<(polarity_lang_ast::Module, Vec<LoweringWarning>)> {
     let mut ctx = Ctx::empty(prg.uri.clone(), symbol_table.clone());
 
     let use_decls = prg.use_decls.lower(&mut ctx)?;
     let decls = prg.decls.lower(&mut ctx)?;
+    let warnings = ctx.warnings(&use_decls, &decls);
 
-    Ok(polarity_lang_ast::Module {
+    let module = polarity_lang_ast::Module {
         uri: prg.uri.clone(),
         use_decls,
         decls,
         meta_vars: ctx.meta_vars,
-    })
+    };
+    Ok((module, warnings))
 }
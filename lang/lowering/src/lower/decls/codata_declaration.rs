@@ -13,7 +13,7 @@ impl Lower for cst::decls::Codata {
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         log::trace!("Lowering codata declaration: {}", self.name.id);
-        let cst::decls::Codata { span, doc, name, attr, params, dtors } = self;
+        let cst::decls::Codata { span, doc, comments, name, attr, params, dtors } = self;
 
         let dtors = dtors
             .iter()
@@ -23,6 +23,7 @@ impl Lower for cst::decls::Codata {
         Ok(polarity_lang_ast::Codata {
             span: Some(*span),
             doc: doc.lower(ctx)?,
+            comments: comments.clone(),
             name: IdBind { span: Some(name.span), id: name.id.clone() },
             attr: attr.lower(ctx)?,
             typ: Box::new(lower_telescope(params, ctx, |_, out| Ok(out))?),
@@ -38,7 +39,16 @@ fn lower_destructor(
     type_arity: usize,
 ) -> LoweringResult<polarity_lang_ast::Dtor> {
     log::trace!("Lowering destructor: {:?}", dtor.name);
-    let cst::decls::Dtor { span, doc, name, params, destructee, ret_typ } = dtor;
+    let cst::decls::Dtor {
+        span,
+        doc,
+        comments,
+        name,
+        params,
+        destructee,
+        ret_typ_comments,
+        ret_typ,
+    } = dtor;
 
     lower_telescope(params, ctx, |ctx, params| {
         // If the type constructor does not take any arguments, it can be left out
@@ -76,9 +86,11 @@ fn lower_destructor(
             Ok(polarity_lang_ast::Dtor {
                 span: Some(*span),
                 doc: doc.lower(ctx)?,
+                comments: comments.clone(),
                 name: IdBind { span: Some(name.span), id: name.id.clone() },
                 params,
                 self_param,
+                ret_typ_comments: ret_typ_comments.clone(),
                 ret_typ: ret_typ.lower(ctx)?,
             })
         })
@@ -11,7 +11,8 @@ impl Lower for cst::decls::Codef {
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         log::trace!("Lowering codefinition: {}", self.name.id);
 
-        let cst::decls::Codef { span, doc, name, attr, params, typ, cases, .. } = self;
+        let cst::decls::Codef { span, doc, comments, name, attr, params, typ_comments, typ, cases } =
+            self;
 
         lower_telescope(params, ctx, |ctx, params| {
             let typ = typ.lower(ctx)?;
@@ -21,9 +22,11 @@ impl Lower for cst::decls::Codef {
             Ok(polarity_lang_ast::Codef {
                 span: Some(*span),
                 doc: doc.lower(ctx)?,
+                comments: comments.clone(),
                 name: IdBind { span: Some(name.span), id: name.id.clone() },
                 attr: attr.lower(ctx)?,
                 params,
+                typ_comments: typ_comments.clone(),
                 typ: typ_ctor,
                 cases: cases.lower(ctx)?,
             })
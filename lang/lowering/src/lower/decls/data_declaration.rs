@@ -11,7 +11,7 @@ impl Lower for cst::decls::Data {
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         log::trace!("Lowering data declaration: {}", self.name.id);
-        let cst::decls::Data { span, doc, name, attr, params, ctors } = self;
+        let cst::decls::Data { span, doc, comments, name, attr, params, ctors } = self;
 
         let ctors = ctors
             .iter()
@@ -21,6 +21,7 @@ impl Lower for cst::decls::Data {
         Ok(polarity_lang_ast::Data {
             span: Some(*span),
             doc: doc.lower(ctx)?,
+            comments: comments.clone(),
             name: IdBind { span: Some(name.span), id: name.id.clone() },
             attr: attr.lower(ctx)?,
             typ: Box::new(lower_telescope(params, ctx, |_, out| Ok(out))?),
@@ -36,7 +37,7 @@ fn lower_constructor(
     type_arity: usize,
 ) -> LoweringResult<polarity_lang_ast::Ctor> {
     log::trace!("Lowering constructor: {:?}", ctor.name);
-    let cst::decls::Ctor { span, doc, name, params, typ } = ctor;
+    let cst::decls::Ctor { span, doc, comments, name, params, typ_comments, typ } = ctor;
 
     lower_telescope(params, ctx, |ctx, params| {
         // If the type constructor does not take any arguments, it can be left out
@@ -71,8 +72,10 @@ fn lower_constructor(
         Ok(polarity_lang_ast::Ctor {
             span: Some(*span),
             doc: doc.lower(ctx)?,
+            comments: comments.clone(),
             name: IdBind { span: Some(name.span), id: name.id.clone() },
             params,
+            typ_comments: typ_comments.clone(),
             typ,
         })
     })
@@ -1,6 +1,7 @@
 use polarity_lang_ast::IdBind;
 use polarity_lang_parser::cst::{self};
 
+use super::super::patterns::{compile_def_cases, is_flat};
 use super::super::*;
 use super::lower_self_param;
 use super::lower_telescope;
@@ -11,22 +12,42 @@ impl Lower for cst::decls::Def {
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         log::trace!("Lowering definition: {}", self.name.id);
 
-        let cst::decls::Def { span, doc, name, attr, params, scrutinee, ret_typ, cases } = self;
+        let cst::decls::Def {
+            span,
+            doc,
+            comments,
+            name,
+            attr,
+            params,
+            scrutinee,
+            ret_typ_comments,
+            ret_typ,
+            cases,
+        } = self;
 
         let self_param: cst::decls::SelfParam = scrutinee.clone().into();
 
         lower_telescope(params, ctx, |ctx, params| {
-            let cases = cases.lower(ctx)?;
+            let (cases, source_cases) = if is_flat(cases, &ctx.symbol_table) {
+                (cases.lower(ctx)?, None)
+            } else {
+                let (cases, source_cases) =
+                    compile_def_cases(*span, &scrutinee.typ.name, cases, ctx)?;
+                (cases, Some(source_cases))
+            };
             lower_self_param(&self_param, ctx, |ctx, self_param| {
                 Ok(polarity_lang_ast::Def {
                     span: Some(*span),
                     doc: doc.lower(ctx)?,
+                    comments: comments.clone(),
                     name: IdBind { span: Some(name.span), id: name.id.clone() },
                     attr: attr.lower(ctx)?,
                     params,
                     self_param,
+                    ret_typ_comments: ret_typ_comments.clone(),
                     ret_typ: ret_typ.lower(ctx)?,
                     cases,
+                    source_cases,
                 })
             })
         })
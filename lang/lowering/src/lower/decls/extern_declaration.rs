@@ -10,15 +10,18 @@ impl Lower for cst::decls::Extern {
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         log::trace!("Lowering extern declaration: {}", self.name.id);
 
-        let cst::decls::Extern { span, doc, name, attr, params, typ } = self;
+        let cst::decls::Extern { span, doc, comments, name, attr, params, typ_comments, typ } =
+            self;
 
         lower_telescope(params, ctx, |ctx, params| {
             Ok(polarity_lang_ast::Extern {
                 span: Some(*span),
                 doc: doc.lower(ctx)?,
+                comments: comments.clone(),
                 name: IdBind { span: Some(name.span), id: name.id.clone() },
                 attr: attr.lower(ctx)?,
                 params,
+                typ_comments: typ_comments.clone(),
                 typ: typ.lower(ctx)?,
             })
         })
@@ -1,7 +1,7 @@
 use polarity_lang_miette_util::ToMiette;
 use polarity_lang_parser::cst;
 
-use crate::expect_ident;
+use crate::{OperatorMeta, expect_ident};
 
 use super::super::*;
 
@@ -9,7 +9,8 @@ impl Lower for cst::decls::Infix {
     type Target = polarity_lang_ast::Infix;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::decls::Infix { span, doc, attr, pattern, rhs } = self;
+        let cst::decls::Infix { span, doc, comments, attr, assoc: _, precedence, pattern, rhs } =
+            self;
 
         let (operator, pattern_rhs) = match pattern.rhs.as_slice() {
             [x] => x,
@@ -55,14 +56,21 @@ impl Lower for cst::decls::Infix {
         }
 
         // Check that the name on the RHS is available at the location
-        // of the infix declaration.
+        // of the infix declaration. This also counts as a use of the name.
         let name = expect_ident(rhs.name.clone())?;
-        ctx.symbol_table.lookup(&name)?;
+        ctx.lookup(&name)?;
+
+        // The fixity has already been checked when computing the symbol table.
+        let (OperatorMeta { fixity, .. }, _) = ctx.symbol_table.lookup_operator(operator)?;
+        let fixity = *fixity;
 
         Ok(polarity_lang_ast::Infix {
             span: Some(*span),
             doc: doc.lower(ctx)?,
+            comments: comments.clone(),
             attr: attr.lower(ctx)?,
+            assoc: fixity.assoc,
+            precedence: precedence.as_ref().map(|_| fixity.precedence),
             lhs: operator.id.clone(),
             rhs: rhs.name.id.clone(),
         })
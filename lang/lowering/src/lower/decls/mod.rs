@@ -58,8 +58,12 @@ impl Lower for cst::decls::UseDecl {
     type Target = polarity_lang_ast::UseDecl;
 
     fn lower(&self, _ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::decls::UseDecl { span, path } = self;
-        Ok(polarity_lang_ast::UseDecl { span: *span, path: path.clone() })
+        let cst::decls::UseDecl { span, comments, path } = self;
+        Ok(polarity_lang_ast::UseDecl {
+            span: *span,
+            comments: comments.clone(),
+            path: path.clone(),
+        })
     }
 }
 
@@ -71,6 +75,12 @@ impl Lower for cst::decls::Decl {
     type Target = polarity_lang_ast::Decl;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
+        // Recursive calls of definitions and let bindings do not count as uses.
+        ctx.current_decl = match self {
+            cst::decls::Decl::Def(def) => Some(def.name.id.clone()),
+            cst::decls::Decl::Let(tl_let) => Some(tl_let.name.id.clone()),
+            _ => None,
+        };
         let decl = match self {
             cst::decls::Decl::Data(data) => data.lower(ctx)?.into(),
             cst::decls::Decl::Codata(codata) => codata.lower(ctx)?.into(),
@@ -125,8 +135,10 @@ fn desugar_telescope(tel: &cst::decls::Telescope) -> cst::decls::Telescope {
     cst::decls::Telescope(params)
 }
 fn desugar_param(param: &cst::decls::Param) -> Vec<cst::decls::Param> {
-    let cst::decls::Param { implicit, name, names, typ } = param;
+    let cst::decls::Param { span, comments, implicit, name, names, typ } = param;
     let mut params: Vec<cst::decls::Param> = vec![cst::decls::Param {
+        span: *span,
+        comments: comments.clone(),
         implicit: *implicit,
         name: name.clone(),
         names: vec![],
@@ -134,6 +146,8 @@ fn desugar_param(param: &cst::decls::Param) -> Vec<cst::decls::Param> {
     }];
     for extra_name in names {
         params.push(cst::decls::Param {
+            span: *span,
+            comments: Default::default(),
             implicit: *implicit,
             name: extra_name.clone(),
             names: vec![],
@@ -156,7 +170,7 @@ where
         tel.0.iter(),
         vec![],
         |ctx, params_out, param| -> LoweringResult<Binder<()>> {
-            let cst::decls::Param { implicit, name, names: _, typ } = param; // The `names` field has been removed by `desugar_telescope`.
+            let cst::decls::Param { span: _, comments, implicit, name, names: _, typ } = param; // The `names` field has been removed by `desugar_telescope`.
             let typ_out = typ.lower(ctx)?;
             let name = name.lower(ctx)?;
             let param_out = polarity_lang_ast::Param {
@@ -164,6 +178,7 @@ where
                 name: name.clone(),
                 typ: typ_out,
                 erased: false,
+                comments: comments.clone(),
             };
             params_out.push(param_out);
             Ok(Binder { name, content: () })
@@ -10,16 +10,30 @@ impl Lower for cst::decls::Let {
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         log::trace!("Lowering top-level let: {}", self.name.id);
 
-        let cst::decls::Let { span, doc, name, attr, params, typ, body } = self;
+        let cst::decls::Let {
+            span,
+            doc,
+            comments,
+            name,
+            attr,
+            params,
+            typ_comments,
+            typ,
+            body_comments,
+            body,
+        } = self;
 
         lower_telescope(params, ctx, |ctx, params| {
             Ok(polarity_lang_ast::Let {
                 span: Some(*span),
                 doc: doc.lower(ctx)?,
+                comments: comments.clone(),
                 name: IdBind { span: Some(name.span), id: name.id.clone() },
                 attr: attr.lower(ctx)?,
                 params,
+                typ_comments: typ_comments.clone(),
                 typ: typ.lower(ctx)?,
+                body_comments: body_comments.clone(),
                 body: body.lower(ctx)?,
             })
         })
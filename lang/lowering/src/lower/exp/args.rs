@@ -102,13 +102,14 @@ pub fn lower_args(
             );
         };
         match arg {
-            cst::exp::Arg::UnnamedArg(exp) => {
+            cst::exp::Arg::UnnamedArg(exp, comments) => {
                 args_out.push(polarity_lang_ast::Arg::UnnamedArg {
                     arg: exp.lower(ctx)?,
                     erased: false,
+                    comments: comments.clone(),
                 });
             }
-            cst::exp::Arg::NamedArg(name, exp) => {
+            cst::exp::Arg::NamedArg(name, exp, comments) => {
                 let expected_name = match &expected_bs {
                     BindingSite::Var { name, .. } => name,
                     BindingSite::Wildcard { span } => {
@@ -132,6 +133,7 @@ pub fn lower_args(
                     name,
                     arg: exp.lower(ctx)?,
                     erased: false,
+                    comments: comments.clone(),
                 });
             }
         }
@@ -143,7 +145,7 @@ pub fn lower_args(
         let names_iter = std::iter::once(&expected_param.name).chain(expected_param.names.iter());
         for expected_bs in names_iter {
             if expected_param.implicit {
-                if let Some(cst::exp::Arg::NamedArg(given_name, exp)) = given_iter.peek() {
+                if let Some(cst::exp::Arg::NamedArg(given_name, exp, _)) = given_iter.peek() {
                     let BindingSite::Var { name: expected_name, .. } = &expected_bs else {
                         return Err(LoweringError::NamedArgForWildcard {
                             given: given_name.clone(),
@@ -1,74 +1,144 @@
-use polarity_lang_ast::{Args, Call, CallKind, TypCtor};
+use polarity_lang_ast::{Args, Associativity, Call, CallKind, Fixity, InfixOp, TypCtor};
 use polarity_lang_miette_util::ToMiette;
-use polarity_lang_parser::cst::{self};
+use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_parser::cst::{self, ident::Operator};
 
 use crate::{Ctx, DeclMeta, LoweringError, LoweringResult, lower::Lower};
 
 impl Lower for cst::exp::BinOp {
     type Target = polarity_lang_ast::Exp;
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::exp::BinOp { span, lhs, rhs } = self;
-
-        // Note: Currently all binary operators are lowered as right-associative.
-        // The plan is to add associativity information to infix declarations so
-        // that we can lower accordingly.
-        match rhs.as_slice() {
-            [] => {
-                let lowered = lhs.lower(ctx)?;
-                Ok(*lowered)
-            }
-            [(operator, rhs), tail @ ..] => {
-                let (id, _url) = ctx.symbol_table.lookup_operator(operator)?;
-                let (meta, name) = ctx.symbol_table.lookup(id)?;
-                let meta = meta.clone();
-
-                let new_bin_op =
-                    cst::exp::BinOp { span: *span, lhs: Box::new(rhs.clone()), rhs: tail.to_vec() };
-                let rhs_lowered = new_bin_op.lower(ctx)?;
-                let args = Args {
-                    args: vec![
-                        polarity_lang_ast::Arg::UnnamedArg { arg: lhs.lower(ctx)?, erased: false },
-                        polarity_lang_ast::Arg::UnnamedArg {
-                            arg: Box::new(rhs_lowered),
-                            erased: false,
-                        },
-                    ],
-                };
-
-                if let DeclMeta::Data { .. } | DeclMeta::Codata { .. } = meta {
-                    return Ok(TypCtor {
-                        span: Some(*span),
-                        name,
-                        args,
-                        is_bin_op: Some(operator.id.clone()),
+        let cst::exp::BinOp { span: _, lhs, rhs } = self;
+
+        let fixities = rhs
+            .iter()
+            .map(|(operator, _)| Ok(ctx.symbol_table.lookup_operator(operator)?.0.fixity))
+            .collect::<LoweringResult<Vec<_>>>()?;
+        let mut chain = Chain { rhs, fixities, next: 0 };
+        let tree = chain.climb(OpTree::Operand(lhs), 0)?;
+        tree.lower(ctx)
+    }
+}
+
+/// A chain of binary operator applications `e0 op1 e1 ... opn en`
+///
+/// The chain is resolved by precedence climbing: operators with a higher precedence level bind more tightly,
+/// and operators of the same precedence level associate to the left or to the right if they are all
+/// declared with `infixl` or all declared with `infixr`. All other chains are ambiguous.
+struct Chain<'a> {
+    rhs: &'a [(Operator, cst::exp::Exp)],
+    /// The fixities of the operators in `rhs`
+    fixities: Vec<Fixity>,
+    /// The position of the next operator in `rhs`
+    next: usize,
+}
+
+impl<'a> Chain<'a> {
+    fn peek(&self) -> Option<Fixity> {
+        self.fixities.get(self.next).copied()
+    }
+
+    /// Consume all operators with at least the given precedence level, starting from the operand `lhs`
+    fn climb(&mut self, mut lhs: OpTree<'a>, min_precedence: u32) -> LoweringResult<OpTree<'a>> {
+        while let Some(fixity) = self.peek()
+            && fixity.precedence >= min_precedence
+        {
+            let (operator, operand) = &self.rhs[self.next];
+            self.next += 1;
+
+            let mut rhs = OpTree::Operand(operand);
+            while let Some(next) = self.peek() {
+                if next.precedence > fixity.precedence {
+                    rhs = self.climb(rhs, fixity.precedence + 1)?;
+                } else if next.precedence < fixity.precedence {
+                    break;
+                } else if fixity.assoc == Associativity::Right && next.assoc == Associativity::Right
+                {
+                    rhs = self.climb(rhs, fixity.precedence)?;
+                } else if fixity.assoc == Associativity::Left && next.assoc == Associativity::Left {
+                    break;
+                } else {
+                    let (next_operator, _) = &self.rhs[self.next];
+                    return Err(LoweringError::AmbiguousOperators {
+                        lhs: operator.id.clone(),
+                        rhs: next_operator.id.clone(),
+                        precedence: fixity.precedence,
+                        span: next_operator.span.to_miette(),
                     }
                     .into());
-                };
-
-                let kind = match meta {
-                    DeclMeta::Ctor { .. } => CallKind::Constructor,
-                    DeclMeta::Codef { .. } => CallKind::Codefinition,
-                    DeclMeta::Let { .. } => CallKind::LetBound,
-                    DeclMeta::Extern { .. } => CallKind::Extern,
-                    _ => {
-                        return Err(LoweringError::Impossible {
-                            message: "Unexpected declaration kind in infix lowering".to_owned(),
-                            span: Some(span.to_miette()),
-                        }
-                        .into());
-                    }
-                };
-
-                Ok(Call {
-                    span: Some(*span),
-                    kind,
-                    name,
-                    args,
-                    is_bin_op: Some(operator.id.clone()),
-                    inferred_type: None,
                 }
-                .into())
             }
+
+            lhs = OpTree::App { operator, fixity, lhs: Box::new(lhs), rhs: Box::new(rhs) };
         }
+        Ok(lhs)
+    }
+}
+
+/// A chain of binary operator applications after it has been resolved
+enum OpTree<'a> {
+    Operand(&'a cst::exp::Exp),
+    App { operator: &'a Operator, fixity: Fixity, lhs: Box<OpTree<'a>>, rhs: Box<OpTree<'a>> },
+}
+
+impl OpTree<'_> {
+    fn span(&self) -> Span {
+        match self {
+            OpTree::Operand(exp) => exp.span(),
+            OpTree::App { lhs, rhs, .. } => Span { start: lhs.span().start, end: rhs.span().end },
+        }
+    }
+}
+
+impl Lower for OpTree<'_> {
+    type Target = polarity_lang_ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
+        let (operator, fixity, lhs, rhs) = match self {
+            OpTree::Operand(exp) => return exp.lower(ctx),
+            OpTree::App { operator, fixity, lhs, rhs } => (operator, fixity, lhs, rhs),
+        };
+        let span = self.span();
+
+        let (operator_meta, uri) = ctx.symbol_table.lookup_operator(operator)?;
+        let (id, uri) = (operator_meta.name.clone(), uri.clone());
+        ctx.use_module(&uri);
+        let (meta, name) = ctx.lookup(&id)?;
+
+        let args = Args {
+            args: vec![
+                polarity_lang_ast::Arg::UnnamedArg {
+                    arg: lhs.lower(ctx)?,
+                    erased: false,
+                    comments: Default::default(),
+                },
+                polarity_lang_ast::Arg::UnnamedArg {
+                    arg: rhs.lower(ctx)?,
+                    erased: false,
+                    comments: Default::default(),
+                },
+            ],
+        };
+        let is_bin_op = Some(InfixOp { id: operator.id.clone(), fixity: *fixity });
+
+        if let DeclMeta::Data { .. } | DeclMeta::Codata { .. } = meta {
+            return Ok(TypCtor { span: Some(span), name, args, is_bin_op }.into());
+        };
+
+        let kind = match meta {
+            DeclMeta::Ctor { .. } => CallKind::Constructor,
+            DeclMeta::Codef { .. } => CallKind::Codefinition,
+            DeclMeta::Let { .. } => CallKind::LetBound,
+            DeclMeta::Extern { .. } => CallKind::Extern,
+            _ => {
+                return Err(LoweringError::Impossible {
+                    message: "Unexpected declaration kind in infix lowering".to_owned(),
+                    span: Some(span.to_miette()),
+                }
+                .into());
+            }
+        };
+
+        Ok(Call { span: Some(span), kind, name, args, is_bin_op, inferred_type: None }.into())
     }
 }
@@ -24,7 +24,7 @@ impl Lower for cst::exp::Call {
         // If we find the identifier in the local context then we have to lower
         // it to a variable.
         let name = expect_ident(name.clone())?;
-        if let Some(idx) = ctx.lookup_local(&name) {
+        if let Some(idx) = ctx.use_local(&name) {
             let name = VarBound { span: Some(name.span), id: name.id.clone() };
             return Ok(polarity_lang_ast::Exp::Variable(Variable {
                 span: Some(*span),
@@ -37,7 +37,7 @@ impl Lower for cst::exp::Call {
 
         // If we find the identifier in the global context then we have to lower
         // it to a call or a type constructor.
-        let (meta, name) = ctx.symbol_table.lookup(&name)?;
+        let (meta, name) = ctx.lookup(&name)?;
         match meta {
             DeclMeta::Data { params, .. } | DeclMeta::Codata { params, .. } => {
                 Ok(polarity_lang_ast::Exp::TypCtor(polarity_lang_ast::TypCtor {
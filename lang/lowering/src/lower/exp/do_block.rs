@@ -2,6 +2,7 @@ use polarity_lang_ast as ast;
 use polarity_lang_ast::ctx::BindContext;
 use polarity_lang_miette_util::ToMiette;
 use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_miette_util::trivia::Comments;
 use polarity_lang_parser::cst;
 
 use crate::Ctx;
@@ -22,7 +23,9 @@ impl Lower for cst::exp::DoBlock {
         };
 
         // The last element must be the final return expression.
-        let cst::exp::DoStatement::Exp { span: ret_span, exp: ret_exp } = last else {
+        let cst::exp::DoStatement::Exp { span: ret_span, comments: ret_comments, exp: ret_exp } =
+            last
+        else {
             // The parser's grammar ensures that the final statement is an expression.
             return Err(Box::new(LoweringError::Impossible {
                 message: "Final statement in do block is not an expression.".to_string(),
@@ -30,7 +33,7 @@ impl Lower for cst::exp::DoBlock {
             }));
         };
 
-        let statements = lower_do_statements(statements, ret_exp, ret_span, ctx)?;
+        let statements = lower_do_statements(statements, ret_exp, ret_span, ret_comments, ctx)?;
 
         let block = ast::DoBlock { span: *span, statements, inferred_type: None };
         Ok(ast::Exp::DoBlock(block))
@@ -41,26 +44,35 @@ fn lower_do_statements(
     statements: &[cst::exp::DoStatement],
     return_exp: &cst::exp::Exp,
     return_exp_span: &Span,
+    return_exp_comments: &Comments,
     ctx: &mut Ctx,
 ) -> LoweringResult<ast::DoStatements> {
     let Some((head, tail)) = statements.split_first() else {
         let return_exp = return_exp.lower(ctx)?;
         return Ok(ast::DoStatements::Return {
             span: *return_exp_span,
+            comments: return_exp_comments.clone(),
             exp: Box::new(return_exp),
             inferred_type: None,
         });
     };
 
     match head {
-        cst::exp::DoStatement::Exp { span, exp } => {
+        cst::exp::DoStatement::Exp { span, comments, exp } => {
             let name = ast::VarBind::Wildcard { span: None };
             let bound = exp.lower(ctx)?;
 
             ctx.bind_single(name.clone(), |ctx| {
-                let body = lower_do_statements(tail, return_exp, return_exp_span, ctx)?;
+                let body = lower_do_statements(
+                    tail,
+                    return_exp,
+                    return_exp_span,
+                    return_exp_comments,
+                    ctx,
+                )?;
                 Ok(ast::DoStatements::Bind {
                     span: *span,
+                    comments: comments.clone(),
                     name,
                     bound,
                     body: Box::new(body),
@@ -68,14 +80,22 @@ fn lower_do_statements(
                 })
             })
         }
-        cst::exp::DoStatement::Bind { span, name, bound } => {
+        cst::exp::DoStatement::Bind { span, comments, name, bound } => {
             let name = name.lower(ctx)?;
             let bound = bound.lower(ctx)?;
+            ctx.declare_local(&name);
 
             ctx.bind_single(name.clone(), |ctx| {
-                let body = lower_do_statements(tail, return_exp, return_exp_span, ctx)?;
+                let body = lower_do_statements(
+                    tail,
+                    return_exp,
+                    return_exp_span,
+                    return_exp_comments,
+                    ctx,
+                )?;
                 Ok(ast::DoStatements::Bind {
                     span: *span,
+                    comments: comments.clone(),
                     name,
                     bound,
                     body: Box::new(body),
@@ -83,15 +103,23 @@ fn lower_do_statements(
                 })
             })
         }
-        cst::exp::DoStatement::Let { span, name, typ, bound } => {
+        cst::exp::DoStatement::Let { span, comments, name, typ, bound } => {
             let name = name.lower(ctx)?;
             let typ = typ.lower(ctx)?;
             let bound = bound.lower(ctx)?;
+            ctx.declare_local(&name);
 
             ctx.bind_single(name.clone(), |ctx| {
-                let body = lower_do_statements(tail, return_exp, return_exp_span, ctx)?;
+                let body = lower_do_statements(
+                    tail,
+                    return_exp,
+                    return_exp_span,
+                    return_exp_comments,
+                    ctx,
+                )?;
                 Ok(ast::DoStatements::Let {
                     span: *span,
+                    comments: comments.clone(),
                     name,
                     typ,
                     bound,
@@ -12,9 +12,9 @@ impl Lower for cst::exp::DotCall {
         let cst::exp::DotCall { span, exp, name, args } = self;
 
         let name = expect_ident(name.clone())?;
-        let (meta, name) = ctx.symbol_table.lookup(&name)?;
+        let (meta, name) = ctx.lookup(&name)?;
 
-        match meta.clone() {
+        match meta {
             DeclMeta::Dtor { params, .. } => {
                 Ok(polarity_lang_ast::Exp::DotCall(polarity_lang_ast::DotCall {
                     span: Some(*span),
@@ -23,7 +23,7 @@ impl Lower for cst::exp::Literal {
         };
 
         // Lookup what type is in scope
-        let (type_decl, type_name) = ctx.symbol_table.lookup(&type_ident)?;
+        let (type_decl, type_name) = ctx.lookup(&type_ident)?;
 
         // Lower to AST variant
         let ast_literal = match kind {
@@ -40,7 +40,7 @@ impl Lower for cst::exp::Literal {
         };
 
         match type_decl {
-            DeclMeta::Extern { params } if params.is_empty() => {
+            DeclMeta::Extern { params, .. } if params.is_empty() => {
                 Ok(polarity_lang_ast::Exp::Literal(polarity_lang_ast::Literal {
                     span: Some(*span),
                     kind: ast_literal,
@@ -30,13 +30,14 @@ impl Lower for cst::exp::Case<cst::exp::Copattern> {
     type Target = polarity_lang_ast::Case;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::exp::Case { span, pattern, body } = self;
+        let cst::exp::Case { span, comments, pattern, body } = self;
 
         lower_telescope_inst(&pattern.params, ctx, |ctx, params| {
             let name = expect_ident(pattern.name.clone())?;
-            let (_, name) = ctx.symbol_table.lookup(&name)?;
+            let (_, name) = ctx.lookup(&name)?;
             Ok(polarity_lang_ast::Case {
                 span: Some(*span),
+                comments: comments.clone(),
                 pattern: polarity_lang_ast::Pattern {
                     span: Some(pattern.span),
                     is_copattern: true,
@@ -44,6 +45,7 @@ impl Lower for cst::exp::Case<cst::exp::Copattern> {
                     params,
                 },
                 body: body.lower(ctx)?,
+                from_wildcard: false,
             })
         })
     }
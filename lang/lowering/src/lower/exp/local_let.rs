@@ -7,16 +7,18 @@ impl Lower for cst::exp::LocalLet {
     type Target = polarity_lang_ast::Exp;
 
     fn lower(&self, ctx: &mut crate::Ctx) -> crate::LoweringResult<Self::Target> {
-        let cst::exp::LocalLet { span, name, typ, bound, body } = self;
+        let cst::exp::LocalLet { span, comments, name, typ, bound, body } = self;
 
         let name = name.lower(ctx)?;
         let typ = typ.lower(ctx)?;
         let bound = bound.lower(ctx)?;
+        ctx.declare_local(&name);
 
         ctx.bind_single(name.clone(), |ctx| {
             let body = body.lower(ctx)?;
             Ok(polarity_lang_ast::LocalLet {
                 span: *span,
+                comments: comments.clone(),
                 name,
                 typ,
                 bound,
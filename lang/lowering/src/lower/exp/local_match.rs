@@ -3,6 +3,7 @@ use polarity_lang_parser::cst;
 
 use crate::{Ctx, LoweringResult, expect_ident, lower::Lower};
 
+use super::super::patterns::{compile_local_match, flat_pattern, is_flat};
 use super::lower_telescope_inst;
 
 impl Lower for cst::exp::LocalMatch {
@@ -10,6 +11,9 @@ impl Lower for cst::exp::LocalMatch {
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         let cst::exp::LocalMatch { span, name, on_exp, motive, cases } = self;
+        if !is_flat(cases, &ctx.symbol_table) {
+            return compile_local_match(self, ctx);
+        }
         let cases = cases.lower(ctx)?;
         let fvs = cases.free_vars(&ctx.binders);
         let closure = polarity_lang_ast::Closure::identity(&ctx.binders, &fvs);
@@ -32,20 +36,23 @@ impl Lower for cst::exp::Case<cst::exp::Pattern> {
     type Target = polarity_lang_ast::Case;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::exp::Case { span, pattern, body } = self;
+        let cst::exp::Case { span, comments, pattern, body } = self;
+        let (name, params) = flat_pattern(pattern)?;
 
-        lower_telescope_inst(&pattern.params, ctx, |ctx, params| {
-            let name = expect_ident(pattern.name.clone())?;
-            let (_, name) = ctx.symbol_table.lookup(&name)?;
+        lower_telescope_inst(&params, ctx, |ctx, params| {
+            let name = expect_ident(name.clone())?;
+            let (_, name) = ctx.lookup(&name)?;
             Ok(polarity_lang_ast::Case {
                 span: Some(*span),
+                comments: comments.clone(),
                 pattern: polarity_lang_ast::Pattern {
-                    span: Some(pattern.span),
+                    span: Some(pattern.span()),
                     is_copattern: false,
                     name,
                     params,
                 },
                 body: body.lower(ctx)?,
+                from_wildcard: false,
             })
         })
     }
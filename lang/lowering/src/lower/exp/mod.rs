@@ -21,6 +21,7 @@ mod literal;
 mod local_comatch;
 mod local_let;
 mod local_match;
+mod multi_match;
 mod parens;
 
 impl Lower for cst::exp::Exp {
@@ -32,6 +33,7 @@ impl Lower for cst::exp::Exp {
             cst::exp::Exp::DotCall(e) => e.lower(ctx),
             cst::exp::Exp::Anno(e) => e.lower(ctx),
             cst::exp::Exp::LocalMatch(e) => e.lower(ctx),
+            cst::exp::Exp::MultiMatch(e) => e.lower(ctx),
             cst::exp::Exp::LocalComatch(e) => e.lower(ctx),
             cst::exp::Exp::Hole(e) => e.lower(ctx),
             cst::exp::Exp::Literal(e) => e.lower(ctx),
@@ -59,6 +61,9 @@ fn lower_telescope_inst<
     f: F,
 ) -> LoweringResult<T> {
     let tel_inst = tel_inst.iter().map(|bs| bs.lower(ctx)).collect::<Result<Vec<_>, _>>()?;
+    for name in tel_inst.iter() {
+        ctx.declare_local(name);
+    }
     ctx.bind_fold_failable(
         tel_inst.into_iter(),
         vec![],
@@ -0,0 +1,13 @@
+use polarity_lang_parser::cst;
+
+use crate::{Ctx, LoweringResult, lower::Lower};
+
+use super::super::patterns::compile_multi_match;
+
+impl Lower for cst::exp::MultiMatch {
+    type Target = polarity_lang_ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
+        compile_multi_match(self, ctx)
+    }
+}
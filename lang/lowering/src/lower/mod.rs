@@ -2,6 +2,7 @@ use super::ctx::*;
 use super::result::*;
 mod decls;
 mod exp;
+mod patterns;
 
 pub trait Lower {
     type Target;
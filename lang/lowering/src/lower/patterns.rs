@@ -0,0 +1,1121 @@
+//! Compilation of nested patterns
+//!
+//! The cases of definitions and local matches may use nested constructor patterns, literal patterns
+//! and wildcards, e.g. `Cons(x, Cons(_, Nil)) => ...` or `0 => ...`. The typechecker only knows
+//! cases whose pattern is a single constructor applied to variables, so matches with other patterns
+//! are compiled into a tree of such single-level matches. Matches which only consist of
+//! single-level patterns are lowered as they are.
+//!
+//! The compilation specializes a matrix of patterns with one row per case, one column at a time.
+//! A row is selected as soon as all of its remaining patterns are irrefutable, and the variables
+//! bound by its patterns are introduced by let bindings in front of its body.
+//!
+//! A case can be selected on several paths of the tree, e.g. if it starts with a wildcard. Its body
+//! is lowered only once, in the context of the match extended by its variables, and every further
+//! path receives a copy of it whose indices account for the cases generated on that path.
+//! Values which are not matched by any case are reported as errors, cases which cannot be reached as warnings.
+//!
+//! The patterns are compiled before typechecking, so a wildcard is expanded into every constructor of
+//! its data type. The cases which are generated for constructors that no pattern mentions are marked,
+//! and the typechecker treats them as absurd if their constructor cannot occur at the given indices.
+//!
+//! The cases as they are written are kept alongside the compiled ones, so that the printer can
+//! reproduce the match without exposing its compilation.
+
+use polarity_lang_ast::ctx::values::Binder;
+use polarity_lang_ast::ctx::{BindContext, LevelCtx};
+use polarity_lang_ast::{self, FreeVars, HasSpan, IdBind, IdBound, Lvl, Shift, VarBind};
+use polarity_lang_miette_util::ToMiette;
+use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_parser::cst::exp::{BindingSite, Case, LiteralKind, MultiPattern, Pattern};
+use polarity_lang_parser::cst::ident::{Ident, QIdent};
+use polarity_lang_parser::cst::{self};
+
+use super::Lower;
+use crate::symbol_table::SymbolTable;
+use crate::{Ctx, DeclMeta, LoweringError, LoweringResult, LoweringWarning, expect_ident};
+
+/// Whether all cases can be lowered without compiling their patterns
+///
+/// This is the case if every pattern is a constructor applied to variables and wildcards.
+pub(super) fn is_flat(cases: &[Case<Pattern>], symbol_table: &SymbolTable) -> bool {
+    cases.iter().all(|case| match &case.pattern {
+        Pattern::Ctor { params, .. } => params.iter().all(|param| match param {
+            Pattern::Wildcard { .. } => true,
+            Pattern::Ctor { name, params, .. } => {
+                params.is_empty() && is_var_name(name, symbol_table)
+            }
+            Pattern::Literal(_) => false,
+        }),
+        Pattern::Literal(_) | Pattern::Wildcard { .. } => false,
+    })
+}
+
+/// The constructor name and the binding sites of a pattern for which `is_flat` holds
+pub(super) fn flat_pattern(pattern: &Pattern) -> LoweringResult<(&QIdent, Vec<BindingSite>)> {
+    let Pattern::Ctor { name, params, .. } = pattern else {
+        return Err(LoweringError::Impossible {
+            message: "Expected a constructor pattern".to_owned(),
+            span: Some(pattern.span().to_miette()),
+        }
+        .into());
+    };
+    let params = params
+        .iter()
+        .map(|param| match param {
+            Pattern::Ctor { span, name, .. } => {
+                BindingSite::Var { span: *span, name: unqualified(name) }
+            }
+            _ => BindingSite::Wildcard { span: param.span() },
+        })
+        .collect();
+    Ok((name, params))
+}
+
+/// Compile the cases of a definition on the data type `typ`
+///
+/// Alongside the compiled cases, the cases as they are written in the source are returned.
+pub(super) fn compile_def_cases(
+    span: Span,
+    typ: &QIdent,
+    cases: &[Case<Pattern>],
+    ctx: &mut Ctx,
+) -> LoweringResult<(Vec<polarity_lang_ast::Case>, Vec<polarity_lang_ast::SourceCase>)> {
+    let typ = expect_ident(typ.clone())?;
+    let mut compiler =
+        Compiler::new(span, cases.iter().map(Clause::from).collect(), vec![Occ::Root]);
+    let rows = compiler.rows(ctx)?;
+    let pats = rows.iter().map(|row| row.pats.clone()).collect();
+    compiler.outer = ctx.binders.clone();
+    let cases = compiler.split_ctor(ctx, &[Occ::Root], rows, 0, &typ)?;
+    compiler.check_reachable(ctx);
+    let source_cases = compiler.source_cases(ctx, pats)?;
+    Ok((cases, source_cases))
+}
+
+/// Compile a local match whose cases are not flat
+pub(super) fn compile_local_match(
+    local_match: &cst::exp::LocalMatch,
+    ctx: &mut Ctx,
+) -> LoweringResult<polarity_lang_ast::Exp> {
+    let cst::exp::LocalMatch { span, name, on_exp, motive, cases } = local_match;
+
+    let clauses = cases.iter().map(Clause::from).collect();
+    let mut compiler = Compiler::new(*span, clauses, vec![Occ::Root]);
+    let rows = compiler.rows(ctx)?;
+    let pats = rows.iter().map(|row| row.pats.clone()).collect();
+    let label = name.as_ref().map(|name| IdBind { span: Some(name.span), id: name.id.clone() });
+
+    // If the first case matches a constructor, the scrutinee is matched on directly.
+    if let Some(Pat::Ctor { ctor, .. }) = rows.first().and_then(|row| row.pats.first()) {
+        let typ = ctor.typ.clone();
+        compiler.outer = ctx.binders.clone();
+        let cases = compiler.split_ctor(ctx, &[Occ::Root], rows, 0, &typ)?;
+        compiler.check_reachable(ctx);
+        let fvs = cases.free_vars(&ctx.binders);
+        let closure = polarity_lang_ast::Closure::identity(&ctx.binders, &fvs);
+        let on_exp = on_exp.lower(ctx)?;
+        let motive = motive.lower(ctx)?;
+        let compiled = polarity_lang_ast::LocalMatch {
+            span: Some(*span),
+            ctx: None,
+            name: ctx.unique_label(name.to_owned(), span)?,
+            closure,
+            on_exp: on_exp.clone(),
+            motive: motive.clone(),
+            ret_typ: None,
+            cases,
+            inferred_type: None,
+        };
+        return Ok(polarity_lang_ast::CompiledMatch {
+            span: Some(*span),
+            on_exps: vec![*on_exp],
+            is_multi: false,
+            label,
+            motive,
+            cases: compiler.source_cases(ctx, pats)?,
+            compiled: Box::new(compiled.into()),
+        }
+        .into());
+    }
+
+    // Otherwise, the scrutinee is compared against literals or bound by the variable patterns.
+    if let Some(motive) = motive {
+        return Err(LoweringError::InvalidPattern {
+            span: motive.span.to_miette(),
+            message: "A motive can only be given if the first case matches a constructor"
+                .to_owned(),
+        }
+        .into());
+    }
+    compiler.roots.clear();
+    let on_exps = vec![*on_exp.lower(ctx)?];
+    let compiled = compiler.compile_scrutinees(ctx, &on_exps, rows)?;
+    compiler.check_reachable(ctx);
+    Ok(polarity_lang_ast::CompiledMatch {
+        span: Some(*span),
+        on_exps,
+        is_multi: false,
+        label,
+        motive: None,
+        cases: compiler.source_cases(ctx, pats)?,
+        compiled: Box::new(compiled),
+    }
+    .into())
+}
+
+/// Compile a match on several expressions at once
+pub(super) fn compile_multi_match(
+    multi_match: &cst::exp::MultiMatch,
+    ctx: &mut Ctx,
+) -> LoweringResult<polarity_lang_ast::Exp> {
+    let cst::exp::MultiMatch { span, on_exps, cases } = multi_match;
+    for Case { pattern, .. } in cases {
+        if pattern.params.len() != on_exps.len() {
+            return Err(LoweringError::InvalidPattern {
+                span: pattern.span.to_miette(),
+                message: format!(
+                    "Wrong number of patterns: got {}, expected {}",
+                    pattern.params.len(),
+                    on_exps.len()
+                ),
+            }
+            .into());
+        }
+    }
+
+    let clauses = cases.iter().map(Clause::from).collect();
+    let mut compiler = Compiler::new(*span, clauses, vec![]);
+    let rows = compiler.rows(ctx)?;
+    let pats = rows.iter().map(|row| row.pats.clone()).collect();
+    let on_exps = on_exps.lower(ctx)?;
+    let compiled = compiler.compile_scrutinees(ctx, &on_exps, rows)?;
+    compiler.check_reachable(ctx);
+    Ok(polarity_lang_ast::CompiledMatch {
+        span: Some(*span),
+        on_exps,
+        is_multi: true,
+        label: None,
+        motive: None,
+        cases: compiler.source_cases(ctx, pats)?,
+        compiled: Box::new(compiled),
+    }
+    .into())
+}
+
+fn unqualified(name: &QIdent) -> Ident {
+    Ident { span: name.span, id: name.id.clone() }
+}
+
+/// Whether an identifier without arguments in a nested pattern binds a variable instead of standing for a constructor
+///
+/// At the top level of a case with a single pattern, an identifier always stands for a constructor.
+fn is_var_name(name: &QIdent, symbol_table: &SymbolTable) -> bool {
+    name.quals.is_empty()
+        && !matches!(symbol_table.lookup(&unqualified(name)), Ok((DeclMeta::Ctor { .. }, _)))
+}
+
+/// Check the name of a variable in a nested pattern, which may be a misspelled constructor
+///
+/// A misspelled constructor would otherwise bind a variable which silently matches every value.
+/// Constructors start with an uppercase letter by convention, so such variable names are rejected,
+/// and variables which shadow a declaration are pointed out.
+/// Flat patterns are not checked, since their arguments are always variables.
+fn check_var_name(name: &QIdent, ctx: &mut Ctx) -> LoweringResult<()> {
+    if name.id.starts_with(char::is_uppercase) {
+        return Err(LoweringError::InvalidPattern {
+            span: name.span.to_miette(),
+            message: format!(
+                "{} is not a constructor, and variables must start with a lowercase letter",
+                name.id
+            ),
+        }
+        .into());
+    }
+    if ctx.symbol_table.lookup(&unqualified(name)).is_ok() {
+        ctx.warn(LoweringWarning::ShadowedDecl {
+            name: name.id.clone(),
+            span: name.span.to_miette(),
+        });
+    }
+    Ok(())
+}
+
+/// A constructor as far as it is relevant for pattern matching
+#[derive(Clone)]
+struct CtorInfo {
+    name: IdBound,
+    arity: usize,
+    /// The data type which the constructor belongs to
+    typ: Ident,
+}
+
+impl CtorInfo {
+    fn lookup(name: &Ident, ctx: &mut Ctx) -> LoweringResult<Option<Self>> {
+        let (DeclMeta::Ctor { params, typ }, name) = ctx.lookup(name)? else {
+            return Ok(None);
+        };
+        let arity = params.0.iter().map(|param| 1 + param.names.len()).sum();
+        Ok(Some(CtorInfo { name, arity, typ }))
+    }
+}
+
+/// A pattern whose identifiers have been resolved
+#[derive(Clone)]
+enum Pat<'a> {
+    Wildcard,
+    Var(VarBind),
+    Ctor { span: Span, ctor: CtorInfo, params: Vec<Pat<'a>> },
+    Literal(&'a cst::exp::Literal),
+}
+
+impl<'a> Pat<'a> {
+    fn resolve(pattern: &'a Pattern, nested: bool, ctx: &mut Ctx) -> LoweringResult<Self> {
+        match pattern {
+            Pattern::Wildcard { .. } => Ok(Pat::Wildcard),
+            Pattern::Literal(lit) => match lit.kind {
+                LiteralKind::F64(_) => Err(LoweringError::InvalidPattern {
+                    span: lit.span.to_miette(),
+                    message: "Floating point literals cannot be used as patterns".to_owned(),
+                }
+                .into()),
+                _ => Ok(Pat::Literal(lit)),
+            },
+            Pattern::Ctor { span, name, params } => {
+                if nested && params.is_empty() && is_var_name(name, &ctx.symbol_table) {
+                    check_var_name(name, ctx)?;
+                    let var = BindingSite::Var { span: *span, name: unqualified(name) };
+                    return Ok(Pat::Var(var.lower(ctx)?));
+                }
+                let ident = expect_ident(name.clone())?;
+                let Some(ctor) = CtorInfo::lookup(&ident, ctx)? else {
+                    return Err(LoweringError::InvalidPattern {
+                        span: span.to_miette(),
+                        message: format!("{} is not a constructor", ident.id),
+                    }
+                    .into());
+                };
+                if ctor.arity != params.len() {
+                    return Err(LoweringError::InvalidPattern {
+                        span: span.to_miette(),
+                        message: format!(
+                            "Wrong number of arguments to {}: got {}, expected {}",
+                            ident.id,
+                            params.len(),
+                            ctor.arity
+                        ),
+                    }
+                    .into());
+                }
+                let params = params
+                    .iter()
+                    .map(|param| Pat::resolve(param, true, ctx))
+                    .collect::<Result<_, _>>()?;
+                Ok(Pat::Ctor { span: *span, ctor, params })
+            }
+        }
+    }
+
+    fn is_irrefutable(&self) -> bool {
+        matches!(self, Pat::Wildcard | Pat::Var(_))
+    }
+
+    /// The pattern as it is written in the source
+    fn to_source(&self, ctx: &mut Ctx) -> LoweringResult<polarity_lang_ast::SourcePattern> {
+        Ok(match self {
+            Pat::Wildcard => polarity_lang_ast::SourcePattern::Wildcard,
+            Pat::Var(var) => polarity_lang_ast::SourcePattern::Var(var.clone()),
+            Pat::Ctor { ctor, params, .. } => polarity_lang_ast::SourcePattern::Ctor {
+                name: ctor.name.clone(),
+                params: params
+                    .iter()
+                    .map(|param| param.to_source(ctx))
+                    .collect::<Result<_, _>>()?,
+            },
+            Pat::Literal(lit) => match lit.lower(ctx)? {
+                polarity_lang_ast::Exp::Literal(lit) => {
+                    polarity_lang_ast::SourcePattern::Literal(lit)
+                }
+                _ => {
+                    return Err(LoweringError::Impossible {
+                        message: "Expected a literal".to_owned(),
+                        span: Some(lit.span.to_miette()),
+                    }
+                    .into());
+                }
+            },
+        })
+    }
+}
+
+/// A value which is matched against a column of the pattern matrix
+#[derive(Clone, Copy, PartialEq)]
+enum Occ {
+    /// The scrutinee of a definition or local match, which is not bound to a variable
+    Root,
+    /// A variable bound by a compiled case
+    Var(Lvl),
+}
+
+/// A row of the pattern matrix
+struct Row<'a> {
+    /// The index of the case the row belongs to
+    case: usize,
+    pats: Vec<Pat<'a>>,
+    /// The variables bound by the patterns which have already been removed from the row
+    bindings: Vec<(VarBind, Occ)>,
+}
+
+/// The constructor which has been matched at an occurrence on the current path of the compiled tree
+struct Decision {
+    occ: Occ,
+    ctor: CtorInfo,
+    params: Vec<Occ>,
+}
+
+/// A case of a match, with one pattern for every scrutinee
+struct Clause<'a> {
+    span: Span,
+    comments: &'a polarity_lang_ast::Comments,
+    pattern_span: Span,
+    patterns: Vec<&'a Pattern>,
+    /// Whether the patterns are the components of a tuple pattern, which can be variables
+    is_tuple: bool,
+    body: Option<&'a cst::exp::Exp>,
+}
+
+impl<'a> From<&'a Case<Pattern>> for Clause<'a> {
+    fn from(case: &'a Case<Pattern>) -> Self {
+        let Case { span, comments, pattern, body } = case;
+        Clause {
+            span: *span,
+            comments,
+            pattern_span: pattern.span(),
+            patterns: vec![pattern],
+            is_tuple: false,
+            body: body.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a Case<MultiPattern>> for Clause<'a> {
+    fn from(case: &'a Case<MultiPattern>) -> Self {
+        let Case { span, comments, pattern, body } = case;
+        Clause {
+            span: *span,
+            comments,
+            pattern_span: pattern.span,
+            patterns: pattern.params.iter().collect(),
+            is_tuple: true,
+            body: body.as_deref(),
+        }
+    }
+}
+
+/// The result of compiling a pattern matrix
+enum Body {
+    Exp(Box<polarity_lang_ast::Exp>),
+    /// The selected case is absurd
+    Absurd(usize),
+}
+
+struct Compiler<'a> {
+    /// The span of the whole match, at which missing cases are reported
+    span: Span,
+    clauses: Vec<Clause<'a>>,
+    /// Whether the clause with the same index is selected for some value
+    reachable: Vec<bool>,
+    /// The occurrences at which the scrutinees are matched
+    roots: Vec<Occ>,
+    decisions: Vec<Decision>,
+    /// The context in which the scrutinees are matched
+    outer: LevelCtx,
+    /// The body of the clause with the same index, once it has been lowered in `outer` extended by its variables
+    bodies: Vec<Option<polarity_lang_ast::Exp>>,
+}
+
+impl<'a> Compiler<'a> {
+    fn new(span: Span, clauses: Vec<Clause<'a>>, roots: Vec<Occ>) -> Self {
+        let reachable = vec![false; clauses.len()];
+        let bodies = vec![None; clauses.len()];
+        Compiler {
+            span,
+            clauses,
+            reachable,
+            roots,
+            decisions: vec![],
+            outer: LevelCtx::empty(),
+            bodies,
+        }
+    }
+
+    fn rows(&self, ctx: &mut Ctx) -> LoweringResult<Vec<Row<'a>>> {
+        self.clauses
+            .iter()
+            .enumerate()
+            .map(|(case, clause)| {
+                let pats = clause
+                    .patterns
+                    .iter()
+                    .map(|pattern| Pat::resolve(pattern, clause.is_tuple, ctx))
+                    .collect::<Result<_, _>>()?;
+                Ok(Row { case, pats, bindings: vec![] })
+            })
+            .collect()
+    }
+
+    /// Compile the rows after binding the lowered scrutinees, which are appended to the roots
+    ///
+    /// A scrutinee which is a variable is matched on directly, all others are bound by let bindings.
+    fn compile_scrutinees(
+        &mut self,
+        ctx: &mut Ctx,
+        on_exps: &[polarity_lang_ast::Exp],
+        rows: Vec<Row<'a>>,
+    ) -> LoweringResult<polarity_lang_ast::Exp> {
+        let Some((on_exp, on_exps)) = on_exps.split_first() else {
+            let roots = self.roots.clone();
+            self.outer = ctx.binders.clone();
+            let body = self.compile(ctx, &roots, rows)?;
+            return self.expect_body(body);
+        };
+        if let polarity_lang_ast::Exp::Variable(polarity_lang_ast::Variable { idx, .. }) = on_exp {
+            self.roots.push(Occ::Var(ctx.binders.idx_to_lvl(*idx)));
+            return self.compile_scrutinees(ctx, on_exps, rows);
+        }
+        let name = VarBind::Wildcard { span: None };
+        let mut on_exps = on_exps.to_vec();
+        on_exps.shift((1, 0));
+        let body = ctx.bind_single(name.clone(), |ctx| {
+            self.roots.push(Occ::Var(Lvl { fst: ctx.binders.len() - 1, snd: 0 }));
+            self.compile_scrutinees(ctx, &on_exps, rows)
+        })?;
+        Ok(polarity_lang_ast::LocalLet {
+            span: on_exp.span().unwrap_or_default(),
+            comments: Default::default(),
+            name,
+            typ: None,
+            bound: Box::new(on_exp.clone()),
+            body: Box::new(body),
+            inferred_type: None,
+        }
+        .into())
+    }
+
+    /// The cases as they are written in the source, given the resolved patterns of every clause
+    ///
+    /// The body of a case is lowered in the context of the match extended by its variables. The
+    /// bodies of the cases which have been selected have already been lowered in `outer`, which
+    /// may additionally bind the scrutinees. The body of an unreachable case is only lowered for
+    /// printing, so its metavariables are not registered.
+    fn source_cases(
+        &self,
+        ctx: &mut Ctx,
+        pats: Vec<Vec<Pat<'a>>>,
+    ) -> LoweringResult<Vec<polarity_lang_ast::SourceCase>> {
+        let generated = (self.outer.len() - ctx.binders.len()) as isize;
+        let mut source_cases = vec![];
+        for ((clause, pats), lowered) in self.clauses.iter().zip(pats).zip(&self.bodies) {
+            let mut pats =
+                pats.into_iter().map(|pat| pat.to_source(ctx)).collect::<Result<Vec<_>, _>>()?;
+            let pattern = if clause.is_tuple {
+                polarity_lang_ast::SourcePattern::Tuple(pats)
+            } else {
+                pats.remove(0)
+            };
+            let vars: Vec<VarBind> = pattern.vars().into_iter().cloned().collect();
+            let body = match (clause.body, lowered) {
+                (None, _) => None,
+                (Some(_), Some(lowered)) => {
+                    let mut lowered = lowered.clone();
+                    lowered.shift_in_range(&(vars.len()..), (-generated, 0));
+                    Some(Box::new(lowered))
+                }
+                (Some(body), None) => {
+                    let meta_vars = ctx.meta_vars.clone();
+                    let lowered = lower_in_scope(ctx, &vars, body);
+                    ctx.meta_vars = meta_vars;
+                    Some(Box::new(lowered?))
+                }
+            };
+            source_cases.push(polarity_lang_ast::SourceCase {
+                span: Some(clause.span),
+                comments: clause.comments.clone(),
+                pattern,
+                body,
+            });
+        }
+        Ok(source_cases)
+    }
+
+    /// Report the cases which are not selected for any value
+    fn check_reachable(&self, ctx: &mut Ctx) {
+        for (clause, reachable) in self.clauses.iter().zip(&self.reachable) {
+            if !reachable {
+                ctx.warn(LoweringWarning::UnreachableCase { span: clause.span.to_miette() });
+            }
+        }
+    }
+
+    fn expect_body(&self, body: Body) -> LoweringResult<polarity_lang_ast::Exp> {
+        match body {
+            Body::Exp(exp) => Ok(*exp),
+            Body::Absurd(case) => Err(LoweringError::InvalidPattern {
+                span: self.clauses[case].pattern_span.to_miette(),
+                message: "Only a case which ends with a constructor pattern can be absurd"
+                    .to_owned(),
+            }
+            .into()),
+        }
+    }
+
+    /// Compile the pattern matrix with the given rows, whose columns match the given occurrences
+    fn compile(&mut self, ctx: &mut Ctx, occs: &[Occ], rows: Vec<Row<'a>>) -> LoweringResult<Body> {
+        let Some(first) = rows.first() else {
+            return Err(LoweringError::NonExhaustiveMatch {
+                witness: self.missing_value(),
+                span: self.span.to_miette(),
+            }
+            .into());
+        };
+
+        let Some(col) = first.pats.iter().position(|pat| !pat.is_irrefutable()) else {
+            let Row { case, pats, mut bindings } = rows.into_iter().next().unwrap();
+            self.reachable[case] = true;
+            for (pat, occ) in pats.into_iter().zip(occs) {
+                if let Pat::Var(name) = pat {
+                    bindings.push((name, *occ));
+                }
+            }
+            // The variables are bound in the same order on every path which selects the case.
+            bindings.sort_by_key(|(name, _)| name.span().map(|span| span.start));
+            let Some(body) = self.clauses[case].body else {
+                return Ok(Body::Absurd(case));
+            };
+            let body = self.case_body(ctx, case, &bindings, body)?;
+            let span = self.clauses[case].span;
+            return Ok(Body::Exp(Box::new(self.bind_vars(ctx, span, &bindings, body)?)));
+        };
+
+        match first.pats[col].clone() {
+            Pat::Ctor { ctor, .. } => {
+                let cases = self.split_ctor(ctx, occs, rows, col, &ctor.typ)?;
+                let on_exp = Box::new(self.occ_value(ctx, occs[col])?);
+                Ok(Body::Exp(Box::new(local_match(ctx, on_exp, cases)?)))
+            }
+            Pat::Literal(lit) => self.split_literal(ctx, occs, rows, col, lit),
+            Pat::Wildcard | Pat::Var(_) => unreachable!(),
+        }
+    }
+
+    /// Generate one case for every constructor of the data type `typ` of the given column
+    fn split_ctor(
+        &mut self,
+        ctx: &mut Ctx,
+        occs: &[Occ],
+        rows: Vec<Row<'a>>,
+        col: usize,
+        typ: &Ident,
+    ) -> LoweringResult<Vec<polarity_lang_ast::Case>> {
+        let ctors = data_ctors(typ, ctx)?;
+        for row in rows.iter() {
+            match &row.pats[col] {
+                Pat::Ctor { span, ctor, .. } if ctor.typ != *typ => {
+                    return Err(LoweringError::InvalidPattern {
+                        span: span.to_miette(),
+                        message: format!("{} is not a constructor of {}", ctor.name.id, typ.id),
+                    }
+                    .into());
+                }
+                Pat::Literal(lit) => {
+                    return Err(LoweringError::InvalidPattern {
+                        span: lit.span.to_miette(),
+                        message: format!(
+                            "A literal cannot be matched against data type {}",
+                            typ.id
+                        ),
+                    }
+                    .into());
+                }
+                _ => {}
+            }
+        }
+
+        let mut cases = vec![];
+        for ctor in ctors {
+            let arity = ctor.arity;
+            let mut ctor_rows = vec![];
+            // The constructor as it is written in the first pattern which matches it, if any
+            let mut written: Option<(Span, IdBound)> = None;
+            for row in rows.iter() {
+                let params = match &row.pats[col] {
+                    Pat::Ctor { span, ctor: other, params } if other.name.id == ctor.name.id => {
+                        written.get_or_insert_with(|| (*span, other.name.clone()));
+                        params.clone()
+                    }
+                    Pat::Ctor { .. } | Pat::Literal(_) => continue,
+                    Pat::Wildcard | Pat::Var(_) => vec![Pat::Wildcard; arity],
+                };
+                let mut bindings = row.bindings.clone();
+                if let Pat::Var(var) = &row.pats[col] {
+                    bindings.push((var.clone(), occs[col]));
+                }
+                let mut pats = row.pats[..col].to_vec();
+                pats.extend(params);
+                pats.extend(row.pats[col + 1..].iter().cloned());
+                ctor_rows.push(Row { case: row.case, pats, bindings });
+            }
+
+            // The generated case belongs to the first clause which it can select.
+            let span = ctor_rows.first().map(|row| self.clauses[row.case].span);
+            let binders = vec![VarBind::Wildcard { span: None }; arity];
+            let body = ctx.bind_fold(
+                binders.iter(),
+                (),
+                |_ctx, (), name| Binder { name: name.clone(), content: () },
+                |ctx, ()| {
+                    let fst = ctx.binders.len() - 1;
+                    let params: Vec<Occ> =
+                        (0..arity).map(|snd| Occ::Var(Lvl { fst, snd })).collect();
+                    let mut ctor_occs = occs[..col].to_vec();
+                    ctor_occs.extend(params.iter().copied());
+                    ctor_occs.extend(occs[col + 1..].iter().copied());
+
+                    self.decisions.push(Decision { occ: occs[col], ctor: ctor.clone(), params });
+                    let body = self.compile(ctx, &ctor_occs, ctor_rows);
+                    self.decisions.pop();
+                    body
+                },
+            )?;
+
+            let params = binders
+                .into_iter()
+                .map(|name| polarity_lang_ast::ParamInst {
+                    span: None,
+                    name,
+                    typ: None,
+                    erased: false,
+                })
+                .collect();
+            let (pattern_span, name) = match written {
+                Some((span, name)) => (Some(span), name),
+                None => (None, IdBound { span: None, ..ctor.name.clone() }),
+            };
+            cases.push(polarity_lang_ast::Case {
+                span,
+                comments: Default::default(),
+                pattern: polarity_lang_ast::Pattern {
+                    span: pattern_span,
+                    is_copattern: false,
+                    name,
+                    params: polarity_lang_ast::TelescopeInst { params },
+                },
+                body: match body {
+                    Body::Exp(exp) => Some(exp),
+                    Body::Absurd(_) => None,
+                },
+                from_wildcard: pattern_span.is_none(),
+            });
+        }
+        Ok(cases)
+    }
+
+    /// Compare the given column with the literal `lit`, using the equality function for its type
+    fn split_literal(
+        &mut self,
+        ctx: &mut Ctx,
+        occs: &[Occ],
+        rows: Vec<Row<'a>>,
+        col: usize,
+        lit: &'a cst::exp::Literal,
+    ) -> LoweringResult<Body> {
+        let mut equal_rows = vec![];
+        let mut other_rows = vec![];
+        for row in rows {
+            let equal = match &row.pats[col] {
+                Pat::Literal(other) if same_literal(&other.kind, &lit.kind) => true,
+                Pat::Literal(_) => {
+                    other_rows.push(row);
+                    continue;
+                }
+                Pat::Ctor { span, ctor, .. } => {
+                    return Err(LoweringError::InvalidPattern {
+                        span: span.to_miette(),
+                        message: format!(
+                            "The constructor {} cannot be matched against a literal",
+                            ctor.name.id
+                        ),
+                    }
+                    .into());
+                }
+                Pat::Wildcard | Pat::Var(_) => false,
+            };
+            let mut pats = row.pats.clone();
+            let mut bindings = row.bindings.clone();
+            if let Pat::Var(var) = pats.remove(col) {
+                bindings.push((var, occs[col]));
+            }
+            equal_rows.push(Row { case: row.case, pats, bindings });
+            if !equal {
+                other_rows.push(row);
+            }
+        }
+
+        let mut equal_occs = occs.to_vec();
+        equal_occs.remove(col);
+        let equal_body = self.compile_nullary_case(ctx, &equal_occs, equal_rows)?;
+        let other_body = self.compile_nullary_case(ctx, occs, other_rows)?;
+
+        let (eq, t, f) = literal_eq(lit, ctx)?;
+        let on_exp = polarity_lang_ast::Call {
+            span: None,
+            kind: eq.1,
+            name: eq.0,
+            args: polarity_lang_ast::Args {
+                args: vec![
+                    polarity_lang_ast::Arg::UnnamedArg {
+                        arg: Box::new(self.occ_value(ctx, occs[col])?),
+                        erased: false,
+                        comments: Default::default(),
+                    },
+                    polarity_lang_ast::Arg::UnnamedArg {
+                        arg: Box::new(lit.lower(ctx)?),
+                        erased: false,
+                        comments: Default::default(),
+                    },
+                ],
+            },
+            is_bin_op: None,
+            inferred_type: None,
+        };
+        let case = |name, body| polarity_lang_ast::Case {
+            span: None,
+            comments: Default::default(),
+            pattern: polarity_lang_ast::Pattern {
+                span: None,
+                is_copattern: false,
+                name,
+                params: polarity_lang_ast::TelescopeInst { params: vec![] },
+            },
+            body: Some(Box::new(body)),
+            from_wildcard: false,
+        };
+        let cases = vec![case(t, equal_body), case(f, other_body)];
+        Ok(Body::Exp(Box::new(local_match(ctx, Box::new(on_exp.into()), cases)?)))
+    }
+
+    /// Compile the body of a case for a constructor without parameters
+    ///
+    /// Such a case still binds an empty telescope, which has to be accounted for by the de Bruijn indices.
+    fn compile_nullary_case(
+        &mut self,
+        ctx: &mut Ctx,
+        occs: &[Occ],
+        rows: Vec<Row<'a>>,
+    ) -> LoweringResult<polarity_lang_ast::Exp> {
+        ctx.bind_fold(
+            std::iter::empty::<&VarBind>(),
+            (),
+            |_ctx, (), name| Binder { name: name.clone(), content: () },
+            |ctx, ()| {
+                let body = self.compile(ctx, occs, rows)?;
+                self.expect_body(body)
+            },
+        )
+    }
+
+    /// The body of a case for the current path, which is bound by the given variables in the context of the path
+    ///
+    /// The body is lowered when the case is selected for the first time. On every other path, the
+    /// variables of the match are shifted past the cases which have been generated on that path.
+    fn case_body(
+        &mut self,
+        ctx: &mut Ctx,
+        case: usize,
+        bindings: &[(VarBind, Occ)],
+        body: &cst::exp::Exp,
+    ) -> LoweringResult<polarity_lang_ast::Exp> {
+        let mut body = match &self.bodies[case] {
+            Some(lowered) => {
+                let mut lowered = lowered.clone();
+                relabel(&mut lowered, ctx);
+                lowered
+            }
+            None => {
+                let names: Vec<_> = bindings.iter().map(|(name, _)| name.clone()).collect();
+                let binders = std::mem::replace(&mut ctx.binders, self.outer.clone());
+                let lowered = lower_in_scope(ctx, &names, body);
+                ctx.binders = binders;
+                let lowered = lowered?;
+                self.bodies[case] = Some(lowered.clone());
+                lowered
+            }
+        };
+        let generated = ctx.binders.len() - self.outer.len();
+        body.shift_in_range(&(bindings.len()..), (generated as isize, 0));
+        Ok(body)
+    }
+
+    /// Bind the given variables by let bindings around the body of the case with the given span
+    fn bind_vars(
+        &mut self,
+        ctx: &mut Ctx,
+        span: Span,
+        bindings: &[(VarBind, Occ)],
+        body: polarity_lang_ast::Exp,
+    ) -> LoweringResult<polarity_lang_ast::Exp> {
+        let Some(((name, occ), bindings)) = bindings.split_first() else {
+            return Ok(body);
+        };
+        let bound = Box::new(self.occ_value(ctx, *occ)?);
+        ctx.bind_single(name.clone(), |ctx| {
+            let body = self.bind_vars(ctx, span, bindings, body)?;
+            Ok(polarity_lang_ast::LocalLet {
+                span,
+                comments: Default::default(),
+                name: name.clone(),
+                typ: None,
+                bound,
+                body: Box::new(body),
+                inferred_type: None,
+            }
+            .into())
+        })
+    }
+
+    /// The value at an occurrence
+    ///
+    /// The scrutinee is not bound to a variable, but it is always split first and never bound by a pattern.
+    fn occ_value(&self, ctx: &Ctx, occ: Occ) -> LoweringResult<polarity_lang_ast::Exp> {
+        let Occ::Var(lvl) = occ else {
+            return Err(LoweringError::Impossible {
+                message: "The scrutinee must be matched first".to_owned(),
+                span: Some(self.span.to_miette()),
+            }
+            .into());
+        };
+        Ok(polarity_lang_ast::Variable {
+            span: None,
+            idx: ctx.binders.lvl_to_idx(lvl),
+            name: ctx.binders.bound[lvl.fst][lvl.snd].name.clone().into(),
+            inferred_type: None,
+            erased: false,
+        }
+        .into())
+    }
+
+    /// The scrutinees which are not matched by any row, given the constructors matched on the current path
+    fn missing_value(&self) -> String {
+        match self.roots.as_slice() {
+            [root] => self.witness(*root),
+            roots => {
+                let roots: Vec<_> = roots.iter().map(|root| self.witness(*root)).collect();
+                format!("({})", roots.join(", "))
+            }
+        }
+    }
+
+    /// A value which is not matched by any row, given the constructors matched on the current path
+    fn witness(&self, occ: Occ) -> String {
+        match self.decisions.iter().find(|decision| decision.occ == occ) {
+            None => "_".to_owned(),
+            Some(Decision { ctor, params, .. }) if params.is_empty() => ctor.name.id.clone(),
+            Some(Decision { ctor, params, .. }) => {
+                let params: Vec<_> = params.iter().map(|param| self.witness(*param)).collect();
+                format!("{}({})", ctor.name.id, params.join(", "))
+            }
+        }
+    }
+}
+
+/// Lower an expression in the scope of the given variables, each of which is bound by a let binding
+fn lower_in_scope(
+    ctx: &mut Ctx,
+    names: &[VarBind],
+    exp: &cst::exp::Exp,
+) -> LoweringResult<polarity_lang_ast::Exp> {
+    let Some((name, names)) = names.split_first() else {
+        return exp.lower(ctx);
+    };
+    ctx.declare_local(name);
+    ctx.bind_single(name.clone(), |ctx| lower_in_scope(ctx, names, exp))
+}
+
+/// Give the local (co)matches in a copy of a lowered body new labels, so that labels stay unique
+fn relabel(exp: &mut polarity_lang_ast::Exp, ctx: &mut Ctx) {
+    use polarity_lang_ast::*;
+
+    let relabel_args = |args: &mut Args, ctx: &mut Ctx| {
+        for arg in args.args.iter_mut() {
+            match arg {
+                Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => relabel(arg, ctx),
+                Arg::InsertedImplicitArg { .. } => {}
+            }
+        }
+    };
+    match exp {
+        Exp::Variable(_) | Exp::TypeUniv(_) | Exp::Hole(_) | Exp::Literal(_) => {}
+        Exp::TypCtor(TypCtor { args, .. }) | Exp::Call(Call { args, .. }) => {
+            relabel_args(args, ctx)
+        }
+        Exp::DotCall(DotCall { exp, args, .. }) => {
+            relabel(exp, ctx);
+            relabel_args(args, ctx);
+        }
+        Exp::Anno(Anno { exp, typ, .. }) => {
+            relabel(exp, ctx);
+            relabel(typ, ctx);
+        }
+        Exp::LocalMatch(LocalMatch { name, on_exp, motive, cases, .. }) => {
+            ctx.relabel(name);
+            relabel(on_exp, ctx);
+            if let Some(Motive { ret_typ, .. }) = motive {
+                relabel(ret_typ, ctx);
+            }
+            cases
+                .iter_mut()
+                .flat_map(|case| case.body.as_mut())
+                .for_each(|body| relabel(body, ctx));
+        }
+        Exp::CompiledMatch(CompiledMatch { compiled, .. }) => relabel(compiled, ctx),
+        Exp::LocalComatch(LocalComatch { name, cases, .. }) => {
+            ctx.relabel(name);
+            cases
+                .iter_mut()
+                .flat_map(|case| case.body.as_mut())
+                .for_each(|body| relabel(body, ctx));
+        }
+        Exp::LocalLet(LocalLet { typ, bound, body, .. }) => {
+            if let Some(typ) = typ {
+                relabel(typ, ctx);
+            }
+            relabel(bound, ctx);
+            relabel(body, ctx);
+        }
+        Exp::DoBlock(DoBlock { statements, .. }) => {
+            let mut statements = statements;
+            loop {
+                match statements {
+                    DoStatements::Bind { bound, body, .. } => {
+                        relabel(bound, ctx);
+                        statements = body;
+                    }
+                    DoStatements::Let { typ, bound, body, .. } => {
+                        if let Some(typ) = typ {
+                            relabel(typ, ctx);
+                        }
+                        relabel(bound, ctx);
+                        statements = body;
+                    }
+                    DoStatements::Return { exp, .. } => {
+                        relabel(exp, ctx);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The constructors of a data type in the order of their declaration
+fn data_ctors(typ: &Ident, ctx: &mut Ctx) -> LoweringResult<Vec<CtorInfo>> {
+    let (DeclMeta::Data { ctors, .. }, _) = ctx.lookup(typ)? else {
+        return Err(LoweringError::InvalidPattern {
+            span: typ.span.to_miette(),
+            message: format!("{} is not a data type", typ.id),
+        }
+        .into());
+    };
+    let mut infos = vec![];
+    for ctor in ctors {
+        let ctor = Ident { span: typ.span, id: ctor.id };
+        infos.extend(CtorInfo::lookup(&ctor, ctx)?);
+    }
+    Ok(infos)
+}
+
+fn same_literal(lhs: &LiteralKind, rhs: &LiteralKind) -> bool {
+    match (lhs, rhs) {
+        (LiteralKind::I64(lhs), LiteralKind::I64(rhs)) => lhs == rhs,
+        (LiteralKind::Char { unescaped: lhs, .. }, LiteralKind::Char { unescaped: rhs, .. }) => {
+            lhs == rhs
+        }
+        (
+            LiteralKind::String { unescaped: lhs, .. },
+            LiteralKind::String { unescaped: rhs, .. },
+        ) => lhs == rhs,
+        _ => false,
+    }
+}
+
+/// The equality function for the type of the literal together with the constructors of its result
+///
+/// The constructors `T` and `F` are resolved in the data type which the equality function returns.
+fn literal_eq(
+    lit: &cst::exp::Literal,
+    ctx: &mut Ctx,
+) -> LoweringResult<((IdBound, polarity_lang_ast::CallKind), IdBound, IdBound)> {
+    let (typ, eq) = match lit.kind {
+        LiteralKind::I64(_) => ("I64", "eq_i64"),
+        LiteralKind::Char { .. } => ("Char", "eq_char"),
+        LiteralKind::String { .. } => ("String", "eq_string"),
+        LiteralKind::F64(_) => ("F64", "eq_f64"),
+    };
+    let missing = || LoweringError::InvalidPattern {
+        span: lit.span.to_miette(),
+        message: format!(
+            "Matching on {typ} literals requires the function {eq} which returns a data type with constructors T and F"
+        ),
+    };
+
+    let eq = Ident { span: lit.span, id: eq.to_owned() };
+    if !ctx.symbol_table.lookup_exists(&eq) {
+        return Err(missing().into());
+    }
+    let (ret_typ, eq) = match ctx.lookup(&eq)? {
+        (DeclMeta::Extern { typ, .. }, name) => (typ, (name, polarity_lang_ast::CallKind::Extern)),
+        (DeclMeta::Let { typ, .. }, name) => (typ, (name, polarity_lang_ast::CallKind::LetBound)),
+        _ => return Err(missing().into()),
+    };
+    let cst::exp::Exp::Call(cst::exp::Call { name: ret_typ, args, .. }) = *ret_typ else {
+        return Err(missing().into());
+    };
+    if !args.is_empty() {
+        return Err(missing().into());
+    }
+    let ret_typ = expect_ident(ret_typ)?;
+    let (DeclMeta::Data { ctors, .. }, ret_typ) = ctx.lookup(&ret_typ)? else {
+        return Err(missing().into());
+    };
+    let ctor = |id: &str| {
+        ctors.iter().any(|ctor| ctor.id == id).then(|| IdBound {
+            span: None,
+            id: id.to_owned(),
+            uri: ret_typ.uri.clone(),
+        })
+    };
+    let (Some(t), Some(f)) = (ctor("T"), ctor("F")) else { return Err(missing().into()) };
+    Ok((eq, t, f))
+}
+
+/// A local match without motive on a value bound by the compiled cases
+fn local_match(
+    ctx: &mut Ctx,
+    on_exp: Box<polarity_lang_ast::Exp>,
+    cases: Vec<polarity_lang_ast::Case>,
+) -> LoweringResult<polarity_lang_ast::Exp> {
+    let fvs = cases.free_vars(&ctx.binders);
+    let closure = polarity_lang_ast::Closure::identity(&ctx.binders, &fvs);
+    Ok(polarity_lang_ast::LocalMatch {
+        span: None,
+        ctx: None,
+        name: ctx.unique_label(None, &Span::default())?,
+        closure,
+        on_exp,
+        motive: None,
+        ret_typ: None,
+        cases,
+        inferred_type: None,
+    }
+    .into())
+}
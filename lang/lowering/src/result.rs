@@ -141,6 +141,37 @@ pub enum LoweringError {
         span: SourceSpan,
         typ: String,
     },
+    #[error("The operators {lhs} and {rhs} cannot be chained without parentheses")]
+    #[diagnostic(
+        code("L-022"),
+        help(
+            "Both operators have precedence level {precedence}, but they are neither both left-associative nor both right-associative."
+        )
+    )]
+    AmbiguousOperators {
+        lhs: String,
+        rhs: String,
+        precedence: u32,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Invalid pattern: {message}")]
+    #[diagnostic(code("L-023"))]
+    InvalidPattern {
+        #[label]
+        span: SourceSpan,
+        message: String,
+    },
+    #[error("The pattern match is not exhaustive: {witness} is not covered")]
+    #[diagnostic(
+        code("L-024"),
+        help("Add a case for {witness}, or mark it as absurd if it cannot occur.")
+    )]
+    NonExhaustiveMatch {
+        witness: String,
+        #[label]
+        span: SourceSpan,
+    },
     #[error("An unexpected internal error occurred: {message}")]
     #[diagnostic(code("L-XXX"))]
     /// This error should not occur.
@@ -151,3 +182,56 @@ pub enum LoweringError {
         span: Option<SourceSpan>,
     },
 }
+
+/// All the warnings that can be emitted during lowering
+///
+/// Warnings do not prevent a module from being lowered.
+#[derive(Error, Diagnostic, Debug, Clone)]
+pub enum LoweringWarning {
+    #[error("Unused import {path}")]
+    #[diagnostic(code("L-W01"), severity(Warning))]
+    UnusedImport {
+        path: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Unused variable {name}")]
+    #[diagnostic(
+        code("L-W02"),
+        severity(Warning),
+        help("If this is intentional, use the wildcard \"_\" instead.")
+    )]
+    UnusedVariable {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("{name} is never used in this module")]
+    #[diagnostic(code("L-W03"), severity(Advice))]
+    UnusedDecl {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("This case is unreachable")]
+    #[diagnostic(
+        code("L-W04"),
+        severity(Warning),
+        help("Every value matched by its pattern is already matched by a previous case.")
+    )]
+    UnreachableCase {
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("The variable {name} shadows the declaration {name}")]
+    #[diagnostic(
+        code("L-W05"),
+        severity(Advice),
+        help("{name} is not a constructor, so the pattern binds a variable.")
+    )]
+    ShadowedDecl {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+}
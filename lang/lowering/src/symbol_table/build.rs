@@ -1,11 +1,12 @@
 use decls::*;
+use polarity_lang_ast::Fixity;
 use polarity_lang_miette_util::ToMiette;
 use polarity_lang_miette_util::codespan::Span;
 use polarity_lang_parser::cst::*;
 
 use crate::{LoweringError, LoweringResult, expect_ident};
 
-use super::{DeclMeta, ModuleSymbolTable};
+use super::{DeclMeta, ModuleSymbolTable, OperatorMeta};
 
 pub fn build_symbol_table(module: &Module) -> LoweringResult<ModuleSymbolTable> {
     let mut symbol_table = ModuleSymbolTable::default();
@@ -65,26 +66,25 @@ impl BuildSymbolTable for Data {
 
         check_name(symbol_table, name, span)?;
 
-        let meta = DeclMeta::Data { params: params.clone() };
+        let ctor_names = ctors.iter().map(|ctor| ctor.name.clone()).collect();
+        let meta = DeclMeta::Data { params: params.clone(), ctors: ctor_names };
         symbol_table.idents.insert(name.clone(), meta);
 
         for ctor in ctors {
-            ctor.build(symbol_table)?;
+            build_ctor(ctor, name, symbol_table)?;
         }
         Ok(())
     }
 }
 
-impl BuildSymbolTable for Ctor {
-    fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Ctor { span, name, params, .. } = self;
-        check_name(symbol_table, name, span)?;
+fn build_ctor(ctor: &Ctor, typ: &Ident, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
+    let Ctor { span, name, params, .. } = ctor;
+    check_name(symbol_table, name, span)?;
 
-        let meta = DeclMeta::Ctor { params: params.clone() };
-        symbol_table.idents.insert(name.clone(), meta);
+    let meta = DeclMeta::Ctor { params: params.clone(), typ: typ.clone() };
+    symbol_table.idents.insert(name.clone(), meta);
 
-        Ok(())
-    }
+    Ok(())
 }
 
 impl BuildSymbolTable for Codata {
@@ -140,10 +140,10 @@ impl BuildSymbolTable for Codef {
 
 impl BuildSymbolTable for Let {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Let { span, name, params, .. } = self;
+        let Let { span, name, params, typ, .. } = self;
         check_name(symbol_table, name, span)?;
 
-        let meta = DeclMeta::Let { params: params.clone() };
+        let meta = DeclMeta::Let { params: params.clone(), typ: typ.clone() };
         symbol_table.idents.insert(name.clone(), meta);
 
         Ok(())
@@ -152,10 +152,10 @@ impl BuildSymbolTable for Let {
 
 impl BuildSymbolTable for Extern {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Extern { span, name, params, .. } = self;
+        let Extern { span, name, params, typ, .. } = self;
         check_name(symbol_table, name, span)?;
 
-        let meta = DeclMeta::Extern { params: params.clone() };
+        let meta = DeclMeta::Extern { params: params.clone(), typ: typ.clone() };
         symbol_table.idents.insert(name.clone(), meta);
 
         Ok(())
@@ -164,7 +164,7 @@ impl BuildSymbolTable for Extern {
 
 impl BuildSymbolTable for Infix {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Infix { span, doc: _, attr: _, pattern, rhs } = self;
+        let Infix { span, doc: _, comments: _, attr: _, assoc, precedence, pattern, rhs } = self;
 
         match pattern.rhs.as_slice() {
             [(operator, _)] => {
@@ -175,8 +175,24 @@ impl BuildSymbolTable for Infix {
                     }
                     .into());
                 }
-                let id = expect_ident(rhs.name.clone())?;
-                symbol_table.infix_ops.insert(operator.clone(), id);
+                let name = expect_ident(rhs.name.clone())?;
+                let assoc = match assoc {
+                    Associativity::Left => polarity_lang_ast::Associativity::Left,
+                    Associativity::Right => polarity_lang_ast::Associativity::Right,
+                    Associativity::NonAssoc => polarity_lang_ast::Associativity::NonAssoc,
+                };
+                let precedence = match precedence {
+                    Some(Precedence { span, level }) => u32::try_from(*level).map_err(|_| {
+                        LoweringError::InvalidInfixDeclaration {
+                            message: "The precedence level must be a non-negative integer."
+                                .to_owned(),
+                            span: span.to_miette(),
+                        }
+                    })?,
+                    None => Fixity::DEFAULT_PRECEDENCE,
+                };
+                let fixity = Fixity { assoc, precedence };
+                symbol_table.infix_ops.insert(operator.clone(), OperatorMeta { name, fixity });
             }
             _ => {
                 let err = LoweringError::InvalidInfixDeclaration {
@@ -5,7 +5,7 @@ use url::Url;
 
 use crate::{LoweringError, LoweringResult};
 
-use super::{DeclMeta, SymbolTable};
+use super::{DeclMeta, OperatorMeta, SymbolTable};
 
 impl SymbolTable {
     /// Check whether the identifier already exists in any of the symbol tables.
@@ -46,10 +46,10 @@ impl SymbolTable {
         false
     }
 
-    pub fn lookup_operator(&self, op: &Operator) -> LoweringResult<(&Ident, &Url)> {
+    pub fn lookup_operator(&self, op: &Operator) -> LoweringResult<(&OperatorMeta, &Url)> {
         for (module_uri, symbol_table) in self.map.iter() {
             match symbol_table.infix_ops.get(op) {
-                Some(id) => return Ok((id, module_uri)),
+                Some(meta) => return Ok((meta, module_uri)),
                 None => continue,
             }
         }
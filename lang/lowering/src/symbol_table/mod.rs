@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use decls::*;
 use ident::Ident;
-use polarity_lang_ast::HashMap;
+use polarity_lang_ast::{Fixity, HashMap};
 use polarity_lang_parser::cst::{ident::Operator, *};
 use url::Url;
 
@@ -15,7 +15,7 @@ pub struct ModuleSymbolTable {
     /// The mapping of identifiers to their metadata
     pub idents: HashMap<Ident, DeclMeta>,
     /// The mapping of operators to their definition
-    pub infix_ops: HashMap<Operator, Ident>,
+    pub infix_ops: HashMap<Operator, OperatorMeta>,
 }
 
 /// The symbol table for a module and all of its imported modules.
@@ -31,15 +31,47 @@ impl SymbolTable {
     }
 }
 
+/// An infix operator declared by an infix declaration
+#[derive(Clone, Debug)]
+pub struct OperatorMeta {
+    /// The name on the right-hand side of the declaration
+    pub name: Ident,
+    pub fixity: Fixity,
+}
+
 #[derive(Clone, Debug)]
 pub enum DeclMeta {
-    Data { params: Telescope },
-    Codata { params: Telescope },
-    Def { params: Telescope },
-    Codef { params: Telescope },
-    Ctor { params: Telescope },
-    Dtor { params: Telescope },
-    Let { params: Telescope },
-    Extern { params: Telescope },
+    /// A data type together with its constructors in the order of their declaration
+    Data {
+        params: Telescope,
+        ctors: Vec<Ident>,
+    },
+    Codata {
+        params: Telescope,
+    },
+    Def {
+        params: Telescope,
+    },
+    Codef {
+        params: Telescope,
+    },
+    /// A constructor together with the data type it belongs to
+    Ctor {
+        params: Telescope,
+        typ: Ident,
+    },
+    Dtor {
+        params: Telescope,
+    },
+    /// A let-bound definition together with its return type
+    Let {
+        params: Telescope,
+        typ: Box<exp::Exp>,
+    },
+    /// An extern declaration together with its return type
+    Extern {
+        params: Telescope,
+        typ: Box<exp::Exp>,
+    },
     Note,
 }
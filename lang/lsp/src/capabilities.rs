@@ -4,7 +4,7 @@ pub fn capabilities() -> ServerCapabilities {
     let text_document_sync = {
         let options = TextDocumentSyncOptions {
             open_close: Some(true),
-            change: Some(TextDocumentSyncKind::FULL),
+            change: Some(TextDocumentSyncKind::INCREMENTAL),
             ..Default::default()
         };
         Some(TextDocumentSyncCapability::Options(options))
@@ -18,12 +18,152 @@ pub fn capabilities() -> ServerCapabilities {
 
     let definition_provider = Some(OneOf::Left(true));
 
+    let references_provider = Some(OneOf::Left(true));
+
+    let document_highlight_provider = Some(OneOf::Left(true));
+
+    let rename_provider = Some(OneOf::Right(RenameOptions {
+        prepare_provider: Some(true),
+        work_done_progress_options: Default::default(),
+    }));
+
+    let document_symbol_provider = Some(OneOf::Left(true));
+
+    let workspace_symbol_provider = Some(OneOf::Left(true));
+
+    let semantic_tokens_provider =
+        Some(SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+            legend: polarity_lang_driver::semantic_tokens_legend(),
+            range: Some(true),
+            full: Some(SemanticTokensFullOptions::Bool(true)),
+            ..Default::default()
+        }));
+
+    let inlay_hint_provider = Some(OneOf::Left(true));
+
+    let signature_help_provider = Some(SignatureHelpOptions {
+        trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
+        retrigger_characters: None,
+        work_done_progress_options: Default::default(),
+    });
+
+    let code_lens_provider = Some(CodeLensOptions { resolve_provider: Some(false) });
+
+    let execute_command_provider = Some(ExecuteCommandOptions {
+        commands: super::commands::commands(),
+        work_done_progress_options: Default::default(),
+    });
+
+    let folding_range_provider = Some(FoldingRangeProviderCapability::Simple(true));
+
+    let selection_range_provider = Some(SelectionRangeProviderCapability::Simple(true));
+
+    let workspace = Some(WorkspaceServerCapabilities {
+        workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+            supported: Some(true),
+            change_notifications: Some(OneOf::Left(true)),
+        }),
+        file_operations: None,
+    });
+
+    let call_hierarchy_provider = Some(CallHierarchyServerCapability::Simple(true));
+
+    let diagnostic_provider = Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+        identifier: Some("polarity".to_owned()),
+        inter_file_dependencies: true,
+        workspace_diagnostics: true,
+        work_done_progress_options: WorkDoneProgressOptions { work_done_progress: Some(true) },
+    }));
+
+    let completion_provider = Some(CompletionOptions {
+        trigger_characters: Some(vec![".".to_owned()]),
+        ..Default::default()
+    });
+
     ServerCapabilities {
         text_document_sync,
         hover_provider,
         code_action_provider,
         document_formatting_provider,
         definition_provider,
+        completion_provider,
+        references_provider,
+        document_highlight_provider,
+        rename_provider,
+        document_symbol_provider,
+        workspace_symbol_provider,
+        semantic_tokens_provider,
+        inlay_hint_provider,
+        signature_help_provider,
+        code_lens_provider,
+        execute_command_provider,
+        folding_range_provider,
+        selection_range_provider,
+        workspace,
+        call_hierarchy_provider,
+        diagnostic_provider,
         ..Default::default()
     }
 }
+
+/// The features of the client which determine how the server reports diagnostics and progress
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientFeatures {
+    /// The client pulls diagnostics, so they are not published by the server
+    pub pull_diagnostics: bool,
+    /// The client can be asked to pull the diagnostics of all documents again
+    pub diagnostic_refresh: bool,
+    /// The server may create progress tokens to report the progress of checking modules
+    pub work_done_progress: bool,
+}
+
+impl ClientFeatures {
+    pub fn new(capabilities: &ClientCapabilities) -> Self {
+        let pull_diagnostics = capabilities
+            .text_document
+            .as_ref()
+            .is_some_and(|text_document| text_document.diagnostic.is_some());
+        let diagnostic_refresh = capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.diagnostic.as_ref())
+            .and_then(|diagnostic| diagnostic.refresh_support)
+            .unwrap_or(false);
+        let work_done_progress = capabilities
+            .window
+            .as_ref()
+            .and_then(|window| window.work_done_progress)
+            .unwrap_or(false);
+        ClientFeatures { pull_diagnostics, diagnostic_refresh, work_done_progress }
+    }
+}
+
+/// The type hierarchy, which cannot be announced statically
+///
+/// The server capabilities of `lsp-types` lack the `typeHierarchyProvider` field, so the type
+/// hierarchy is registered dynamically for the documents selected by the client.
+pub fn type_hierarchy_registration() -> Registration {
+    Registration {
+        id: "polarity/typeHierarchy".to_owned(),
+        method: "textDocument/prepareTypeHierarchy".to_owned(),
+        register_options: Some(serde_json::json!({ "documentSelector": null })),
+    }
+}
+
+/// The file watchers registered dynamically once the server is initialized
+///
+/// Changes of modules on disk, e.g. after checking out another branch, are reported to the server
+/// even if the modules are not opened in the editor.
+pub fn watched_files_registration() -> Registration {
+    let options = DidChangeWatchedFilesRegistrationOptions {
+        watchers: vec![FileSystemWatcher {
+            glob_pattern: GlobPattern::String("**/*.pol".to_owned()),
+            kind: None,
+        }],
+    };
+    Registration {
+        id: "polarity/watchedFiles".to_owned(),
+        method: "workspace/didChangeWatchedFiles".to_owned(),
+        register_options: serde_json::to_value(options).ok(),
+    }
+}
@@ -0,0 +1,56 @@
+//! Implementation of the code lenses of the LSP server
+//!
+//! Top-level let bindings without parameters can be evaluated directly from the editor:
+//! `let main` gets a "Run" lens, all other such bindings get a "Normalize" lens.
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use polarity_lang_ast::{Decl, Let};
+
+use super::commands::NORMALIZE_LET_COMMAND;
+use super::conversion::*;
+use super::server::*;
+
+pub async fn code_lens(
+    server: &Server,
+    params: CodeLensParams,
+) -> jsonrpc::Result<Option<Vec<CodeLens>>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("CodeLens request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let uri = text_document.uri.from_lsp();
+    let mut db = server.database.write().await;
+    let Ok(module) = db.ust(&uri).await else {
+        return Ok(None);
+    };
+
+    let lenses = module
+        .decls
+        .iter()
+        .filter_map(|decl| {
+            let Decl::Let(tl_let @ Let { span, name, params, .. }) = decl else {
+                return None;
+            };
+            if !params.is_empty() {
+                return None;
+            }
+            let range = db.span_to_locations(&uri, (*span)?)?;
+            let title = if tl_let.is_main() { "Run" } else { "Normalize" };
+            let command = Command {
+                title: title.to_owned(),
+                command: NORMALIZE_LET_COMMAND.to_owned(),
+                arguments: Some(vec![text_document.uri.as_str().into(), name.id.as_str().into()]),
+            };
+            Some(CodeLens { range, command: Some(command), data: None })
+        })
+        .collect();
+
+    Ok(Some(lenses))
+}
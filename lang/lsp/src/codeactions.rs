@@ -4,7 +4,8 @@ use std::collections::HashMap;
 
 use tower_lsp_server::{jsonrpc, lsp_types::*};
 
-use polarity_lang_driver::{Database, Item, Xfunc};
+use polarity_lang_driver::{Database, HoleAction, Item, QuickFix, Xfunc};
+use polarity_lang_miette_util::codespan::Span;
 
 use super::conversion::*;
 use super::server::*;
@@ -27,18 +28,15 @@ pub async fn code_action(
     let mut db = server.database.write().await;
     let span_start = db.location_to_index(&text_document.uri.from_lsp(), range.start);
     let span_end = db.location_to_index(&text_document.uri.from_lsp(), range.end);
-    let span = span_start.and_then(|start| {
-        span_end.map(|end| polarity_lang_miette_util::codespan::Span { start, end })
-    });
-    let item = if let Some(span) = span {
-        db.item_at_span(&text_document.uri.from_lsp(), span).await
-    } else {
-        None
+    let span = span_start.and_then(|start| span_end.map(|end| Span { start, end }));
+    let Some(span) = span else {
+        return Ok(Some(vec![]));
     };
 
-    if let Some(item) = item {
-        let mut res = vec![];
+    let mut res = quick_fix_actions(&mut db, &text_document, span, &params.context).await;
+    res.extend(hole_actions(&mut db, &text_document, span).await);
 
+    if let Some(item) = db.item_at_span(&text_document.uri.from_lsp(), span).await {
         if let Some(action) = xfunc_action(&mut db, &text_document, &item).await {
             res.push(action);
         }
@@ -46,11 +44,91 @@ pub async fn code_action(
         if let Some(action) = lifting_action(&mut db, &text_document, &item).await {
             res.push(action);
         }
-
-        Ok(Some(res))
-    } else {
-        Ok(Some(vec![]))
     }
+
+    Ok(Some(res))
+}
+
+/// Quick-fixes for the errors in the requested range
+///
+/// Each action carries the diagnostic it resolves, which is found among the diagnostics sent by the client
+/// via its code and range.
+async fn quick_fix_actions(
+    db: &mut Database,
+    text_document: &TextDocumentIdentifier,
+    span: Span,
+    context: &CodeActionContext,
+) -> Vec<CodeActionOrCommand> {
+    let uri = text_document.uri.from_lsp();
+    db.quick_fixes(&uri, span)
+        .await
+        .into_iter()
+        .filter_map(|QuickFix { title, code, span, edits }| {
+            let range = db.span_to_locations(&uri, span);
+            let diagnostics = context
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| {
+                    diagnostic.code == Some(NumberOrString::String(code.clone()))
+                        && Some(diagnostic.range) == range
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            // A fix is skipped if one of its edits no longer fits the document.
+            let edits = edits
+                .into_iter()
+                .map(|edit| {
+                    Some(TextEdit {
+                        range: db.span_to_locations(&uri, edit.span)?,
+                        new_text: edit.text,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            #[allow(clippy::mutable_key_type)]
+            let mut changes = HashMap::new();
+            changes.insert(text_document.uri.clone(), edits);
+
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+                edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+                ..Default::default()
+            }))
+        })
+        .collect()
+}
+
+async fn hole_actions(
+    db: &mut Database,
+    text_document: &TextDocumentIdentifier,
+    span: Span,
+) -> Vec<CodeActionOrCommand> {
+    let Ok(actions) = db.hole_actions(&text_document.uri.from_lsp(), span).await else {
+        return vec![];
+    };
+
+    actions
+        .into_iter()
+        .filter_map(|HoleAction { title, edit }| {
+            let edits = vec![TextEdit {
+                range: db.span_to_locations(&text_document.uri.from_lsp(), edit.span)?,
+                new_text: edit.text,
+            }];
+
+            #[allow(clippy::mutable_key_type)]
+            let mut changes = HashMap::new();
+            changes.insert(text_document.uri.clone(), edits);
+
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title,
+                kind: Some(CodeActionKind::REFACTOR_REWRITE),
+                edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+                ..Default::default()
+            }))
+        })
+        .collect()
 }
 
 async fn xfunc_action(
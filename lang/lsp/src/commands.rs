@@ -0,0 +1,135 @@
+//! Implementation of the commands which can be executed via `workspace/executeCommand`
+
+use serde_json::Value;
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use polarity_lang_driver::{AppErrors, Database};
+use polarity_lang_miette_util::codespan::Span;
+use polarity_lang_printer::Print;
+use url::Url;
+
+use super::conversion::*;
+use super::server::*;
+
+/// Normalize a top-level let binding without parameters
+///
+/// Arguments: the URI of the module and the name of the let binding.
+/// The normal form is shown to the user and returned as a string.
+pub const NORMALIZE_LET_COMMAND: &str = "polarity.normalizeLet";
+
+/// Normalize the smallest expression which encloses a range
+///
+/// Arguments: the URI of the module and the range.
+/// Local variables in scope of the expression are not unfolded.
+/// The normal form is shown to the user and returned as a string.
+pub const NORMALIZE_COMMAND: &str = "polarity.normalize";
+
+/// Infer the type of the smallest expression which encloses a range
+///
+/// Arguments: the URI of the module and the range.
+/// The type is shown to the user and returned as a string.
+pub const INFER_TYPE_COMMAND: &str = "polarity.inferType";
+
+/// The commands supported by the server
+pub fn commands() -> Vec<String> {
+    vec![
+        NORMALIZE_LET_COMMAND.to_owned(),
+        NORMALIZE_COMMAND.to_owned(),
+        INFER_TYPE_COMMAND.to_owned(),
+    ]
+}
+
+pub async fn execute_command(
+    server: &Server,
+    params: ExecuteCommandParams,
+) -> jsonrpc::Result<Option<Value>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("ExecuteCommand request: {}", params.command))
+        .await;
+
+    match params.command.as_str() {
+        NORMALIZE_LET_COMMAND => normalize_let(server, params.arguments).await,
+        NORMALIZE_COMMAND => normalize(server, params.arguments).await,
+        INFER_TYPE_COMMAND => infer_type(server, params.arguments).await,
+        command => Err(jsonrpc::Error::invalid_params(format!("Unknown command: {command}"))),
+    }
+}
+
+async fn normalize_let(server: &Server, arguments: Vec<Value>) -> jsonrpc::Result<Option<Value>> {
+    let (uri, name): (Uri, String) = parse_arguments(arguments)?;
+
+    let mut db = server.database.write().await;
+    let nf = db.normalize_let(&uri.from_lsp(), &name).await.map_err(app_errors)?;
+    drop(db);
+    let Some(nf) = nf else {
+        return Err(jsonrpc::Error::invalid_params(format!(
+            "{name} is not a top-level let binding without parameters"
+        )));
+    };
+
+    let nf = nf.print_to_string(None);
+    server.client.show_message(MessageType::INFO, format!("{name} normalizes to:\n{nf}")).await;
+    Ok(Some(Value::String(nf)))
+}
+
+async fn normalize(server: &Server, arguments: Vec<Value>) -> jsonrpc::Result<Option<Value>> {
+    let (uri, range): (Uri, Range) = parse_arguments(arguments)?;
+    let uri = uri.from_lsp();
+
+    let mut db = server.database.write().await;
+    let span = selection_span(&db, &uri, range)?;
+    let nf = db.normalize_selection(&uri, span).await.map_err(app_errors)?;
+    drop(db);
+    let Some(nf) = nf else {
+        return Err(no_expression());
+    };
+
+    let nf = nf.print_to_string(None);
+    server
+        .client
+        .show_message(MessageType::INFO, format!("The selection normalizes to:\n{nf}"))
+        .await;
+    Ok(Some(Value::String(nf)))
+}
+
+async fn infer_type(server: &Server, arguments: Vec<Value>) -> jsonrpc::Result<Option<Value>> {
+    let (uri, range): (Uri, Range) = parse_arguments(arguments)?;
+    let uri = uri.from_lsp();
+
+    let mut db = server.database.write().await;
+    let span = selection_span(&db, &uri, range)?;
+    let typ = db.infer_selection_type(&uri, span).await.map_err(app_errors)?;
+    drop(db);
+    let Some(typ) = typ else {
+        return Err(no_expression());
+    };
+
+    let typ = typ.print_to_string(None);
+    server.client.show_message(MessageType::INFO, format!("The selection has type:\n{typ}")).await;
+    Ok(Some(Value::String(typ)))
+}
+
+fn selection_span(db: &Database, uri: &Url, range: Range) -> jsonrpc::Result<Span> {
+    let start = db.location_to_index(uri, range.start);
+    let end = db.location_to_index(uri, range.end);
+    match (start, end) {
+        (Some(start), Some(end)) => Ok(Span { start, end }),
+        _ => Err(jsonrpc::Error::invalid_params("The range lies outside of the module")),
+    }
+}
+
+fn no_expression() -> jsonrpc::Error {
+    jsonrpc::Error::invalid_params("The selection is not part of an expression")
+}
+
+fn app_errors(errs: AppErrors) -> jsonrpc::Error {
+    jsonrpc::Error::invalid_params(
+        errs.into_errors().iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"),
+    )
+}
+
+fn parse_arguments<T: serde::de::DeserializeOwned>(arguments: Vec<Value>) -> jsonrpc::Result<T> {
+    serde_json::from_value(Value::Array(arguments))
+        .map_err(|err| jsonrpc::Error::invalid_params(format!("Invalid arguments: {err}")))
+}
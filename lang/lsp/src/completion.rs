@@ -0,0 +1,31 @@
+//! Implementation of the code completion functionality of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn completion(
+    server: &Server,
+    params: CompletionParams,
+) -> jsonrpc::Result<Option<CompletionResponse>> {
+    let pos_params = params.text_document_position;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("Completion request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let info = db.location_to_index(&text_document.uri.from_lsp(), pos);
+    let items = match info {
+        Some(idx) => db.completions_at_index(&text_document.uri.from_lsp(), idx).await.ok(),
+        None => None,
+    };
+    Ok(items.map(CompletionResponse::Array))
+}
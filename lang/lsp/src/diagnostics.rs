@@ -1,3 +1,5 @@
+use std::collections::hash_map::Entry;
+
 use lsp_types::NumberOrString;
 use miette::Diagnostic;
 use miette::SourceSpan;
@@ -5,16 +7,32 @@ use tower_lsp_server::lsp_types;
 use url::Url;
 
 use polarity_lang_driver::Database;
-use polarity_lang_driver::{AppErrors, AppResult};
+use polarity_lang_driver::{AppErrors, AppResult, AppWarning};
 use polarity_lang_miette_util::FromMiette;
 
 use crate::conversion::ToLsp;
 
 pub trait Diagnostics {
-    /// Compute the diagnostics for the given URI and all of its reverse dependencies.
+    /// Compute the diagnostics for the given URI, all of its reverse dependencies and the modules it imports.
     async fn diagnostics(&mut self, uri: &Url, result: AppResult) -> DiagnosticsPerUri;
 
+    /// Compute the diagnostics for the given URI only, by checking it if necessary.
+    async fn module_diagnostics(&mut self, uri: &Url) -> Vec<lsp_types::Diagnostic>;
+
+    /// Compute the diagnostics for the given URI from the result of checking it, together with its warnings.
+    async fn result_diagnostics(
+        &mut self,
+        uri: &Url,
+        result: AppResult,
+    ) -> Vec<lsp_types::Diagnostic>;
+
     fn error_diagnostics(&self, uri: &Url, errors: AppErrors) -> Vec<lsp_types::Diagnostic>;
+
+    fn warning_diagnostics(
+        &self,
+        uri: &Url,
+        warnings: Vec<AppWarning>,
+    ) -> Vec<lsp_types::Diagnostic>;
 }
 
 pub type DiagnosticsPerUri = polarity_lang_ast::HashMap<Url, Vec<lsp_types::Diagnostic>>;
@@ -26,23 +44,44 @@ impl Diagnostics for Database {
         let mut diagnostics = polarity_lang_ast::HashMap::default();
 
         for uri in rev_deps {
-            let mut diagnostics_for_uri = vec![];
-            let ast = self.ast(&uri).await;
-            if let Err(err) = ast {
-                diagnostics_for_uri.extend(self.error_diagnostics(&uri, err));
-            }
+            let diagnostics_for_uri = self.module_diagnostics(&uri).await;
             diagnostics.insert(uri, diagnostics_for_uri);
         }
 
-        if let Err(err) = result {
-            diagnostics.insert(uri.clone(), self.error_diagnostics(uri, err));
-        } else {
-            diagnostics.insert(uri.clone(), vec![]);
+        let diagnostics_for_uri = self.result_diagnostics(uri, result).await;
+        diagnostics.insert(uri.clone(), diagnostics_for_uri);
+
+        // Whether the declarations of the imported modules are used may have changed as well.
+        let deps: Vec<_> = self.deps.get(uri).cloned().unwrap_or_default();
+        for dep in deps {
+            if let Entry::Vacant(entry) = diagnostics.entry(dep) {
+                let diagnostics_for_dep = self.module_diagnostics(entry.key()).await;
+                entry.insert(diagnostics_for_dep);
+            }
         }
 
         diagnostics
     }
 
+    async fn module_diagnostics(&mut self, uri: &Url) -> Vec<lsp_types::Diagnostic> {
+        let result = self.ast(uri).await.map(|_| ());
+        self.result_diagnostics(uri, result).await
+    }
+
+    async fn result_diagnostics(
+        &mut self,
+        uri: &Url,
+        result: AppResult,
+    ) -> Vec<lsp_types::Diagnostic> {
+        let mut diagnostics = vec![];
+        if let Err(err) = result {
+            diagnostics.extend(self.error_diagnostics(uri, err));
+        }
+        let warnings = self.warnings(uri).await;
+        diagnostics.extend(self.warning_diagnostics(uri, warnings));
+        diagnostics
+    }
+
     fn error_diagnostics(&self, uri: &Url, errors: AppErrors) -> Vec<lsp_types::Diagnostic> {
         errors
             .into_errors()
@@ -77,6 +116,40 @@ impl Diagnostics for Database {
             })
             .collect()
     }
+
+    fn warning_diagnostics(
+        &self,
+        uri: &Url,
+        warnings: Vec<AppWarning>,
+    ) -> Vec<lsp_types::Diagnostic> {
+        warnings
+            .into_iter()
+            .map(|warning| {
+                let range = get_span(&warning)
+                    .and_then(|x| self.span_to_locations(uri, x.from_miette()))
+                    .unwrap_or_default();
+
+                // Unused code is additionally marked so that editors can render it faded out.
+                let tags =
+                    warning.is_unnecessary().then(|| vec![lsp_types::DiagnosticTag::UNNECESSARY]);
+
+                lsp_types::Diagnostic {
+                    range,
+                    message: warning.to_string(),
+                    severity: match warning.severity() {
+                        Some(sev) => Some(sev.to_lsp()),
+                        None => Some(lsp_types::DiagnosticSeverity::WARNING),
+                    },
+                    code: warning.code().map(|x| NumberOrString::String(format!("{x}"))),
+                    code_description: None,
+                    source: None,
+                    related_information: None,
+                    tags,
+                    data: None,
+                }
+            })
+            .collect()
+    }
 }
 
 fn get_span<T: Diagnostic>(err: &T) -> Option<SourceSpan> {
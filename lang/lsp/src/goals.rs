@@ -0,0 +1,80 @@
+//! Implementation of the custom `polarity/goals` request of the LSP server
+//!
+//! The request lists the unsolved holes of a document together with their expected types
+//! and local contexts, so that editors can show them in a goal panel.
+
+use serde_derive::{Deserialize, Serialize};
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use polarity_lang_driver::Goal;
+use polarity_lang_printer::Print;
+
+use super::conversion::*;
+use super::server::*;
+
+pub const GOALS_METHOD: &str = "polarity/goals";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalsParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalInfo {
+    pub range: Range,
+    /// The name of the metavariable of the hole, e.g. `?3`
+    pub name: String,
+    /// The expected type of the hole
+    pub goal: String,
+    /// The variables in scope at the hole, starting with the innermost one
+    pub context: Vec<ContextEntry>,
+    /// The goal and its context, formatted like a goal buffer
+    pub rendered: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContextEntry {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+}
+
+pub async fn goals(server: &Server, params: GoalsParams) -> jsonrpc::Result<Vec<GoalInfo>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(MessageType::INFO, format!("Goals request: {}", text_document.uri.from_lsp()))
+        .await;
+
+    let uri = text_document.uri.from_lsp();
+    let mut db = server.database.write().await;
+    let goals = db.goals(&uri).await.map_err(|errs| {
+        let message =
+            errs.into_errors().iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+        jsonrpc::Error::invalid_params(message)
+    })?;
+
+    Ok(goals
+        .iter()
+        .filter_map(|goal| {
+            let Goal { span, goal: typ, vars, .. } = goal;
+            Some(GoalInfo {
+                range: db.span_to_locations(&uri, *span)?,
+                name: goal.name(),
+                goal: typ.as_ref().map_or("?".to_owned(), |typ| typ.print_to_string(None)),
+                context: vars
+                    .iter()
+                    .rev()
+                    .map(|(name, typ)| ContextEntry {
+                        name: name.clone(),
+                        typ: typ.print_to_string(None),
+                    })
+                    .collect(),
+                rendered: goal.print_to_string(None),
+            })
+        })
+        .collect())
+}
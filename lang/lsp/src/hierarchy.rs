@@ -0,0 +1,166 @@
+//! Implementation of the call hierarchy and type hierarchy of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use polarity_lang_ast::IdBound;
+use polarity_lang_driver::HierarchyItem;
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn prepare_call_hierarchy(
+    server: &Server,
+    params: CallHierarchyPrepareParams,
+) -> jsonrpc::Result<Option<Vec<CallHierarchyItem>>> {
+    let pos_params = params.text_document_position_params;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("PrepareCallHierarchy request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let uri = text_document.uri.from_lsp();
+    let mut db = server.database.write().await;
+    let item = match db.location_to_index(&uri, pos_params.position) {
+        Some(idx) => db.call_hierarchy_item(&uri, idx).await,
+        None => None,
+    };
+    Ok(item.map(|item| vec![call_hierarchy_item(item)]))
+}
+
+pub async fn incoming_calls(
+    server: &Server,
+    params: CallHierarchyIncomingCallsParams,
+) -> jsonrpc::Result<Option<Vec<CallHierarchyIncomingCall>>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("IncomingCalls request: {}", params.item.name))
+        .await;
+
+    let name = item_name(&params.item.uri, &params.item.name);
+    let mut db = server.database.write().await;
+    let calls = db
+        .incoming_calls(&name)
+        .await
+        .into_iter()
+        .map(|(item, from_ranges)| CallHierarchyIncomingCall {
+            from: call_hierarchy_item(item),
+            from_ranges,
+        })
+        .collect();
+    Ok(Some(calls))
+}
+
+pub async fn outgoing_calls(
+    server: &Server,
+    params: CallHierarchyOutgoingCallsParams,
+) -> jsonrpc::Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("OutgoingCalls request: {}", params.item.name))
+        .await;
+
+    let name = item_name(&params.item.uri, &params.item.name);
+    let mut db = server.database.write().await;
+    let calls = db
+        .outgoing_calls(&name)
+        .await
+        .into_iter()
+        .map(|(item, from_ranges)| CallHierarchyOutgoingCall {
+            to: call_hierarchy_item(item),
+            from_ranges,
+        })
+        .collect();
+    Ok(Some(calls))
+}
+
+pub async fn prepare_type_hierarchy(
+    server: &Server,
+    params: TypeHierarchyPrepareParams,
+) -> jsonrpc::Result<Option<Vec<TypeHierarchyItem>>> {
+    let pos_params = params.text_document_position_params;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("PrepareTypeHierarchy request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let uri = text_document.uri.from_lsp();
+    let mut db = server.database.write().await;
+    let item = match db.location_to_index(&uri, pos_params.position) {
+        Some(idx) => db.type_hierarchy_item(&uri, idx).await,
+        None => None,
+    };
+    Ok(item.map(|item| vec![type_hierarchy_item(item)]))
+}
+
+pub async fn supertypes(
+    server: &Server,
+    params: TypeHierarchySupertypesParams,
+) -> jsonrpc::Result<Option<Vec<TypeHierarchyItem>>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("Supertypes request: {}", params.item.name))
+        .await;
+
+    let name = item_name(&params.item.uri, &params.item.name);
+    let mut db = server.database.write().await;
+    let items = db.supertypes(&name).await.into_iter().map(type_hierarchy_item).collect();
+    Ok(Some(items))
+}
+
+pub async fn subtypes(
+    server: &Server,
+    params: TypeHierarchySubtypesParams,
+) -> jsonrpc::Result<Option<Vec<TypeHierarchyItem>>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("Subtypes request: {}", params.item.name))
+        .await;
+
+    let name = item_name(&params.item.uri, &params.item.name);
+    let mut db = server.database.write().await;
+    let items = db.subtypes(&name).await.into_iter().map(type_hierarchy_item).collect();
+    Ok(Some(items))
+}
+
+/// Recover the declaration from an item which has been sent back by the client
+fn item_name(uri: &Uri, name: &str) -> IdBound {
+    IdBound { span: None, id: name.to_owned(), uri: uri.from_lsp() }
+}
+
+fn call_hierarchy_item(item: HierarchyItem) -> CallHierarchyItem {
+    let HierarchyItem { name, kind, detail, range, selection_range } = item;
+    CallHierarchyItem {
+        name: name.id,
+        kind,
+        tags: None,
+        detail,
+        uri: name.uri.to_lsp(),
+        range,
+        selection_range,
+        data: None,
+    }
+}
+
+fn type_hierarchy_item(item: HierarchyItem) -> TypeHierarchyItem {
+    let HierarchyItem { name, kind, detail, range, selection_range } = item;
+    TypeHierarchyItem {
+        name: name.id,
+        kind,
+        tags: None,
+        detail,
+        uri: name.uri.to_lsp(),
+        range,
+        selection_range,
+        data: None,
+    }
+}
@@ -0,0 +1,32 @@
+//! Implementation of the inlay-hint functionality of the LSP server
+
+use polarity_lang_miette_util::codespan::Span;
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn inlay_hint(
+    server: &Server,
+    params: InlayHintParams,
+) -> jsonrpc::Result<Option<Vec<InlayHint>>> {
+    let text_document = params.text_document;
+    let range = params.range;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("InlayHint request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let (Some(start), Some(end)) =
+        (db.location_to_index(&uri, range.start), db.location_to_index(&uri, range.end))
+    else {
+        return Ok(None);
+    };
+    Ok(db.inlay_hints(&uri, Span { start, end }).await.ok())
+}
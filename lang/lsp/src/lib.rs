@@ -1,10 +1,35 @@
 mod capabilities;
+mod code_lens;
 mod codeactions;
+mod commands;
+mod completion;
 mod conversion;
 mod diagnostics;
 mod format;
+mod goals;
 mod gotodefinition;
+mod hierarchy;
 mod hover;
+mod inlay_hints;
+mod progress;
+mod pull_diagnostics;
+mod ranges;
+mod references;
+mod rename;
+mod semantic_tokens;
 mod server;
+mod signature_help;
+mod symbols;
 
+use tower_lsp_server::{Client, ClientSocket, LspService};
+
+pub use goals::{ContextEntry, GOALS_METHOD, GoalInfo, GoalsParams};
 pub use server::*;
+
+/// Create the LSP service, including the custom requests of the Polarity language server
+pub fn service<F>(init: F) -> (LspService<Server>, ClientSocket)
+where
+    F: FnOnce(Client) -> Server,
+{
+    LspService::build(init).custom_method(GOALS_METHOD, Server::goals).finish()
+}
@@ -0,0 +1,83 @@
+//! Work-done progress reported to the client while checking several modules
+
+use std::sync::atomic::Ordering;
+
+use tower_lsp_server::lsp_types::request::WorkDoneProgressCreate;
+use tower_lsp_server::lsp_types::*;
+use tower_lsp_server::{Bounded, NotCancellable, OngoingProgress};
+use url::Url;
+
+use super::server::*;
+
+/// The progress of checking a fixed number of modules
+///
+/// Nothing is reported if the client provided no token and does not support tokens created by the server.
+pub struct CheckProgress {
+    progress: Option<OngoingProgress<Bounded, NotCancellable>>,
+    total: usize,
+}
+
+impl Server {
+    /// Begin to report the progress of checking `total` many modules
+    ///
+    /// The token sent by the client with a request is preferred over creating a new one.
+    /// A single module is checked quickly enough that no progress is reported for it.
+    pub(crate) async fn begin_progress(
+        &self,
+        title: &str,
+        token: Option<ProgressToken>,
+        total: usize,
+    ) -> CheckProgress {
+        if total <= 1 {
+            return CheckProgress { progress: None, total };
+        }
+        let token = match token {
+            Some(token) => Some(token),
+            None if self.client_features().work_done_progress => {
+                let id = self.progress_tokens.fetch_add(1, Ordering::Relaxed);
+                let token = ProgressToken::String(format!("polarity/check/{id}"));
+                let params = WorkDoneProgressCreateParams { token: token.clone() };
+                self.client
+                    .send_request::<WorkDoneProgressCreate>(params)
+                    .await
+                    .is_ok()
+                    .then_some(token)
+            }
+            None => None,
+        };
+        let progress = match token {
+            Some(token) => Some(
+                self.client
+                    .progress(token, title)
+                    .with_message(format!("0/{total} modules"))
+                    .with_percentage(0)
+                    .begin()
+                    .await,
+            ),
+            None => None,
+        };
+        CheckProgress { progress, total }
+    }
+}
+
+impl CheckProgress {
+    /// Report that `done` many modules have been checked and the given module is next
+    pub async fn report(&self, done: usize, uri: &Url) {
+        if let Some(progress) = &self.progress {
+            let name = uri.path_segments().and_then(|mut segments| segments.next_back());
+            let message =
+                format!("{done}/{} modules: {}", self.total, name.unwrap_or(uri.as_str()));
+            let percentage = (done * 100 / self.total.max(1)) as u32;
+            progress.report_with_message(message, percentage).await;
+        }
+    }
+
+    pub async fn finish(self, message: Option<&str>) {
+        if let Some(progress) = self.progress {
+            match message {
+                Some(message) => progress.finish_with_message(message).await,
+                None => progress.finish().await,
+            }
+        }
+    }
+}
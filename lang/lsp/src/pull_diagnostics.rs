@@ -0,0 +1,135 @@
+//! Implementation of the diagnostics pulled by the client
+//!
+//! Each report carries a result id derived from the diagnostics themselves,
+//! so that unchanged diagnostics need not be sent again.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde_json::json;
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+use url::Url;
+
+use super::conversion::*;
+use super::diagnostics::*;
+use super::server::*;
+
+pub async fn document_diagnostic(
+    server: &Server,
+    params: DocumentDiagnosticParams,
+) -> jsonrpc::Result<DocumentDiagnosticReportResult> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("DocumentDiagnostic request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let uri = text_document.uri.from_lsp();
+    let diagnostics = server.database.write().await.module_diagnostics(&uri).await;
+    let report = match report(diagnostics, params.previous_result_id.as_deref()) {
+        Ok(full_document_diagnostic_report) => {
+            DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report,
+            })
+        }
+        Err(unchanged_document_diagnostic_report) => {
+            DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                related_documents: None,
+                unchanged_document_diagnostic_report,
+            })
+        }
+    };
+    Ok(DocumentDiagnosticReportResult::Report(report))
+}
+
+/// Check all modules of the workspace
+///
+/// The database is locked for each module separately. If a document is edited in the meantime,
+/// the request is cancelled and the client is asked to send it again.
+pub async fn workspace_diagnostic(
+    server: &Server,
+    params: WorkspaceDiagnosticParams,
+) -> jsonrpc::Result<WorkspaceDiagnosticReportResult> {
+    server.client.log_message(MessageType::INFO, "WorkspaceDiagnostic request").await;
+
+    let revision = server.revision();
+    let previous_result_ids: HashMap<Url, String> = params
+        .previous_result_ids
+        .into_iter()
+        .map(|PreviousResultId { uri, value }| (uri.from_lsp(), value))
+        .collect();
+
+    let modules = server.workspace_modules().await;
+    let progress = server
+        .begin_progress(
+            "Checking workspace",
+            params.work_done_progress_params.work_done_token,
+            modules.len(),
+        )
+        .await;
+
+    let mut items = vec![];
+    for (done, uri) in modules.into_iter().enumerate() {
+        if server.is_stale(revision) {
+            progress.finish(Some("Cancelled")).await;
+            return Err(server_cancelled());
+        }
+        progress.report(done, &uri).await;
+        let diagnostics = server.database.write().await.module_diagnostics(&uri).await;
+        let previous_result_id = previous_result_ids.get(&uri).map(String::as_str);
+        let item = match report(diagnostics, previous_result_id) {
+            Ok(full_document_diagnostic_report) => {
+                WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                    uri: uri.to_lsp(),
+                    version: None,
+                    full_document_diagnostic_report,
+                })
+            }
+            Err(unchanged_document_diagnostic_report) => {
+                WorkspaceDocumentDiagnosticReport::Unchanged(
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri: uri.to_lsp(),
+                        version: None,
+                        unchanged_document_diagnostic_report,
+                    },
+                )
+            }
+        };
+        items.push(item);
+    }
+    progress.finish(None).await;
+
+    Ok(WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items }))
+}
+
+/// The full report, or the unchanged report if the client already has these diagnostics
+fn report(
+    diagnostics: Vec<Diagnostic>,
+    previous_result_id: Option<&str>,
+) -> Result<FullDocumentDiagnosticReport, UnchangedDocumentDiagnosticReport> {
+    let result_id = result_id(&diagnostics);
+    if previous_result_id == Some(result_id.as_str()) {
+        return Err(UnchangedDocumentDiagnosticReport { result_id });
+    }
+    Ok(FullDocumentDiagnosticReport { result_id: Some(result_id), items: diagnostics })
+}
+
+fn result_id(diagnostics: &[Diagnostic]) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(diagnostics).unwrap_or_default().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// The `ServerCancelled` error of LSP 3.17, which asks the client to send the request again
+fn server_cancelled() -> jsonrpc::Error {
+    jsonrpc::Error {
+        code: jsonrpc::ErrorCode::ServerError(-32802),
+        message: "The workspace was edited while it was checked".into(),
+        data: Some(json!({ "retriggerRequest": true })),
+    }
+}
@@ -0,0 +1,51 @@
+//! Implementation of the folding-range and selection-range functionality of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn folding_range(
+    server: &Server,
+    params: FoldingRangeParams,
+) -> jsonrpc::Result<Option<Vec<FoldingRange>>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("FoldingRange request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    Ok(db.folding_ranges(&text_document.uri.from_lsp()).await.ok())
+}
+
+pub async fn selection_range(
+    server: &Server,
+    params: SelectionRangeParams,
+) -> jsonrpc::Result<Option<Vec<SelectionRange>>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("SelectionRange request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let uri = text_document.uri.from_lsp();
+    let Some(positions) = params
+        .positions
+        .into_iter()
+        .map(|pos| db.location_to_index(&uri, pos))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+    Ok(db.selection_ranges(&uri, &positions).await.ok())
+}
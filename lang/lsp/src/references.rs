@@ -0,0 +1,81 @@
+//! Implementation of the find-references and document-highlight functionality of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn references(
+    server: &Server,
+    params: ReferenceParams,
+) -> jsonrpc::Result<Option<Vec<Location>>> {
+    let pos_params = params.text_document_position;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("References request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let info = db.location_to_index(&text_document.uri.from_lsp(), pos);
+    let reference = match info {
+        Some(idx) => db.reference_at_index(&text_document.uri.from_lsp(), idx).await,
+        None => None,
+    };
+    let Some(reference) = reference else {
+        return Ok(None);
+    };
+
+    let references = db.references(&reference.name, params.context.include_declaration).await;
+    let locations = references
+        .into_iter()
+        .filter_map(|(uri, span)| {
+            let range = db.span_to_locations(&uri, span)?;
+            Some(Location { uri: uri.to_lsp(), range })
+        })
+        .collect();
+    Ok(Some(locations))
+}
+
+pub async fn document_highlight(
+    server: &Server,
+    params: DocumentHighlightParams,
+) -> jsonrpc::Result<Option<Vec<DocumentHighlight>>> {
+    let pos_params = params.text_document_position_params;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("DocumentHighlight request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let info = db.location_to_index(&text_document.uri.from_lsp(), pos);
+    let reference = match info {
+        Some(idx) => db.reference_at_index(&text_document.uri.from_lsp(), idx).await,
+        None => None,
+    };
+    let Some(reference) = reference else {
+        return Ok(None);
+    };
+
+    let uri = text_document.uri.from_lsp();
+    let references = db.references_in_module(&uri, &reference.name, true).await;
+    let highlights = references
+        .into_iter()
+        .filter_map(|span| {
+            let range = db.span_to_locations(&uri, span)?;
+            Some(DocumentHighlight { range, kind: Some(DocumentHighlightKind::TEXT) })
+        })
+        .collect();
+    Ok(Some(highlights))
+}
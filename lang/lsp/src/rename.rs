@@ -0,0 +1,79 @@
+//! Implementation of the rename functionality of the LSP server
+
+use std::collections::HashMap;
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn prepare_rename(
+    server: &Server,
+    params: TextDocumentPositionParams,
+) -> jsonrpc::Result<Option<PrepareRenameResponse>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("PrepareRename request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = params.position;
+    let mut db = server.database.write().await;
+    let info = db.location_to_index(&text_document.uri.from_lsp(), pos);
+    let info = match info {
+        Some(idx) => db.prepare_rename(&text_document.uri.from_lsp(), idx).await,
+        None => None,
+    };
+    let res = info.and_then(|(span, placeholder)| {
+        let range = db.span_to_locations(&text_document.uri.from_lsp(), span)?;
+        Some(PrepareRenameResponse::RangeWithPlaceholder { range, placeholder })
+    });
+    Ok(res)
+}
+
+pub async fn rename(
+    server: &Server,
+    params: RenameParams,
+) -> jsonrpc::Result<Option<WorkspaceEdit>> {
+    let pos_params = params.text_document_position;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(MessageType::INFO, format!("Rename request: {}", text_document.uri.from_lsp()))
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let Some(idx) = db.location_to_index(&text_document.uri.from_lsp(), pos) else {
+        return Ok(None);
+    };
+    let edits =
+        db.rename(&text_document.uri.from_lsp(), idx, &params.new_name).await.map_err(|errs| {
+            let message =
+                errs.into_errors().iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+            jsonrpc::Error::invalid_params(message)
+        })?;
+    let Some(edits) = edits else {
+        return Ok(None);
+    };
+
+    #[allow(clippy::mutable_key_type)]
+    let mut changes = HashMap::new();
+    for (uri, edits) in edits {
+        let edits = edits
+            .into_iter()
+            .filter_map(|edit| {
+                let range = db.span_to_locations(&uri, edit.span)?;
+                Some(TextEdit { range, new_text: edit.text })
+            })
+            .collect();
+        changes.insert(uri.to_lsp(), edits);
+    }
+
+    Ok(Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }))
+}
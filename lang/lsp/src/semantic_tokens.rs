@@ -0,0 +1,54 @@
+//! Implementation of the semantic-tokens functionality of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn semantic_tokens_full(
+    server: &Server,
+    params: SemanticTokensParams,
+) -> jsonrpc::Result<Option<SemanticTokensResult>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("SemanticTokensFull request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let tokens = db.semantic_tokens(&text_document.uri.from_lsp(), None).await.ok();
+    Ok(tokens.map(|data| SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data })))
+}
+
+pub async fn semantic_tokens_range(
+    server: &Server,
+    params: SemanticTokensRangeParams,
+) -> jsonrpc::Result<Option<SemanticTokensRangeResult>> {
+    let text_document = params.text_document;
+    let range = params.range;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("SemanticTokensRange request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let span_start = db.location_to_index(&text_document.uri.from_lsp(), range.start);
+    let span_end = db.location_to_index(&text_document.uri.from_lsp(), range.end);
+    let span = span_start.and_then(|start| {
+        span_end.map(|end| polarity_lang_miette_util::codespan::Span { start, end })
+    });
+    let Some(span) = span else {
+        return Ok(None);
+    };
+    let tokens = db.semantic_tokens(&text_document.uri.from_lsp(), Some(span)).await.ok();
+    Ok(tokens
+        .map(|data| SemanticTokensRangeResult::Tokens(SemanticTokens { result_id: None, data })))
+}
@@ -1,19 +1,32 @@
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use async_lock::RwLock;
 use tower_lsp_server::jsonrpc::Result;
 use tower_lsp_server::{LanguageServer, jsonrpc, lsp_types::*};
 
 use polarity_lang_driver::Database;
 #[cfg(not(target_arch = "wasm32"))]
-use polarity_lang_driver::{FileSource, FileSystemSource, InMemorySource};
+use polarity_lang_driver::{
+    FileSource, FileSystemSource, InMemorySource, WorkspaceSource, folder_root,
+};
+use url::Url;
 
 use crate::conversion::{FromLsp, ToLsp};
 
 use super::capabilities::*;
 use super::diagnostics::*;
+use super::goals::{GoalInfo, GoalsParams};
 
 pub struct Server {
     pub client: tower_lsp_server::Client,
     pub database: RwLock<Database>,
+    /// The features of the client, known once the server is initialized
+    client_features: OnceLock<ClientFeatures>,
+    /// Counts the edits, so that checks which started before an edit can detect that they are stale
+    revision: AtomicU64,
+    /// Used to create unique tokens for progress reports initiated by the server
+    pub(crate) progress_tokens: AtomicU64,
 }
 
 impl Server {
@@ -23,25 +36,52 @@ impl Server {
     }
 
     pub fn with_database(client: tower_lsp_server::Client, database: Database) -> Self {
-        Server { client, database: RwLock::new(database) }
+        Server {
+            client,
+            database: RwLock::new(database),
+            client_features: OnceLock::new(),
+            revision: AtomicU64::new(0),
+            progress_tokens: AtomicU64::new(0),
+        }
+    }
+
+    /// Handler of the custom `polarity/goals` request
+    pub async fn goals(&self, params: GoalsParams) -> jsonrpc::Result<Vec<GoalInfo>> {
+        super::goals::goals(self, params).await
     }
 }
 
 impl LanguageServer for Server {
     async fn initialize(&self, params: InitializeParams) -> jsonrpc::Result<InitializeResult> {
         let capabilities = capabilities();
+        let _ = self.client_features.set(ClientFeatures::new(&params.capabilities));
         #[cfg(not(target_arch = "wasm32"))]
-        // FIXME: Use `workspace_folders` instead of `root_uri`.
-        // `root_uri` is deprecated in in favor of `workspace_folders`, see:
-        // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#initialize
-        #[allow(deprecated)]
-        if let Some(root_uri) = params.root_uri {
-            let root_path =
-                root_uri.from_lsp().to_file_path().map_err(|_| jsonrpc::Error::internal_error())?;
-            let source = InMemorySource::new().fallback_to(FileSystemSource::new(root_path));
-            let mut database = self.database.write().await;
-            let source_mut = database.file_source_mut();
-            *source_mut = Box::new(source);
+        {
+            // Older clients only send the deprecated `root_uri` instead of `workspace_folders`.
+            #[allow(deprecated)]
+            let roots: Vec<Uri> = match params.workspace_folders {
+                Some(folders) => folders.into_iter().map(|folder| folder.uri).collect(),
+                None => params.root_uri.into_iter().collect(),
+            };
+            // Files outside of the workspace folders, such as imports from other folders on disk
+            let default = InMemorySource::new()
+                .fallback_to(FileSystemSource::new(std::env::current_dir().unwrap_or_default()));
+            let mut source = WorkspaceSource::new(default);
+            let mut errors = vec![];
+            for root in roots {
+                let root = root.from_lsp();
+                let Some(folder_source) = folder_source(&root) else {
+                    errors.push(format!("Workspace folder is not on the file system: {root}"));
+                    continue;
+                };
+                if let Err(err) = source.add_folder(&root, folder_source).await {
+                    errors.push(format!("Failed to add workspace folder {root}: {err}"));
+                }
+            }
+            *self.database.write().await.file_source_mut() = Box::new(source);
+            for error in errors {
+                self.client.log_message(MessageType::ERROR, error).await;
+            }
         }
         // prevent unused variable warning when compiled for wasm
         let _ = params;
@@ -50,6 +90,88 @@ impl LanguageServer for Server {
 
     async fn initialized(&self, _: InitializedParams) {
         self.client.log_message(MessageType::INFO, "server initialized!").await;
+        let mut registrations = vec![type_hierarchy_registration()];
+        #[cfg(not(target_arch = "wasm32"))]
+        registrations.push(watched_files_registration());
+        for registration in registrations {
+            let method = registration.method.clone();
+            if let Err(err) = self.client.register_capability(vec![registration]).await {
+                self.client
+                    .log_message(MessageType::ERROR, format!("Failed to register {method}: {err}"))
+                    .await;
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let roots: Vec<Url> = self
+                .database
+                .write()
+                .await
+                .file_source_as_mut::<WorkspaceSource>()
+                .map(|source| source.folders().cloned().collect())
+                .unwrap_or_default();
+            for root in roots {
+                self.check_workspace_folder(&root).await;
+            }
+        }
+    }
+
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let WorkspaceFoldersChangeEvent { added, removed } = params.event;
+            let added: Vec<Url> = added.into_iter().map(|folder| folder.uri.from_lsp()).collect();
+            let removed: Vec<Url> =
+                removed.into_iter().map(|folder| folder.uri.from_lsp()).collect();
+
+            self.new_revision();
+            let mut db = self.database.write().await;
+            let Some(source) = db.file_source_as_mut::<WorkspaceSource>() else {
+                return;
+            };
+            let mut errors = vec![];
+            for root in &removed {
+                if let Err(err) = source.remove_folder(root).await {
+                    errors.push(format!("Failed to remove workspace folder {root}: {err}"));
+                }
+            }
+            for root in &added {
+                let Some(folder_source) = folder_source(root) else {
+                    errors.push(format!("Workspace folder is not on the file system: {root}"));
+                    continue;
+                };
+                if let Err(err) = source.add_folder(root, folder_source).await {
+                    errors.push(format!("Failed to add workspace folder {root}: {err}"));
+                }
+            }
+
+            // The files of the changed folders are now provided by other sources.
+            let roots: Vec<Url> = added.iter().chain(&removed).map(folder_root).collect();
+            let changed: Vec<Url> = db
+                .files
+                .keys()
+                .filter(|uri| roots.iter().any(|root| uri.as_str().starts_with(root.as_str())))
+                .cloned()
+                .collect();
+            for uri in &changed {
+                db.invalidate(uri).await;
+            }
+            for uri in &changed {
+                let res = db.ast(uri).await.map(|_| ());
+                let diags = db.diagnostics(uri, res).await;
+                self.send_diagnostics(diags).await;
+            }
+            drop(db);
+
+            for error in errors {
+                self.client.log_message(MessageType::ERROR, error).await;
+            }
+            for root in &added {
+                self.check_workspace_folder(root).await;
+            }
+        }
+        // prevent unused variable warning when compiled for wasm
+        let _ = params;
     }
 
     async fn shutdown(&self) -> jsonrpc::Result<()> {
@@ -72,6 +194,11 @@ impl LanguageServer for Server {
         assert!(source_mut.register(&text_document.uri.from_lsp()));
         source_mut.write_string(&text_document.uri.from_lsp(), &text_document.text).await.unwrap();
 
+        // The client pulls the diagnostics of the opened document itself.
+        if self.client_features().pull_diagnostics {
+            return;
+        }
+
         let res = db.ast(&text_document.uri.from_lsp()).await.map(|_| ());
         let diags = db.diagnostics(&text_document.uri.from_lsp(), res).await;
         self.send_diagnostics(diags).await;
@@ -94,27 +221,90 @@ impl LanguageServer for Server {
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let text_document = params.text_document;
-        let mut content_changes = params.content_changes;
+        let uri = text_document.uri.from_lsp();
 
-        self.client
-            .log_message(
-                MessageType::INFO,
-                format!("Changed file: {}", text_document.uri.from_lsp()),
-            )
-            .await;
+        self.client.log_message(MessageType::INFO, format!("Changed file: {uri}")).await;
 
+        let revision = self.new_revision();
         let mut db = self.database.write().await;
-        let text = content_changes.drain(0..).next().unwrap().text;
 
         let source_mut = db.file_source_mut();
-        assert!(source_mut.register(&text_document.uri.from_lsp()));
-        source_mut.write_string(&text_document.uri.from_lsp(), &text).await.unwrap();
+        assert!(source_mut.register(&uri));
 
-        db.invalidate(&text_document.uri.from_lsp()).await;
+        let res = db.apply_content_changes(&uri, params.content_changes).await;
+        let mut affected: Vec<Url> =
+            db.deps.reverse_dependencies(&uri).into_iter().cloned().collect();
+        // The imported modules are affected as well, since their declarations may become (un)used.
+        affected.extend(db.deps.get(&uri).cloned().unwrap_or_default());
+        // The edited module itself is handled separately below.
+        affected.retain(|other| *other != uri);
 
-        let res = db.ast(&text_document.uri.from_lsp()).await.map(|_| ());
-        let diags = db.diagnostics(&text_document.uri.from_lsp(), res).await;
+        if self.client_features().pull_diagnostics {
+            drop(db);
+            if let Err(err) = res {
+                self.client.log_message(MessageType::ERROR, format!("{err:?}")).await;
+            }
+            // The client pulls the diagnostics of the edited document, but not of the other affected modules.
+            if !affected.is_empty() {
+                self.refresh_diagnostics().await;
+            }
+            return;
+        }
+
+        // Only the edited module is checked while the database is locked for the edit.
+        let res = match res {
+            Ok(()) => db.ast(&uri).await.map(|_| ()),
+            Err(err) => Err(err),
+        };
+        let diags = db.result_diagnostics(&uri, res).await;
+        drop(db);
+        self.client.publish_diagnostics(uri.to_lsp(), diags, None).await;
+
+        self.check_modules("Checking affected modules", affected, revision).await;
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        self.new_revision();
+        let mut db = self.database.write().await;
 
+        // The modules whose diagnostics may have changed
+        let mut affected = vec![];
+        for FileEvent { uri, typ } in params.changes {
+            let uri = uri.from_lsp();
+            self.client.log_message(MessageType::INFO, format!("Changed on disk: {uri}")).await;
+
+            if typ == FileChangeType::CREATED {
+                // Modules which failed to import the new file so far
+                let failed: Vec<_> = db
+                    .ast
+                    .keys()
+                    .filter(|uri| matches!(db.ast.get_even_if_stale(uri), Some(Err(_))))
+                    .cloned()
+                    .collect();
+                for uri in failed {
+                    db.invalidate(&uri).await;
+                    affected.push(uri);
+                }
+            }
+
+            if db.files.keys().any(|known| *known == uri) {
+                affected.extend(db.deps.reverse_dependencies(&uri).into_iter().cloned());
+                db.invalidate(&uri).await;
+            }
+        }
+        affected.sort();
+        affected.dedup();
+
+        let mut diags = DiagnosticsPerUri::default();
+        for uri in affected {
+            // Deleted modules no longer have any diagnostics.
+            if !db.file_source_mut().exists(&uri).await.unwrap_or(false) {
+                diags.insert(uri, vec![]);
+                continue;
+            }
+            let res = db.ast(&uri).await.map(|_| ());
+            diags.extend(db.diagnostics(&uri, res).await);
+        }
         self.send_diagnostics(diags).await;
     }
 
@@ -125,6 +315,148 @@ impl LanguageServer for Server {
         super::gotodefinition::goto_definition(self, params).await
     }
 
+    async fn references(&self, params: ReferenceParams) -> jsonrpc::Result<Option<Vec<Location>>> {
+        super::references::references(self, params).await
+    }
+
+    async fn document_highlight(
+        &self,
+        params: DocumentHighlightParams,
+    ) -> jsonrpc::Result<Option<Vec<DocumentHighlight>>> {
+        super::references::document_highlight(self, params).await
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> jsonrpc::Result<Option<PrepareRenameResponse>> {
+        super::rename::prepare_rename(self, params).await
+    }
+
+    async fn rename(&self, params: RenameParams) -> jsonrpc::Result<Option<WorkspaceEdit>> {
+        super::rename::rename(self, params).await
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> jsonrpc::Result<Option<DocumentSymbolResponse>> {
+        super::symbols::document_symbol(self, params).await
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> jsonrpc::Result<Option<Vec<SymbolInformation>>> {
+        super::symbols::workspace_symbol(self, params).await
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> jsonrpc::Result<Option<SemanticTokensResult>> {
+        super::semantic_tokens::semantic_tokens_full(self, params).await
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> jsonrpc::Result<Option<SemanticTokensRangeResult>> {
+        super::semantic_tokens::semantic_tokens_range(self, params).await
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> jsonrpc::Result<Option<Vec<InlayHint>>> {
+        super::inlay_hints::inlay_hint(self, params).await
+    }
+
+    async fn signature_help(
+        &self,
+        params: SignatureHelpParams,
+    ) -> jsonrpc::Result<Option<SignatureHelp>> {
+        super::signature_help::signature_help(self, params).await
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> jsonrpc::Result<Option<Vec<CodeLens>>> {
+        super::code_lens::code_lens(self, params).await
+    }
+
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> jsonrpc::Result<Option<LSPAny>> {
+        super::commands::execute_command(self, params).await
+    }
+
+    async fn folding_range(
+        &self,
+        params: FoldingRangeParams,
+    ) -> jsonrpc::Result<Option<Vec<FoldingRange>>> {
+        super::ranges::folding_range(self, params).await
+    }
+
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> jsonrpc::Result<Option<Vec<SelectionRange>>> {
+        super::ranges::selection_range(self, params).await
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> jsonrpc::Result<Option<Vec<CallHierarchyItem>>> {
+        super::hierarchy::prepare_call_hierarchy(self, params).await
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> jsonrpc::Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        super::hierarchy::incoming_calls(self, params).await
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> jsonrpc::Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        super::hierarchy::outgoing_calls(self, params).await
+    }
+
+    async fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> jsonrpc::Result<Option<Vec<TypeHierarchyItem>>> {
+        super::hierarchy::prepare_type_hierarchy(self, params).await
+    }
+
+    async fn supertypes(
+        &self,
+        params: TypeHierarchySupertypesParams,
+    ) -> jsonrpc::Result<Option<Vec<TypeHierarchyItem>>> {
+        super::hierarchy::supertypes(self, params).await
+    }
+
+    async fn subtypes(
+        &self,
+        params: TypeHierarchySubtypesParams,
+    ) -> jsonrpc::Result<Option<Vec<TypeHierarchyItem>>> {
+        super::hierarchy::subtypes(self, params).await
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> jsonrpc::Result<DocumentDiagnosticReportResult> {
+        super::pull_diagnostics::document_diagnostic(self, params).await
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> jsonrpc::Result<WorkspaceDiagnosticReportResult> {
+        super::pull_diagnostics::workspace_diagnostic(self, params).await
+    }
+
     async fn hover(&self, params: HoverParams) -> jsonrpc::Result<Option<Hover>> {
         super::hover::hover(self, params).await
     }
@@ -136,15 +468,125 @@ impl LanguageServer for Server {
         super::codeactions::code_action(self, params).await
     }
 
+    async fn completion(
+        &self,
+        params: CompletionParams,
+    ) -> jsonrpc::Result<Option<CompletionResponse>> {
+        super::completion::completion(self, params).await
+    }
+
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         super::format::formatting(self, params).await
     }
 }
 
 impl Server {
+    pub(crate) fn client_features(&self) -> ClientFeatures {
+        self.client_features.get().copied().unwrap_or_default()
+    }
+
+    /// The current revision, which changes with every edit
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
+    fn new_revision(&self) -> u64 {
+        self.revision.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Whether an edit has happened since the given revision
+    pub(crate) fn is_stale(&self, revision: u64) -> bool {
+        self.revision() != revision
+    }
+
+    /// Check all modules in a workspace folder and publish their diagnostics
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn check_workspace_folder(&self, root: &Url) {
+        // The client pulls the diagnostics of the workspace itself.
+        if self.client_features().pull_diagnostics {
+            return;
+        }
+        let Ok(path) = root.to_file_path() else {
+            return;
+        };
+        self.client
+            .log_message(MessageType::INFO, format!("Checking workspace folder: {root}"))
+            .await;
+        let uris = FileSystemSource::new(path).module_uris();
+        self.check_modules("Checking workspace folder", uris, self.revision()).await;
+    }
+
+    /// Check the given modules one after another and publish their diagnostics
+    ///
+    /// The database is only locked while a single module is checked, so that other requests and
+    /// edits can be handled in between. The check stops as soon as an edit arrives, since it
+    /// started from outdated sources. The edit causes a new check of the affected modules.
+    async fn check_modules(&self, title: &str, uris: Vec<Url>, revision: u64) {
+        let progress = self.begin_progress(title, None, uris.len()).await;
+        for (done, uri) in uris.iter().enumerate() {
+            if self.is_stale(revision) {
+                self.client.log_message(MessageType::INFO, format!("{title}: cancelled")).await;
+                progress.finish(Some("Cancelled")).await;
+                return;
+            }
+            progress.report(done, uri).await;
+            let diags = self.database.write().await.module_diagnostics(uri).await;
+            self.client.publish_diagnostics(uri.to_lsp(), diags, None).await;
+        }
+        progress.finish(None).await;
+    }
+
+    /// The modules in the workspace folders together with all other modules known to the server
+    pub(crate) async fn workspace_modules(&self) -> Vec<Url> {
+        let mut modules: Vec<Url> = self.database.read().await.files.keys().cloned().collect();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let roots: Vec<Url> = self
+                .database
+                .write()
+                .await
+                .file_source_as_mut::<WorkspaceSource>()
+                .map(|source| source.folders().cloned().collect())
+                .unwrap_or_default();
+            for root in roots {
+                if let Ok(path) = root.to_file_path() {
+                    modules.extend(FileSystemSource::new(path).module_uris());
+                }
+            }
+        }
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+
+    /// Ask the client to pull the diagnostics of all documents again
+    async fn refresh_diagnostics(&self) {
+        if !self.client_features().diagnostic_refresh {
+            return;
+        }
+        if let Err(err) = self.client.workspace_diagnostic_refresh().await {
+            self.client
+                .log_message(MessageType::ERROR, format!("Failed to refresh diagnostics: {err}"))
+                .await;
+        }
+    }
+
     pub(crate) async fn send_diagnostics(&self, diags: DiagnosticsPerUri) {
+        if self.client_features().pull_diagnostics {
+            self.refresh_diagnostics().await;
+            return;
+        }
         for (uri, diags) in diags {
             self.client.publish_diagnostics(uri.to_lsp(), diags, None).await;
         }
     }
 }
+
+/// The source of a workspace folder
+///
+/// Open documents are kept in memory, all other files are read from disk.
+#[cfg(not(target_arch = "wasm32"))]
+fn folder_source(root: &Url) -> Option<impl FileSource + use<>> {
+    let path = root.to_file_path().ok()?;
+    Some(InMemorySource::new().fallback_to(FileSystemSource::new(path)))
+}
@@ -0,0 +1,31 @@
+//! Implementation of the signature help functionality of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn signature_help(
+    server: &Server,
+    params: SignatureHelpParams,
+) -> jsonrpc::Result<Option<SignatureHelp>> {
+    let pos_params = params.text_document_position_params;
+    let text_document = pos_params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("SignatureHelp request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let pos = pos_params.position;
+    let mut db = server.database.write().await;
+    let info = db.location_to_index(&text_document.uri.from_lsp(), pos);
+    let help = match info {
+        Some(idx) => db.signature_help_at_index(&text_document.uri.from_lsp(), idx).await.ok(),
+        None => None,
+    };
+    Ok(help.flatten())
+}
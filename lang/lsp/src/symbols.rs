@@ -0,0 +1,79 @@
+//! Implementation of the document-symbol and workspace-symbol functionality of the LSP server
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use super::conversion::*;
+use super::server::*;
+
+pub async fn document_symbol(
+    server: &Server,
+    params: DocumentSymbolParams,
+) -> jsonrpc::Result<Option<DocumentSymbolResponse>> {
+    let text_document = params.text_document;
+
+    server
+        .client
+        .log_message(
+            MessageType::INFO,
+            format!("DocumentSymbol request: {}", text_document.uri.from_lsp()),
+        )
+        .await;
+
+    let mut db = server.database.write().await;
+    let symbols = db.document_symbols(&text_document.uri.from_lsp()).await.ok();
+    Ok(symbols.map(DocumentSymbolResponse::Nested))
+}
+
+pub async fn workspace_symbol(
+    server: &Server,
+    params: WorkspaceSymbolParams,
+) -> jsonrpc::Result<Option<Vec<SymbolInformation>>> {
+    server
+        .client
+        .log_message(MessageType::INFO, format!("WorkspaceSymbol request: {}", params.query))
+        .await;
+
+    let mut db = server.database.write().await;
+    let uris: Vec<_> = db.files.keys().cloned().collect();
+
+    let mut res = vec![];
+    for uri in uris {
+        let Ok(symbols) = db.document_symbols(&uri).await else {
+            continue;
+        };
+        flatten_symbols(&uri.to_lsp(), symbols, None, &params.query, &mut res);
+    }
+    Ok(Some(res))
+}
+
+#[allow(deprecated)]
+fn flatten_symbols(
+    uri: &Uri,
+    symbols: Vec<DocumentSymbol>,
+    container_name: Option<&str>,
+    query: &str,
+    res: &mut Vec<SymbolInformation>,
+) {
+    for symbol in symbols {
+        let DocumentSymbol { name, kind, selection_range, children, .. } = symbol;
+        if let Some(children) = children {
+            flatten_symbols(uri, children, Some(&name), query, res);
+        }
+        if matches_query(&name, query) {
+            res.push(SymbolInformation {
+                name,
+                kind,
+                tags: None,
+                deprecated: None,
+                location: Location { uri: uri.clone(), range: selection_range },
+                container_name: container_name.map(ToOwned::to_owned),
+            });
+        }
+    }
+}
+
+/// Whether the characters of the query occur in the name in the same order, ignoring case
+fn matches_query(name: &str, query: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query.chars().flat_map(char::to_lowercase).all(|c| name.any(|n| n == c))
+}
@@ -1,4 +1,5 @@
 pub mod codespan;
+pub mod trivia;
 
 pub trait ToMiette {
     type Target;
@@ -0,0 +1,46 @@
+//! Ordinary comments and blank lines which are kept so that a node can be printed again
+//!
+//! The parser attaches them to the nodes of the CST, and lowering copies them to the AST unchanged.
+
+use crate::codespan::Span;
+
+/// An ordinary comment or a blank line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trivia {
+    /// A comment, including its delimiters
+    Comment(String),
+    /// An empty line which separates comments or nodes
+    BlankLine,
+}
+
+/// The trivia attached to a node
+#[derive(Debug, Clone, Default)]
+pub struct Comments {
+    /// Comments on the lines before the node
+    pub leading: Vec<Trivia>,
+    /// A comment at the end of the last line of the node
+    pub end_of_line: Option<String>,
+    /// Comments on the lines after the node, and a blank line which separates the node from the next one
+    pub trailing: Vec<Trivia>,
+    /// The span from the first to the last of these comments
+    pub span: Option<Span>,
+}
+
+impl Comments {
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.end_of_line.is_none() && self.trailing.is_empty()
+    }
+
+    /// Extend the span of these comments to `span`
+    pub fn cover(&mut self, span: Span) {
+        self.span = Some(self.span_with(span));
+    }
+
+    /// The span of a node together with these comments
+    pub fn span_with(&self, node: Span) -> Span {
+        match self.span {
+            Some(span) => Span { start: span.start.min(node.start), end: span.end.max(node.end) },
+            None => node,
+        }
+    }
+}
@@ -4,6 +4,7 @@ use url::Url;
 use super::exp::{self, Pattern};
 use super::exp::{BinOp, Call, Copattern};
 use super::ident::*;
+use super::trivia::Comments;
 
 #[derive(Debug, Clone)]
 pub struct DocComment {
@@ -35,6 +36,7 @@ pub struct Module {
 #[derive(Debug, Clone)]
 pub struct UseDecl {
     pub span: Span,
+    pub comments: Comments,
     pub path: String,
 }
 
@@ -66,6 +68,7 @@ pub enum Decl {
 #[derive(Debug, Clone)]
 pub struct Data {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub attr: Attributes,
     pub name: Ident,
@@ -84,6 +87,7 @@ pub struct Data {
 #[derive(Debug, Clone)]
 pub struct Codata {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub attr: Attributes,
     pub name: Ident,
@@ -104,9 +108,12 @@ pub struct Codata {
 #[derive(Debug, Clone)]
 pub struct Ctor {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: Option<exp::Call>,
 }
 
@@ -123,10 +130,13 @@ pub struct Ctor {
 #[derive(Debug, Clone)]
 pub struct Dtor {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub params: Telescope,
     pub destructee: Destructee,
+    /// The comments of the return type
+    pub ret_typ_comments: Comments,
     pub ret_typ: Box<exp::Exp>,
 }
 
@@ -159,11 +169,14 @@ pub struct Destructee {
 #[derive(Debug, Clone)]
 pub struct Def {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub attr: Attributes,
     pub params: Telescope,
     pub scrutinee: Scrutinee,
+    /// The comments of the return type
+    pub ret_typ_comments: Comments,
     pub ret_typ: Box<exp::Exp>,
     pub cases: Vec<exp::Case<Pattern>>,
 }
@@ -196,10 +209,13 @@ pub struct Scrutinee {
 #[derive(Debug, Clone)]
 pub struct Codef {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub attr: Attributes,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: exp::Call,
     pub cases: Vec<exp::Case<Copattern>>,
 }
@@ -217,11 +233,16 @@ pub struct Codef {
 #[derive(Debug, Clone)]
 pub struct Let {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub attr: Attributes,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: Box<exp::Exp>,
+    /// The comments of the body within the braces
+    pub body_comments: Comments,
     pub body: Box<exp::Exp>,
 }
 
@@ -238,22 +259,55 @@ pub struct Let {
 #[derive(Debug, Clone)]
 pub struct Extern {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub attr: Attributes,
     pub params: Telescope,
+    /// The comments of the type
+    pub typ_comments: Comments,
     pub typ: Box<exp::Exp>,
 }
 
+/// Infix operator declaration
+///
+/// ```text
+/// infixr 5 _ -> _ := Fun(_,_)
+///   ^    ^   ^       ^----- rhs
+///   |    |   \------------- pattern
+///   |    \----------------- precedence
+///   \---------------------- assoc
+/// ```
 #[derive(Debug, Clone)]
 pub struct Infix {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub attr: Attributes,
+    pub assoc: Associativity,
+    pub precedence: Option<Precedence>,
     pub pattern: BinOp,
     pub rhs: Call,
 }
 
+/// The associativity of an infix operator, given by the keyword of its declaration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `infixl`
+    Left,
+    /// `infixr`
+    Right,
+    /// `infix`
+    NonAssoc,
+}
+
+/// The precedence level of an infix operator
+#[derive(Debug, Clone)]
+pub struct Precedence {
+    pub span: Span,
+    pub level: i64,
+}
+
 /// A note declaration, used for holding doc-comments
 ///
 /// ```text
@@ -263,6 +317,7 @@ pub struct Infix {
 #[derive(Debug, Clone)]
 pub struct Note {
     pub span: Span,
+    pub comments: Comments,
     pub doc: Option<DocComment>,
     pub name: Ident,
     pub attr: Attributes,
@@ -272,6 +327,8 @@ pub struct Note {
 /// The parameter list can be optionally prefixed with the "implicit" keyword: `implicit x : T` or `implicit x y z: T`
 #[derive(Debug, Clone)]
 pub struct Param {
+    pub span: Span,
+    pub comments: Comments,
     /// Whether the "implicit" keyword was used.
     pub implicit: bool,
     /// The obligatory parameter name.
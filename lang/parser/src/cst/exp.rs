@@ -3,6 +3,7 @@ use ordered_float::NotNan;
 use polarity_lang_miette_util::codespan::Span;
 
 use super::ident::*;
+use super::trivia::Comments;
 
 #[derive(Debug, Clone)]
 pub enum BindingSite {
@@ -19,11 +20,32 @@ impl BindingSite {
     }
 }
 
+/// A pattern in the case of a pattern match, e.g. `Cons(x, Cons(_, Nil))`
+///
+/// An identifier without arguments, such as `Nil` or `x`, is parsed as a constructor pattern.
+/// Whether it stands for a constructor or binds a variable is only decided during lowering.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Ctor { span: Span, name: QIdent, params: Vec<Pattern> },
+    Literal(Literal),
+    Wildcard { span: Span },
+}
+
+/// The patterns of a case which matches on several expressions at once, e.g. `(S(x), Z)`
 #[derive(Debug, Clone)]
-pub struct Pattern {
+pub struct MultiPattern {
     pub span: Span,
-    pub name: QIdent,
-    pub params: Vec<BindingSite>,
+    pub params: Vec<Pattern>,
+}
+
+impl Pattern {
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Ctor { span, .. } => *span,
+            Pattern::Literal(lit) => lit.span,
+            Pattern::Wildcard { span } => *span,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +58,7 @@ pub struct Copattern {
 #[derive(Debug, Clone)]
 pub struct Case<P> {
     pub span: Span,
+    pub comments: Comments,
     pub pattern: P,
     /// Body being `None` represents an absurd pattern
     pub body: Option<Box<Exp>>,
@@ -46,21 +69,21 @@ pub struct Case<P> {
 /// Example for unnamed arguments: `f(1, 2)``
 #[derive(Debug, Clone)]
 pub enum Arg {
-    UnnamedArg(Box<Exp>),
-    NamedArg(Ident, Box<Exp>),
+    UnnamedArg(Box<Exp>, Comments),
+    NamedArg(Ident, Box<Exp>, Comments),
 }
 
 impl Arg {
     pub fn span(&self) -> Span {
         match self {
-            Arg::UnnamedArg(exp) => exp.span(),
-            Arg::NamedArg(_, exp) => exp.span(),
+            Arg::UnnamedArg(exp, _) => exp.span(),
+            Arg::NamedArg(_, exp, _) => exp.span(),
         }
     }
 
     pub fn is_underscore(&self) -> bool {
         match self {
-            Arg::UnnamedArg(arg) => arg.is_underscore(),
+            Arg::UnnamedArg(arg, _) => arg.is_underscore(),
             _ => false,
         }
     }
@@ -72,6 +95,7 @@ pub enum Exp {
     DotCall(DotCall),
     Anno(Anno),
     LocalMatch(LocalMatch),
+    MultiMatch(MultiMatch),
     LocalComatch(LocalComatch),
     Hole(Hole),
     Literal(Literal),
@@ -94,6 +118,7 @@ impl Exp {
             Exp::DotCall(dot_call) => dot_call.span,
             Exp::Anno(anno) => anno.span,
             Exp::LocalMatch(local_match) => local_match.span,
+            Exp::MultiMatch(multi_match) => multi_match.span,
             Exp::LocalComatch(local_comatch) => local_comatch.span,
             Exp::Hole(hole) => hole.span,
             Exp::Literal(lit) => lit.span,
@@ -148,6 +173,8 @@ pub struct Anno {
 /// ```
 pub struct LocalLet {
     pub span: Span,
+    /// The comments of the line `let x := e;`
+    pub comments: Comments,
     pub name: BindingSite,
     pub typ: Option<Box<Exp>>,
     pub bound: Box<Exp>,
@@ -164,6 +191,14 @@ pub struct LocalMatch {
     pub cases: Vec<Case<Pattern>>,
 }
 
+#[derive(Debug, Clone)]
+/// Pattern match on several expressions at once, e.g. match (x, y) { (Z, Z) => .., ..}
+pub struct MultiMatch {
+    pub span: Span,
+    pub on_exps: Vec<Exp>,
+    pub cases: Vec<Case<MultiPattern>>,
+}
+
 #[derive(Debug, Clone)]
 /// Copattern match, e.g. comatch { .x(a, ..) => .. }
 pub struct LocalComatch {
@@ -182,9 +217,24 @@ pub struct DoBlock {
 
 #[derive(Debug, Clone)]
 pub enum DoStatement {
-    Exp { span: Span, exp: Box<Exp> },
-    Bind { span: Span, name: BindingSite, bound: Box<Exp> },
-    Let { span: Span, name: BindingSite, typ: Option<Box<Exp>>, bound: Box<Exp> },
+    Exp {
+        span: Span,
+        comments: Comments,
+        exp: Box<Exp>,
+    },
+    Bind {
+        span: Span,
+        comments: Comments,
+        name: BindingSite,
+        bound: Box<Exp>,
+    },
+    Let {
+        span: Span,
+        comments: Comments,
+        name: BindingSite,
+        typ: Option<Box<Exp>>,
+        bound: Box<Exp>,
+    },
 }
 
 #[derive(Debug, Clone)]
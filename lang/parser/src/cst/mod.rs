@@ -7,5 +7,6 @@
 pub mod decls;
 pub mod exp;
 pub mod ident;
+pub mod trivia;
 
 pub use ident::Ident;
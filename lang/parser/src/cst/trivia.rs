@@ -0,0 +1,406 @@
+//! Comments which are kept in the CST so that they can be printed again
+//!
+//! The lexer emits comments as trivia, which the parser does not see. After parsing, every comment is
+//! attached to a node of the CST: a use declaration, a declaration, a parameter, a return type, a constructor,
+//! a destructor, a case, the body of a let declaration, an argument, a local let binding or a statement of a do block.
+//! A comment is attached to the innermost such node which contains it, as follows:
+//!
+//! * A comment at the end of the line on which a node ends is an end-of-line comment of that node,
+//!   unless there is a token other than a comma or a semicolon between them.
+//! * Any other comment is a leading comment of the next node in the same block.
+//! * If there is no next node in the same block, it is a trailing comment of the previous one.
+//! * If the block contains no node at all, it is a leading comment of the enclosing node.
+//!
+//! Blank lines between these comments, and between two nodes in the same block, are kept as well.
+//! The declarations of a module are always separated by a blank line, so blank lines between them are not recorded.
+
+use std::ops::Range;
+
+use polarity_lang_miette_util::codespan::{ByteIndex, Span};
+pub use polarity_lang_miette_util::trivia::{Comments, Trivia};
+
+use super::decls::*;
+use super::exp::*;
+
+/// A comment in the source text, together with its location
+pub struct Comment {
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+/// Attach the comments of the source text `source` to the nodes of a module
+///
+/// The comments must be ordered by their position in the source text.
+pub fn attach_comments(
+    source: &str,
+    use_decls: &mut [UseDecl],
+    decls: &mut [Decl],
+    comments: Vec<Comment>,
+) {
+    if comments.is_empty() {
+        return;
+    }
+
+    let mut anchors = Anchors { anchors: vec![], parent: None };
+    for UseDecl { span, comments, .. } in use_decls.iter_mut() {
+        anchors.push(*span, comments, |_| {});
+    }
+    for decl in decls.iter_mut() {
+        anchors.decl(decl);
+    }
+    let mut anchors = anchors.anchors;
+
+    // Both the anchors and the comments are ordered by their start, so they are merged in a single pass.
+    // The stack holds the anchors which contain the current position, together with their last child
+    // which ends before it. The bottom of the stack stands for the module itself.
+    let mut stack: Vec<(Option<usize>, Option<usize>)> = vec![(None, None)];
+    let mut next_anchor = 0;
+    for comment in comments {
+        let span = comment.span.clone();
+        while next_anchor < anchors.len() && anchors[next_anchor].start <= span.start {
+            close_anchors(&anchors, &mut stack, anchors[next_anchor].start);
+            stack.push((Some(next_anchor), None));
+            next_anchor += 1;
+        }
+        close_anchors(&anchors, &mut stack, span.start);
+
+        let (parent, prev) = *stack.last().unwrap();
+        // In pre-order, the next sibling is the next anchor, if any.
+        let next =
+            Some(next_anchor).filter(|idx| *idx < anchors.len() && anchors[*idx].parent == parent);
+
+        match (prev, next, parent) {
+            (Some(prev), _, _)
+                if is_end_of_line(source, anchors[prev].end, span.start)
+                    && anchors[prev].end_of_line.is_none() =>
+            {
+                anchors[prev].end_of_line = Some(comment);
+            }
+            (_, Some(next), _) => anchors[next].leading.push(comment),
+            (Some(prev), None, _) => anchors[prev].trailing.push(comment),
+            (None, None, Some(parent)) => anchors[parent].leading.push(comment),
+            // A module without any declarations can only consist of comments, which are discarded.
+            (None, None, None) => {}
+        }
+    }
+
+    // The next sibling of every anchor which has a parent
+    let mut next_sibling = vec![None; anchors.len()];
+    let mut last_child = vec![None; anchors.len()];
+    for (idx, anchor) in anchors.iter().enumerate() {
+        if let Some(parent) = anchor.parent
+            && let Some(prev) = last_child[parent].replace(idx)
+        {
+            next_sibling[prev] = Some(idx);
+        }
+    }
+
+    for idx in 0..anchors.len() {
+        // The position from which the gap to the next sibling is measured
+        let end = anchors[idx].end_of_trivia();
+        let blank_line_after = next_sibling[idx]
+            .is_some_and(|next| is_blank(source, end, anchors[next].start_of_trivia()));
+        anchors[idx].finish(source, blank_line_after);
+    }
+}
+
+/// Pop the anchors which end before `pos` from the stack of open anchors
+///
+/// Each of them becomes the last child of the anchor below it.
+fn close_anchors(anchors: &[Anchor], stack: &mut Vec<(Option<usize>, Option<usize>)>, pos: usize) {
+    while let Some((Some(idx), _)) = stack.last().copied() {
+        if anchors[idx].end > pos {
+            break;
+        }
+        stack.pop();
+        stack.last_mut().unwrap().1 = Some(idx);
+    }
+}
+
+/// Convert a range of byte offsets in the source text into a span
+fn to_span(range: &Range<usize>) -> Span {
+    Span { start: ByteIndex(range.start as u32), end: ByteIndex(range.end as u32) }
+}
+
+/// Whether a comment starting at `to` is on the same line as the end `from` of the previous node
+///
+/// Only commas and semicolons may separate the two, so that e.g. a comment after the opening brace of a block
+/// is not attached to the node before the brace.
+fn is_end_of_line(source: &str, from: usize, to: usize) -> bool {
+    source.get(from..to).is_some_and(|gap| {
+        gap.chars().all(|c| c == ',' || c == ';' || (c.is_whitespace() && c != '\n'))
+    })
+}
+
+/// Whether the source text between two nodes or comments contains a blank line
+fn is_blank(source: &str, from: usize, to: usize) -> bool {
+    // The first and the last line of the gap are not blank, because they belong to the nodes or comments.
+    source.get(from..to).is_some_and(|gap| {
+        let lines: Vec<_> = gap.split('\n').collect();
+        lines.len() > 2 && lines[1..lines.len() - 1].iter().any(|line| line.trim().is_empty())
+    })
+}
+
+/// A node of the CST to which comments can be attached
+struct Anchor<'a> {
+    start: usize,
+    end: usize,
+    /// The index of the innermost anchor which contains this one
+    parent: Option<usize>,
+    leading: Vec<Comment>,
+    end_of_line: Option<Comment>,
+    trailing: Vec<Comment>,
+    comments: &'a mut Comments,
+}
+
+impl Anchor<'_> {
+    /// The start of the node including its leading comments
+    fn start_of_trivia(&self) -> usize {
+        self.leading.first().map_or(self.start, |comment| comment.span.start.min(self.start))
+    }
+
+    /// The end of the node including its end-of-line and trailing comments
+    fn end_of_trivia(&self) -> usize {
+        self.trailing
+            .last()
+            .or(self.end_of_line.as_ref())
+            .map_or(self.end, |comment| comment.span.end)
+    }
+
+    /// Store the attached comments in the node, together with the blank lines between them
+    fn finish(&mut self, source: &str, blank_line_after: bool) {
+        let Anchor { start, end, leading, end_of_line, trailing, comments, .. } = self;
+
+        for (idx, comment) in leading.iter().enumerate() {
+            comments.cover(to_span(&comment.span));
+            comments.leading.push(Trivia::Comment(comment.text.clone()));
+            let next = leading.get(idx + 1).map_or(*start, |next| next.span.start);
+            if is_blank(source, comment.span.end, next) {
+                comments.leading.push(Trivia::BlankLine);
+            }
+        }
+
+        let mut prev = *end;
+        if let Some(comment) = end_of_line {
+            comments.cover(to_span(&comment.span));
+            comments.end_of_line = Some(comment.text.clone());
+            prev = comment.span.end;
+        }
+
+        for comment in trailing.iter() {
+            comments.cover(to_span(&comment.span));
+            if is_blank(source, prev, comment.span.start) {
+                comments.trailing.push(Trivia::BlankLine);
+            }
+            comments.trailing.push(Trivia::Comment(comment.text.clone()));
+            prev = comment.span.end;
+        }
+
+        if blank_line_after {
+            comments.trailing.push(Trivia::BlankLine);
+        }
+    }
+}
+
+/// Collects the anchors of a module in pre-order
+struct Anchors<'a> {
+    anchors: Vec<Anchor<'a>>,
+    /// The index of the anchor whose children are currently collected
+    parent: Option<usize>,
+}
+
+impl<'a> Anchors<'a> {
+    /// Add an anchor and collect its children with `children`
+    fn push<F: FnOnce(&mut Self)>(&mut self, span: Span, comments: &'a mut Comments, children: F) {
+        let idx = self.anchors.len();
+        self.anchors.push(Anchor {
+            start: span.start.to_usize(),
+            end: span.end.to_usize(),
+            parent: self.parent,
+            leading: vec![],
+            end_of_line: None,
+            trailing: vec![],
+            comments,
+        });
+        let parent = self.parent.replace(idx);
+        children(self);
+        self.parent = parent;
+    }
+
+    fn decl(&mut self, decl: &'a mut Decl) {
+        match decl {
+            Decl::Data(Data { span, comments, params, ctors, .. }) => {
+                self.push(*span, comments, move |this| {
+                    this.telescope(params);
+                    for ctor in ctors.iter_mut() {
+                        this.ctor(ctor);
+                    }
+                })
+            }
+            Decl::Codata(Codata { span, comments, params, dtors, .. }) => {
+                self.push(*span, comments, move |this| {
+                    this.telescope(params);
+                    for dtor in dtors.iter_mut() {
+                        this.dtor(dtor);
+                    }
+                })
+            }
+            Decl::Def(Def { span, comments, params, ret_typ_comments, ret_typ, cases, .. }) => self
+                .push(*span, comments, move |this| {
+                    this.telescope(params);
+                    this.push(ret_typ.span(), ret_typ_comments, move |this| this.exp(ret_typ));
+                    this.cases(cases);
+                }),
+            Decl::Codef(Codef { span, comments, params, typ_comments, typ, cases, .. }) => self
+                .push(*span, comments, move |this| {
+                    this.telescope(params);
+                    this.push(typ.span, typ_comments, move |this| this.args(&mut typ.args));
+                    this.cases(cases);
+                }),
+            Decl::Let(Let {
+                span,
+                comments,
+                params,
+                typ_comments,
+                typ,
+                body_comments,
+                body,
+                ..
+            }) => self.push(*span, comments, move |this| {
+                this.telescope(params);
+                this.push(typ.span(), typ_comments, move |this| this.exp(typ));
+                this.push(body.span(), body_comments, move |this| this.exp(body))
+            }),
+            Decl::Extern(Extern { span, comments, params, typ_comments, typ, .. }) => {
+                self.push(*span, comments, move |this| {
+                    this.telescope(params);
+                    this.push(typ.span(), typ_comments, move |this| this.exp(typ));
+                })
+            }
+            Decl::Infix(Infix { span, comments, .. }) => self.push(*span, comments, |_| {}),
+            Decl::Note(Note { span, comments, .. }) => self.push(*span, comments, |_| {}),
+            Decl::Error => {}
+        }
+    }
+
+    fn ctor(&mut self, ctor: &'a mut Ctor) {
+        let Ctor { span, comments, params, typ_comments, typ, .. } = ctor;
+        self.push(*span, comments, move |this| {
+            this.telescope(params);
+            if let Some(typ) = typ {
+                this.push(typ.span, typ_comments, move |this| this.args(&mut typ.args));
+            }
+        })
+    }
+
+    fn dtor(&mut self, dtor: &'a mut Dtor) {
+        let Dtor { span, comments, params, ret_typ_comments, ret_typ, .. } = dtor;
+        self.push(*span, comments, move |this| {
+            this.telescope(params);
+            this.push(ret_typ.span(), ret_typ_comments, move |this| this.exp(ret_typ));
+        })
+    }
+
+    fn telescope(&mut self, telescope: &'a mut Telescope) {
+        for Param { span, comments, typ, .. } in telescope.0.iter_mut() {
+            self.push(*span, comments, move |this| this.exp(typ));
+        }
+    }
+
+    fn cases<P>(&mut self, cases: &'a mut [Case<P>]) {
+        for Case { span, comments, body, .. } in cases.iter_mut() {
+            self.push(*span, comments, move |this| {
+                if let Some(body) = body {
+                    this.exp(body)
+                }
+            });
+        }
+    }
+
+    /// Collect the anchors within an expression
+    fn exp(&mut self, exp: &'a mut Exp) {
+        match exp {
+            Exp::Call(Call { args, .. }) => self.args(args),
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                self.exp(exp);
+                self.args(args);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                self.exp(exp);
+                self.exp(typ);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, cases, .. }) => {
+                self.exp(on_exp);
+                self.cases(cases);
+            }
+            Exp::MultiMatch(MultiMatch { on_exps, cases, .. }) => {
+                for on_exp in on_exps.iter_mut() {
+                    self.exp(on_exp);
+                }
+                self.cases(cases);
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => self.cases(cases),
+            // The case of a lambda is printed on the same line as the lambda,
+            // so comments within it are attached to an enclosing node instead.
+            Exp::Lam(Lam { case: Case { body, .. }, .. }) => {
+                if let Some(body) = body {
+                    self.exp(body);
+                }
+            }
+            Exp::BinOp(BinOp { lhs, rhs, .. }) => {
+                self.exp(lhs);
+                for (_, exp) in rhs.iter_mut() {
+                    self.exp(exp);
+                }
+            }
+            // The anchor of a local let binding is the line `let x := e;`,
+            // so that the body is anchored next to it in the same block.
+            Exp::LocalLet(LocalLet { span, comments, typ, bound, body, .. }) => {
+                let line = Span { start: span.start, end: bound.span().end };
+                self.push(line, comments, move |this| {
+                    if let Some(typ) = typ {
+                        this.exp(typ);
+                    }
+                    this.exp(bound);
+                });
+                self.exp(body);
+            }
+            Exp::DoBlock(DoBlock { statements, .. }) => {
+                for statement in statements.iter_mut() {
+                    match statement {
+                        DoStatement::Exp { span, comments, exp } => {
+                            self.push(*span, comments, move |this| this.exp(exp))
+                        }
+                        DoStatement::Bind { span, comments, bound, .. } => {
+                            self.push(*span, comments, move |this| this.exp(bound))
+                        }
+                        DoStatement::Let { span, comments, typ, bound, .. } => {
+                            self.push(*span, comments, move |this| {
+                                if let Some(typ) = typ {
+                                    this.exp(typ);
+                                }
+                                this.exp(bound);
+                            })
+                        }
+                    }
+                }
+            }
+            Exp::Parens(Parens { exp, .. }) => self.exp(exp),
+            Exp::Hole(_) | Exp::Literal(_) | Exp::Error(_) => {}
+        }
+    }
+
+    fn args(&mut self, args: &'a mut [Arg]) {
+        for arg in args.iter_mut() {
+            match arg {
+                Arg::UnnamedArg(exp, comments) => {
+                    self.push(exp.span(), comments, move |this| this.exp(exp))
+                }
+                Arg::NamedArg(name, exp, comments) => {
+                    let span = Span { start: name.span.start, end: exp.span().end };
+                    self.push(span, comments, move |this| this.exp(exp))
+                }
+            }
+        }
+    }
+}
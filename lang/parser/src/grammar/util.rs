@@ -3,3 +3,24 @@ use polarity_lang_miette_util::codespan::{ByteIndex, Span};
 pub fn span(l: usize, r: usize) -> Span {
     Span { start: ByteIndex(l as u32), end: ByteIndex(r as u32) }
 }
+
+/// The lines of a doc comment token, which is either a line starting with `///` or a `/** ... */` block
+///
+/// In a block, a leading `*` is removed from every line, as well as empty lines at the beginning and the end.
+pub fn doc_comment_lines(doc: &str) -> Vec<String> {
+    let Some(block) = doc.strip_prefix("/**") else {
+        return doc.strip_prefix("///").unwrap().lines().map(|s| s.trim().to_owned()).collect();
+    };
+    let lines: Vec<&str> = block
+        .strip_suffix("*/")
+        .unwrap_or(block)
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').map(str::trim_start).unwrap_or(line)
+        })
+        .collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |idx| idx + 1);
+    lines[start..end].iter().map(|line| line.to_string()).collect()
+}
@@ -28,6 +28,9 @@ pub enum LexicalError {
 
     /// An invalid hexadecimal number literal
     InvalidHexNumber(Span),
+
+    /// A block comment which is not closed by a matching `*/`
+    UnterminatedComment(Span),
 }
 
 impl Default for LexicalError {
@@ -44,14 +47,6 @@ impl fmt::Display for LexicalError {
 
 #[derive(Logos, Clone, Debug, PartialEq)]
 #[logos(error(LexicalError, callback = |lex| LexicalError::InvalidToken(Some(lex.span()))))]
-#[logos(skip r"\s*", skip r"//([^/\n\r]([^\n\r]*))?[\n\r]*")]
-//                          ^^   ^^^^^^  ^^^^^^^  ^^^^^^^
-//                          (1)   (2)      (3)     (4)
-// Comments start with "//" (1).
-// Then we have to exclude the possibility of a doc comment which starts with "///":
-// If the line is not empty, then the next character must not contain "/" (2)
-// And this character can be followed by any number of characters which don't end the line (3)
-// And finally many newlines (5)
 pub enum Token {
     // Keywords
     //
@@ -80,6 +75,10 @@ pub enum Token {
     Use,
     #[token("infix")]
     Infix,
+    #[token("infixl")]
+    InfixL,
+    #[token("infixr")]
+    InfixR,
     #[token("note")]
     Note,
     #[token("extern")]
@@ -173,7 +172,54 @@ pub enum Token {
     // Doc comments start with "///" (1),
     // followed by any number of non-line-break characters (2),
     // followed by any number of empty lines (3).
+    #[regex(r"/\*\*[^*/]", block_comment)]
+    // Doc blocks start with "/**", followed by a character which makes sure that
+    // neither "/***" nor the empty block comment "/**/" start a doc block.
     DocComment(String),
+
+    // Trivia
+    //
+    // Whitespace and comments are only emitted by a lexer created with `Lexer::with_trivia`.
+    #[regex(r"\s+")]
+    Whitespace,
+    #[regex(r"//([^/\n\r][^\n\r]*)?", |lex| lex.slice().to_string())]
+    //        ^^ ^^^^^^^ ^^^^^^^
+    //        (1)  (2)     (3)
+    // Comments start with "//" (1).
+    // Then we have to exclude the possibility of a doc comment which starts with "///":
+    // If the line is not empty, then the next character must not be "/" (2),
+    // and this character can be followed by any number of characters which don't end the line (3).
+    #[token("/*", block_comment)]
+    // Block comments can be nested, so they are lexed by a callback.
+    Comment(String),
+}
+
+/// Lex the rest of a block comment after its opening delimiter
+///
+/// Every nested `/*` has to be closed by its own `*/`.
+fn block_comment(lex: &mut logos::Lexer<Token>) -> Result<String, LexicalError> {
+    let rest = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut idx = 0;
+    while idx < rest.len() {
+        match rest[idx..] {
+            [b'/', b'*', ..] => {
+                depth += 1;
+                idx += 2;
+            }
+            [b'*', b'/', ..] => {
+                depth -= 1;
+                idx += 2;
+                if depth == 0 {
+                    lex.bump(idx);
+                    return Ok(lex.slice().to_string());
+                }
+            }
+            _ => idx += 1,
+        }
+    }
+    lex.bump(rest.len());
+    Err(LexicalError::UnterminatedComment(lex.span()))
 }
 
 impl fmt::Display for Token {
@@ -191,6 +237,8 @@ impl fmt::Display for Token {
             Token::Implicit => write!(f, r#""implicit""#),
             Token::Use => write!(f, r#""use""#),
             Token::Infix => write!(f, r#""infix""#),
+            Token::InfixL => write!(f, r#""infixl""#),
+            Token::InfixR => write!(f, r#""infixr""#),
             Token::Note => write!(f, r#""note""#),
             Token::Extern => write!(f, r#""extern""#),
             Token::Do => write!(f, r#""do""#),
@@ -219,6 +267,8 @@ impl fmt::Display for Token {
             Token::StringLit(_) => write!(f, r#""string literal""#),
             Token::CharLit(_) => write!(f, r#""character literal""#),
             Token::DocComment(_) => write!(f, r#""doc comment""#),
+            Token::Whitespace => write!(f, r#""whitespace""#),
+            Token::Comment(_) => write!(f, r#""comment""#),
         }
     }
 }
@@ -228,12 +278,21 @@ pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
 pub struct Lexer<'input> {
     // instead of an iterator over characters, we have a token iterator
     token_stream: SpannedIter<'input, Token>,
+    /// Whether whitespace and comments are emitted
+    trivia: bool,
 }
 
 impl<'input> Lexer<'input> {
     pub fn new(input: &'input str) -> Self {
         // the Token::lexer() method is provided by the Logos trait
-        Self { token_stream: Token::lexer(input).spanned() }
+        Self { token_stream: Token::lexer(input).spanned(), trivia: false }
+    }
+
+    /// A lossless lexer, which also emits whitespace and comments
+    ///
+    /// The tokens cover the entire input, so concatenating their source text yields the input again.
+    pub fn with_trivia(input: &'input str) -> Self {
+        Self { token_stream: Token::lexer(input).spanned(), trivia: true }
     }
 }
 
@@ -241,7 +300,13 @@ impl Iterator for Lexer<'_> {
     type Item = Spanned<Token, usize, LexicalError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.token_stream.next().map(|(token, span)| Ok((span.start, token?, span.end)))
+        loop {
+            let (token, span) = self.token_stream.next()?;
+            if !self.trivia && matches!(token, Ok(Token::Whitespace | Token::Comment(_))) {
+                continue;
+            }
+            return Some(token.map(|token| (span.start, token, span.end)));
+        }
     }
 }
 
@@ -390,6 +455,69 @@ mod lexer_tests {
         assert_eq!(lexer.next().unwrap().unwrap().1, Token::DocComment("/// hello".to_string()))
     }
 
+    #[test]
+    fn comment_trivia() {
+        let str = "data // comment\n/// doc";
+        let tokens: Vec<_> = Lexer::with_trivia(str)
+            .map(|token| token.unwrap())
+            .map(|(_, token, _)| token)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Data,
+                Token::Whitespace,
+                Token::Comment("// comment".to_string()),
+                Token::Whitespace,
+                Token::DocComment("/// doc".to_string()),
+            ]
+        )
+    }
+
+    #[test]
+    fn trivia_lossless() {
+        let str = "let x : Nat {\n    // comment\n    Z //\n}\n";
+        let source: String = Lexer::with_trivia(str)
+            .map(|token| token.unwrap())
+            .map(|(start, _, end)| &str[start..end])
+            .collect();
+        assert_eq!(source, str)
+    }
+
+    #[test]
+    fn block_comment_nested() {
+        let str = "/* outer /* inner */ still outer */ data";
+        let mut lexer = Lexer::new(str);
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::Data)
+    }
+
+    #[test]
+    fn block_comment_trivia() {
+        let str = "/**/ /*** banner ***/ /** doc */";
+        let tokens: Vec<_> = Lexer::with_trivia(str)
+            .map(|token| token.unwrap())
+            .map(|(_, token, _)| token)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Comment("/**/".to_string()),
+                Token::Whitespace,
+                Token::Comment("/*** banner ***/".to_string()),
+                Token::Whitespace,
+                Token::DocComment("/** doc */".to_string()),
+            ]
+        )
+    }
+
+    #[test]
+    fn block_comment_unterminated() {
+        let str = "data /* outer /* inner */";
+        let mut lexer = Lexer::new(str);
+        assert_eq!(lexer.next().unwrap().unwrap().1, Token::Data);
+        assert_eq!(lexer.next().unwrap(), Err(LexicalError::UnterminatedComment(5..25)))
+    }
+
     fn assert_eq_string_lit(str: &str, unescaped: &str) {
         let without_quotes = &str[1..str.len() - 1];
         let mut lexer = Lexer::new(str);
@@ -409,7 +537,7 @@ mod lexer_tests {
 
         assert_eq!(
             lexer.next().unwrap().unwrap().1,
-            Token::CharLit(CharLit { original: without_quotes.to_string(), unescaped: unescaped })
+            Token::CharLit(CharLit { original: without_quotes.to_string(), unescaped })
         )
     }
 
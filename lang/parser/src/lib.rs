@@ -3,7 +3,8 @@ mod grammar;
 pub mod lexer;
 mod result;
 
-use lexer::Lexer;
+use cst::trivia::{Comment, attach_comments};
+use lexer::{Lexer, Token};
 use url::Url;
 
 use grammar::cst::{ExpParser, ModuleContentsParser};
@@ -26,17 +27,28 @@ pub fn parse_exp(s: &str) -> Result<Box<cst::exp::Exp>, Vec<ParseError>> {
 }
 
 pub fn parse_module(uri: Url, s: &str) -> Result<cst::decls::Module, Vec<ParseError>> {
-    let lexer = Lexer::new(s);
+    // The comments are removed from the token stream, and attached to the CST after parsing.
+    let mut comments = Vec::new();
+    let lexer = Lexer::with_trivia(s).filter(|token| match token {
+        Ok((start, Token::Comment(text), end)) => {
+            comments.push(Comment { span: *start..*end, text: text.trim_end().to_owned() });
+            false
+        }
+        Ok((_, Token::Whitespace, _)) => false,
+        _ => true,
+    });
     let parser = ModuleContentsParser::new();
     let mut errors = Vec::new();
 
     // `parser.parse` only *returns* an error if error recovery failed (e.g. a lexing error).
     // Recovered errors are passed into the `errors` Vec.
     // For the moment, we have to catch all errors at parsing stage here.
-    let (use_decls, decls) = parser.parse(&mut errors, lexer).map_err(|e| vec![e.into()])?;
+    let (mut use_decls, mut decls) =
+        parser.parse(&mut errors, lexer).map_err(|e| vec![e.into()])?;
     if !errors.is_empty() {
         return Err(errors.into_iter().map(|e| e.error.into()).collect());
     }
 
+    attach_comments(s, &mut use_decls, &mut decls, comments);
     Ok(cst::decls::Module { uri, use_decls, decls })
 }
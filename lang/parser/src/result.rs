@@ -102,6 +102,13 @@ pub enum ParseError {
         span: SourceSpan,
     },
 
+    #[error("Unterminated block comment")]
+    #[diagnostic(code("P-012"), help("Every `/*` has to be closed by a matching `*/`"))]
+    UnterminatedComment {
+        #[label]
+        span: SourceSpan,
+    },
+
     #[error("An unexpected internal error occurred: {message}")]
     #[diagnostic(code("P-XXX"))]
     /// This error should not occur.
@@ -147,6 +154,7 @@ impl From<lalrpop_util::ParseError<usize, Token, LexicalError>> for ParseError {
                 LexicalError::MalformedUnicodeEscape(span) => ParseError::MalformedUnicodeEscape { span: span.into() },
                 LexicalError::InvalidUnicodeScalarValue(span) => ParseError::InvalidUnicodeScalarValue { span: span.into() },
                 LexicalError::InvalidHexNumber(span) => ParseError::InvalidHexNumber { span: span.into() },
+                LexicalError::UnterminatedComment(span) => ParseError::UnterminatedComment { span: span.into() },
             }
         }
     }
@@ -44,6 +44,12 @@ pub const LEFT_ARROW: &str = "<-";
 /// The keyword `infix`
 pub const INFIX: &str = "infix";
 
+/// The keyword `infixl`
+pub const INFIXL: &str = "infixl";
+
+/// The keyword `infixr`
+pub const INFIXR: &str = "infixr";
+
 /// The keyword `data`
 pub const DATA: &str = "data";
 
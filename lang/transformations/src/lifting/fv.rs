@@ -96,6 +96,9 @@ impl FV for Exp {
             Exp::Hole(hole) => hole.free_vars_closure(lvl_ctx, type_ctx),
             Exp::TypeUniv(_) => HashSet::default(),
             Exp::LocalMatch(local_match) => local_match.free_vars_closure(lvl_ctx, type_ctx),
+            Exp::CompiledMatch(compiled_match) => {
+                compiled_match.compiled.free_vars_closure(lvl_ctx, type_ctx)
+            }
             Exp::LocalLet(local_let) => local_let.free_vars_closure(lvl_ctx, type_ctx),
             Exp::DoBlock(do_block) => do_block.free_vars_closure(lvl_ctx, type_ctx),
             Exp::Literal(literal) => literal.free_vars_closure(lvl_ctx, type_ctx),
@@ -166,7 +169,7 @@ impl FV for TypCtor {
 
 impl FV for LocalLet {
     fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
-        let LocalLet { span: _, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span: _, comments: _, name, typ, bound, body, inferred_type: _ } = self;
         let mut fvs = typ.free_vars_closure(lvl_ctx, type_ctx);
         fvs.extend(bound.free_vars_closure(lvl_ctx, type_ctx));
         lvl_ctx.bind_iter([name.clone()].into_iter(), |ctx| {
@@ -186,14 +189,22 @@ impl FV for DoBlock {
 impl FV for DoStatements {
     fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
         match self {
-            DoStatements::Bind { span: _, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span: _, comments: _, name, bound, body, inferred_type: _ } => {
                 let mut fvs = bound.free_vars_closure(lvl_ctx, type_ctx);
                 lvl_ctx.bind_single(name.clone(), |ctx| {
                     fvs.extend(body.free_vars_closure(ctx, type_ctx));
                 });
                 fvs
             }
-            DoStatements::Let { span: _, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let {
+                span: _,
+                comments: _,
+                name,
+                typ,
+                bound,
+                body,
+                inferred_type: _,
+            } => {
                 let mut fvs = typ.free_vars_closure(lvl_ctx, type_ctx);
                 fvs.extend(bound.free_vars_closure(lvl_ctx, type_ctx));
                 lvl_ctx.bind_single(name.clone(), |ctx| {
@@ -201,7 +212,7 @@ impl FV for DoStatements {
                 });
                 fvs
             }
-            DoStatements::Return { span: _, exp, inferred_type: _ } => {
+            DoStatements::Return { span: _, comments: _, exp, inferred_type: _ } => {
                 exp.free_vars_closure(lvl_ctx, type_ctx)
             }
         }
@@ -268,7 +279,7 @@ impl FV for LocalMatch {
 
 impl FV for Case {
     fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
-        let Case { span: _, pattern, body } = self;
+        let Case { span: _, comments: _, pattern, body, from_wildcard: _ } = self;
         lvl_ctx.bind_iter(pattern.params.params.iter(), |ctx| body.free_vars_closure(ctx, type_ctx))
     }
 }
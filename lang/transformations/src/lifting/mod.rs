@@ -125,7 +125,7 @@ impl Lift for Data {
     type Target = Data;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Data { span, doc, name, attr, typ, ctors } = self;
+        let Data { span, doc, comments, name, attr, typ, ctors } = self;
         ctx.set_curr_decl(name.clone());
 
         let ctors = ctors.iter().map(|ctor| ctor.lift(ctx)).collect();
@@ -133,6 +133,7 @@ impl Lift for Data {
         Data {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             typ: Box::new(typ.lift_telescope(ctx, |_, params| params)),
@@ -145,7 +146,7 @@ impl Lift for Codata {
     type Target = Codata;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Codata { span, doc, name, attr, typ, dtors } = self;
+        let Codata { span, doc, comments, name, attr, typ, dtors } = self;
         ctx.set_curr_decl(name.clone());
 
         let dtors = dtors.iter().map(|dtor| dtor.lift(ctx)).collect();
@@ -153,6 +154,7 @@ impl Lift for Codata {
         Codata {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             typ: Box::new(typ.lift_telescope(ctx, |_, params| params)),
@@ -165,13 +167,15 @@ impl Lift for Ctor {
     type Target = Ctor;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Ctor { span, doc, name, params, typ } = self;
+        let Ctor { span, doc, comments, name, params, typ_comments, typ } = self;
 
         params.lift_telescope(ctx, |ctx, params| Ctor {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             params,
+            typ_comments: typ_comments.clone(),
             typ: typ.lift(ctx),
         })
     }
@@ -181,14 +185,24 @@ impl Lift for Dtor {
     type Target = Dtor;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Dtor { span, doc, name, params, self_param, ret_typ } = self;
+        let Dtor { span, doc, comments, name, params, self_param, ret_typ_comments, ret_typ } =
+            self;
 
         params.lift_telescope(ctx, |ctx, params| {
             let (self_param, ret_typ) = self_param.lift_telescope(ctx, |ctx, self_param| {
                 let ret_typ = ret_typ.lift(ctx);
                 (self_param, ret_typ)
             });
-            Dtor { span: *span, doc: doc.clone(), name: name.clone(), params, self_param, ret_typ }
+            Dtor {
+                span: *span,
+                doc: doc.clone(),
+                comments: comments.clone(),
+                name: name.clone(),
+                params,
+                self_param,
+                ret_typ_comments: ret_typ_comments.clone(),
+                ret_typ,
+            }
         })
     }
 }
@@ -197,7 +211,19 @@ impl Lift for Def {
     type Target = Def;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Def { span, doc, name, attr, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span,
+            doc,
+            comments,
+            name,
+            attr,
+            params,
+            self_param,
+            ret_typ_comments,
+            ret_typ,
+            cases,
+            source_cases,
+        } = self;
         ctx.set_curr_decl(name.clone());
 
         params.lift_telescope(ctx, |ctx, params| {
@@ -205,16 +231,24 @@ impl Lift for Def {
                 let ret_typ = ret_typ.lift(ctx);
                 (self_param, ret_typ)
             });
+            let n_decls = ctx.new_decls.len();
+            let cases = cases.lift(ctx);
+            // The source cases no longer match the cases if a match has been lifted out of them
+            let source_cases =
+                if ctx.new_decls.len() == n_decls { source_cases.clone() } else { None };
 
             Def {
                 span: *span,
                 doc: doc.clone(),
+                comments: comments.clone(),
                 name: name.clone(),
                 attr: attr.clone(),
                 params,
                 self_param,
+                ret_typ_comments: ret_typ_comments.clone(),
                 ret_typ,
-                cases: cases.lift(ctx),
+                cases,
+                source_cases,
             }
         })
     }
@@ -224,15 +258,17 @@ impl Lift for Codef {
     type Target = Codef;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Codef { span, doc, name, attr, params, typ, cases } = self;
+        let Codef { span, doc, comments, name, attr, params, typ_comments, typ, cases } = self;
         ctx.set_curr_decl(name.clone());
 
         params.lift_telescope(ctx, |ctx, params| Codef {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             params,
+            typ_comments: typ_comments.clone(),
             typ: typ.lift(ctx),
             cases: cases.lift(ctx),
         })
@@ -243,16 +279,20 @@ impl Lift for Let {
     type Target = Let;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Let { span, doc, name, attr, params, typ, body } = self;
+        let Let { span, doc, comments, name, attr, params, typ_comments, typ, body_comments, body } =
+            self;
         ctx.set_curr_decl(name.clone());
 
         params.lift_telescope(ctx, |ctx, params| Let {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             params,
+            typ_comments: typ_comments.clone(),
             typ: typ.lift(ctx),
+            body_comments: body_comments.clone(),
             body: body.lift(ctx),
         })
     }
@@ -262,15 +302,17 @@ impl Lift for Extern {
     type Target = Extern;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Extern { span, doc, name, attr, params, typ } = self;
+        let Extern { span, doc, comments, name, attr, params, typ_comments, typ } = self;
         ctx.set_curr_decl(name.clone());
 
         params.lift_telescope(ctx, |ctx, params| Extern {
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: attr.clone(),
             params,
+            typ_comments: typ_comments.clone(),
             typ: typ.lift(ctx),
         })
     }
@@ -280,10 +322,11 @@ impl Lift for Case {
     type Target = Case;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Case { span, pattern, body } = self;
+        let Case { span, comments, pattern, body, from_wildcard } = self;
 
         pattern.params.lift_telescope(ctx, |ctx, params| Case {
             span: *span,
+            comments: comments.clone(),
             pattern: Pattern {
                 span: None,
                 is_copattern: pattern.is_copattern,
@@ -291,6 +334,7 @@ impl Lift for Case {
                 params,
             },
             body: body.lift(ctx),
+            from_wildcard: *from_wildcard,
         })
     }
 }
@@ -321,6 +365,7 @@ impl Lift for Exp {
             Exp::TypeUniv(e) => e.lift(ctx),
             Exp::Hole(e) => e.lift(ctx).into(),
             Exp::LocalMatch(e) => e.lift(ctx),
+            Exp::CompiledMatch(e) => e.lift(ctx),
             Exp::LocalComatch(e) => e.lift(ctx),
             Exp::LocalLet(e) => e.lift(ctx),
             Exp::DoBlock(e) => e.lift(ctx),
@@ -430,6 +475,20 @@ impl Lift for Hole {
     }
 }
 
+impl Lift for CompiledMatch {
+    type Target = Exp;
+
+    fn lift(&self, ctx: &mut Ctx) -> Self::Target {
+        let n_decls = ctx.new_decls.len();
+        let compiled = self.compiled.lift(ctx);
+        // The match as it is written can no longer be printed if a match has been lifted out of it
+        if ctx.new_decls.len() != n_decls {
+            return *compiled;
+        }
+        CompiledMatch { compiled, ..self.clone() }.into()
+    }
+}
+
 impl Lift for LocalMatch {
     type Target = Exp;
 
@@ -488,10 +547,11 @@ impl Lift for LocalLet {
     type Target = Exp;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let LocalLet { span, name, typ, bound, body, inferred_type: _ } = self;
+        let LocalLet { span, comments, name, typ, bound, body, inferred_type: _ } = self;
 
         Exp::LocalLet(LocalLet {
             span: *span,
+            comments: comments.clone(),
             name: name.clone(),
             typ: typ.lift(ctx),
             bound: bound.lift(ctx),
@@ -516,18 +576,20 @@ impl Lift for DoStatements {
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
         match self {
-            DoStatements::Bind { span, name, bound, body, inferred_type: _ } => {
+            DoStatements::Bind { span, comments, name, bound, body, inferred_type: _ } => {
                 DoStatements::Bind {
                     span: *span,
+                    comments: comments.clone(),
                     name: name.clone(),
                     bound: bound.lift(ctx),
                     body: ctx.bind_single(name.clone(), |ctx| body.lift(ctx)),
                     inferred_type: None,
                 }
             }
-            DoStatements::Let { span, name, typ, bound, body, inferred_type: _ } => {
+            DoStatements::Let { span, comments, name, typ, bound, body, inferred_type: _ } => {
                 DoStatements::Let {
                     span: *span,
+                    comments: comments.clone(),
                     name: name.clone(),
                     typ: typ.lift(ctx),
                     bound: bound.lift(ctx),
@@ -535,8 +597,13 @@ impl Lift for DoStatements {
                     inferred_type: None,
                 }
             }
-            DoStatements::Return { span, exp, inferred_type: _ } => {
-                DoStatements::Return { span: *span, exp: exp.lift(ctx), inferred_type: None }
+            DoStatements::Return { span, comments, exp, inferred_type: _ } => {
+                DoStatements::Return {
+                    span: *span,
+                    comments: comments.clone(),
+                    exp: exp.lift(ctx),
+                    inferred_type: None,
+                }
             }
         }
     }
@@ -614,12 +681,15 @@ impl Lift for Arg {
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
         match self {
-            Arg::UnnamedArg { arg, erased } => {
-                Arg::UnnamedArg { arg: arg.lift(ctx), erased: *erased }
-            }
-            Arg::NamedArg { name, arg, erased } => {
-                Arg::NamedArg { name: name.clone(), arg: arg.lift(ctx), erased: *erased }
+            Arg::UnnamedArg { arg, erased, comments } => {
+                Arg::UnnamedArg { arg: arg.lift(ctx), erased: *erased, comments: comments.clone() }
             }
+            Arg::NamedArg { name, arg, erased, comments } => Arg::NamedArg {
+                name: name.clone(),
+                arg: arg.lift(ctx),
+                erased: *erased,
+                comments: comments.clone(),
+            },
             Arg::InsertedImplicitArg { hole, erased } => {
                 Arg::InsertedImplicitArg { hole: hole.lift(ctx), erased: *erased }
             }
@@ -631,9 +701,15 @@ impl Lift for Param {
     type Target = Param;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Param { implicit, name, typ, erased } = self;
+        let Param { implicit, name, typ, erased, comments } = self;
 
-        Param { implicit: *implicit, name: name.clone(), typ: typ.lift(ctx), erased: *erased }
+        Param {
+            implicit: *implicit,
+            name: name.clone(),
+            typ: typ.lift(ctx),
+            erased: *erased,
+            comments: comments.clone(),
+        }
     }
 }
 
@@ -736,6 +812,7 @@ impl Ctx {
         let def = Def {
             span: None,
             doc: None,
+            comments: Comments::default(),
             name: name.clone(),
             attr: Attributes::default(),
             params: telescope,
@@ -747,8 +824,10 @@ impl Ctx {
                     .unwrap_or(VarBind::Wildcard { span: None }),
                 typ: self_typ,
             },
+            ret_typ_comments: Comments::default(),
             ret_typ: def_ret_typ,
             cases,
+            source_cases: None,
         };
 
         self.new_decls.push(Decl::Def(def));
@@ -810,9 +889,11 @@ impl Ctx {
         let codef = Codef {
             span: None,
             doc: None,
+            comments: Comments::default(),
             name: name.clone(),
             attr: Attributes::default(),
             params: telescope,
+            typ_comments: Comments::default(),
             typ,
             cases,
         };
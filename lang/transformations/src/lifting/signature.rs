@@ -56,6 +56,7 @@ pub fn lifted_signature(fvs: HashSet<FreeVar>, base_ctx: &LevelCtx) -> LiftedSig
             name: VarBind::from_string(&name),
             typ: typ.clone(),
             erased: false,
+            comments: Comments::default(),
         };
         let arg = Arg::UnnamedArg {
             arg: Box::new(Exp::Variable(Variable {
@@ -66,6 +67,7 @@ pub fn lifted_signature(fvs: HashSet<FreeVar>, base_ctx: &LevelCtx) -> LiftedSig
                 erased: false,
             })),
             erased: false,
+            comments: Default::default(),
         };
         args.push(arg);
         params.push(param);
@@ -2,7 +2,7 @@ use url::Url;
 
 use polarity_lang_ast::ctx::{BindContext, LevelCtx};
 use polarity_lang_ast::{self, HashMap, IdBound, SwapWithCtx};
-use polarity_lang_ast::{Attributes, DocComment};
+use polarity_lang_ast::{Attributes, Comments, DocComment};
 use polarity_lang_miette_util::codespan::Span;
 
 use crate::result::XfuncError;
@@ -18,11 +18,14 @@ pub struct XData {
     pub repr: Repr,
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
+    pub comments: Comments,
     pub name: polarity_lang_ast::IdBind,
     pub typ: Box<polarity_lang_ast::Telescope>,
     pub ctors: HashMap<String, polarity_lang_ast::Ctor>,
     pub dtors: HashMap<String, polarity_lang_ast::Dtor>,
     pub exprs: HashMap<Key, Option<Box<polarity_lang_ast::Exp>>>,
+    /// The comments of the case from which a matrix cell was built
+    pub case_comments: HashMap<Key, Comments>,
 }
 
 /// A key points to a matrix cell
@@ -82,17 +85,19 @@ impl BuildMatrix for polarity_lang_ast::Module {
 
 impl BuildMatrix for polarity_lang_ast::Data {
     fn build_matrix(&self, out: &mut Prg) -> Result<(), XfuncError> {
-        let polarity_lang_ast::Data { span, doc, name, attr: _, typ, ctors } = self;
+        let polarity_lang_ast::Data { span, doc, comments, name, attr: _, typ, ctors } = self;
 
         let mut xdata = XData {
             repr: Repr::Data,
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             typ: typ.clone(),
             ctors: HashMap::default(),
             dtors: HashMap::default(),
             exprs: HashMap::default(),
+            case_comments: HashMap::default(),
         };
         for ctor in ctors {
             xdata.ctors.insert(ctor.name.id.clone(), ctor.clone());
@@ -104,17 +109,19 @@ impl BuildMatrix for polarity_lang_ast::Data {
 }
 impl BuildMatrix for polarity_lang_ast::Codata {
     fn build_matrix(&self, out: &mut Prg) -> Result<(), XfuncError> {
-        let polarity_lang_ast::Codata { span, doc, name, attr: _, typ, dtors } = self;
+        let polarity_lang_ast::Codata { span, doc, comments, name, attr: _, typ, dtors } = self;
 
         let mut xdata = XData {
             repr: Repr::Codata,
             span: *span,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             typ: typ.clone(),
             ctors: HashMap::default(),
             dtors: HashMap::default(),
             exprs: HashMap::default(),
+            case_comments: HashMap::default(),
         };
 
         for dtor in dtors {
@@ -136,8 +143,9 @@ impl BuildMatrix for polarity_lang_ast::Def {
         let cases = &self.cases;
 
         for case in cases {
-            let polarity_lang_ast::Case { pattern, body, .. } = case;
+            let polarity_lang_ast::Case { comments, pattern, body, .. } = case;
             let key = Key { dtor: self.name.id.clone(), ctor: pattern.name.id.clone() };
+            xdata.case_comments.insert(key.clone(), comments.clone());
             xdata.exprs.insert(key, body.clone());
         }
         Ok(())
@@ -156,8 +164,9 @@ impl BuildMatrix for polarity_lang_ast::Codef {
         let cases = &self.cases;
 
         for case in cases {
-            let polarity_lang_ast::Case { pattern, body, .. } = case;
+            let polarity_lang_ast::Case { comments, pattern, body, .. } = case;
             let key = Key { ctor: self.name.id.clone(), dtor: pattern.name.id.clone() };
+            xdata.case_comments.insert(key.clone(), comments.clone());
             // Swap binding order to the order imposed by the matrix representation
             let body = body.as_ref().map(|body| {
                 let mut ctx = LevelCtx::empty();
@@ -176,11 +185,12 @@ impl BuildMatrix for polarity_lang_ast::Codef {
 
 impl XData {
     pub fn as_data(&self, uri: &Url) -> (polarity_lang_ast::Data, Vec<polarity_lang_ast::Def>) {
-        let XData { name, doc, typ, ctors, dtors, exprs, .. } = self;
+        let XData { name, doc, comments, typ, ctors, dtors, exprs, case_comments, .. } = self;
 
         let data = polarity_lang_ast::Data {
             span: None,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: Attributes::default(),
             typ: typ.clone(),
@@ -197,6 +207,7 @@ impl XData {
                         let body = exprs.get(&key).cloned();
                         body.map(|body| polarity_lang_ast::Case {
                             span: None,
+                            comments: case_comments.get(&key).cloned().unwrap_or_default(),
                             pattern: polarity_lang_ast::Pattern {
                                 span: None,
                                 is_copattern: false,
@@ -208,6 +219,7 @@ impl XData {
                                 params: ctor.params.instantiate(),
                             },
                             body,
+                            from_wildcard: false,
                         })
                     })
                     .collect();
@@ -215,12 +227,15 @@ impl XData {
                 polarity_lang_ast::Def {
                     span: None,
                     doc: dtor.doc.clone(),
+                    comments: dtor.comments.clone(),
                     name: dtor.name.clone(),
                     attr: Attributes::default(),
                     params: dtor.params.clone(),
                     self_param: dtor.self_param.clone(),
+                    ret_typ_comments: dtor.ret_typ_comments.clone(),
                     ret_typ: dtor.ret_typ.clone(),
                     cases,
+                    source_cases: None,
                 }
             })
             .collect();
@@ -232,11 +247,12 @@ impl XData {
         &self,
         uri: &Url,
     ) -> (polarity_lang_ast::Codata, Vec<polarity_lang_ast::Codef>) {
-        let XData { name, doc, typ, ctors, dtors, exprs, .. } = self;
+        let XData { name, doc, comments, typ, ctors, dtors, exprs, case_comments, .. } = self;
 
         let codata = polarity_lang_ast::Codata {
             span: None,
             doc: doc.clone(),
+            comments: comments.clone(),
             name: name.clone(),
             attr: Attributes::default(),
             typ: typ.clone(),
@@ -262,6 +278,7 @@ impl XData {
                         });
                         body.map(|body| polarity_lang_ast::Case {
                             span: None,
+                            comments: case_comments.get(&key).cloned().unwrap_or_default(),
                             pattern: polarity_lang_ast::Pattern {
                                 span: None,
                                 is_copattern: true,
@@ -273,6 +290,7 @@ impl XData {
                                 params: dtor.params.instantiate(),
                             },
                             body,
+                            from_wildcard: false,
                         })
                     })
                     .collect();
@@ -280,9 +298,11 @@ impl XData {
                 polarity_lang_ast::Codef {
                     span: None,
                     doc: ctor.doc.clone(),
+                    comments: ctor.comments.clone(),
                     name: ctor.name.clone(),
                     attr: Attributes::default(),
                     params: ctor.params.clone(),
+                    typ_comments: ctor.typ_comments.clone(),
                     typ: ctor.typ.clone(),
                     cases,
                 }
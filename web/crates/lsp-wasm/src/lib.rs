@@ -3,7 +3,7 @@
 
 use futures::stream::TryStreamExt;
 use polarity_lang_driver::{FileSource, InMemorySource};
-use tower_lsp_server::{LspService, Server};
+use tower_lsp_server::Server;
 use wasm_bindgen::{JsCast, prelude::*};
 use wasm_bindgen_futures::stream::JsStream;
 
@@ -69,7 +69,7 @@ pub async fn serve(config: ServerConfig) -> Result<(), JsValue> {
         polarity_lang_lsp_server::Server::with_database(client, database)
     };
 
-    let (service, messages) = LspService::new(create_server);
+    let (service, messages) = polarity_lang_lsp_server::service(create_server);
     Server::new(input, output, messages).serve(service).await;
 
     Ok(())